@@ -2,14 +2,14 @@
 extern crate criterion;
 extern crate generational_arena;
 
-use criterion::{Criterion, ParameterizedBenchmark, Throughput};
-use generational_arena::{Arena, Index};
+use criterion::{BenchmarkId, Criterion, Throughput};
+use generational_arena::{Arena, Index, ReusePolicy, SoaArena};
 
 #[derive(Default)]
-struct Small(usize);
+struct Small(#[allow(dead_code)] usize);
 
 #[derive(Default)]
-struct Big([usize; 32]);
+struct Big(#[allow(dead_code)] [usize; 32]);
 
 fn insert<T: Default>(n: usize) {
     let mut arena = Arena::<T>::new();
@@ -25,98 +25,237 @@ fn lookup<T>(arena: &Arena<T>, idx: Index, n: usize) {
     }
 }
 
+fn get_many<T>(arena: &Arena<T>, idx: Index, n: usize) {
+    for _ in 0..n {
+        criterion::black_box(arena.get(criterion::black_box(idx)));
+    }
+}
+
+fn contains_many<T>(arena: &Arena<T>, idx: Index, n: usize) {
+    for _ in 0..n {
+        criterion::black_box(arena.contains(criterion::black_box(idx)));
+    }
+}
+
 fn collect<T>(arena: &Arena<T>, n: usize) {
     for _ in 0..n {
         criterion::black_box(arena.iter().collect::<Vec<_>>());
     }
 }
 
+// Remove `batch` elements scattered across the arena, then insert as many,
+// so every insertion reuses a slot far from the last one.
+fn churn<T: Default>(arena: &mut Arena<T>, indices: &mut [Index], batch: usize, round: &mut usize) {
+    let n = indices.len();
+    let slots: Vec<_> = (0..batch).map(|k| (*round + k * 7919) % n).collect();
+    for &slot in &slots {
+        criterion::black_box(arena.remove(indices[slot]));
+    }
+    for &slot in &slots {
+        indices[slot] = arena.insert(Default::default());
+    }
+    *round += 1;
+}
+
+fn sizes() -> Vec<usize> {
+    (1..3).map(|n| n * 100).collect()
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench(
-        "insert",
-        ParameterizedBenchmark::new(
-            "insert-small",
-            |b, n| b.iter(|| insert::<Small>(*n)),
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "insert",
-        ParameterizedBenchmark::new(
-            "insert-big",
-            |b, n| b.iter(|| insert::<Big>(*n)),
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "lookup",
-        ParameterizedBenchmark::new(
-            "lookup-small",
-            |b, n| {
-                let mut small_arena = Arena::<Small>::new();
-                for _ in 0..1024 {
-                    small_arena.insert(Default::default());
-                }
-                let small_idx = small_arena.iter().map(|pair| pair.0).next().unwrap();
-                b.iter(|| lookup(&small_arena, small_idx, *n))
-            },
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "lookup",
-        ParameterizedBenchmark::new(
-            "lookup-big",
-            |b, n| {
-                let mut big_arena = Arena::<Big>::new();
-                for _ in 0..1024 {
-                    big_arena.insert(Default::default());
-                }
-                let big_idx = big_arena.iter().map(|pair| pair.0).next().unwrap();
-                b.iter(|| lookup(&big_arena, big_idx, *n))
-            },
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "collect",
-        ParameterizedBenchmark::new(
-            "collect-small",
-            |b, n| {
-                let mut small_arena = Arena::<Small>::new();
-                for _ in 0..1024 {
-                    small_arena.insert(Default::default());
-                }
-                b.iter(|| collect(&small_arena, *n))
-            },
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
-
-    c.bench(
-        "collect",
-        ParameterizedBenchmark::new(
-            "collect-big",
-            |b, n| {
-                let mut big_arena = Arena::<Big>::new();
-                for _ in 0..1024 {
-                    big_arena.insert(Default::default());
+    let mut group = c.benchmark_group("insert");
+    for n in sizes() {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("insert-small", n), &n, |b, n| {
+            b.iter(|| insert::<Small>(*n))
+        });
+        group.bench_with_input(BenchmarkId::new("insert-big", n), &n, |b, n| {
+            b.iter(|| insert::<Big>(*n))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("extend");
+    for n in [1_000, 100_000] {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("extend-small", n), &n, |b, n| {
+            b.iter(|| {
+                let mut arena = Arena::new();
+                arena.extend((0..*n).map(Small));
+                criterion::black_box(arena)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("insert-loop-small", n), &n, |b, n| {
+            b.iter(|| {
+                let mut arena = Arena::new();
+                for i in 0..*n {
+                    arena.insert(Small(i));
                 }
-                b.iter(|| collect(&big_arena, *n))
-            },
-            (1..3).map(|n| n * 100).collect::<Vec<usize>>(),
-        )
-        .throughput(|n| Throughput::Elements(*n as u64)),
-    );
+                criterion::black_box(arena)
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("with_capacity");
+    for n in [1_000, 1_000_000, 10_000_000] {
+        group.bench_with_input(BenchmarkId::new("with-capacity-small", n), &n, |b, n| {
+            b.iter(|| {
+                let mut arena = Arena::<Small>::with_capacity(*n);
+                criterion::black_box(arena.insert(Default::default()));
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("retain");
+    for n in [1_000, 100_000] {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("retain-half", n), &n, |b, n| {
+            let mut arena = Arena::with_capacity(*n);
+            arena.extend(0..*n);
+            b.iter_batched_ref(
+                || arena.clone(),
+                |arena| arena.retain(|_, x| *x % 2 == 0),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("lookup");
+    for n in sizes() {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("lookup-small", n), &n, |b, n| {
+            let mut small_arena = Arena::<Small>::new();
+            for _ in 0..1024 {
+                small_arena.insert(Default::default());
+            }
+            let small_idx = small_arena.iter().map(|pair| pair.0).next().unwrap();
+            b.iter(|| lookup(&small_arena, small_idx, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("lookup-big", n), &n, |b, n| {
+            let mut big_arena = Arena::<Big>::new();
+            for _ in 0..1024 {
+                big_arena.insert(Default::default());
+            }
+            let big_idx = big_arena.iter().map(|pair| pair.0).next().unwrap();
+            b.iter(|| lookup(&big_arena, big_idx, *n))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("get");
+    for n in sizes() {
+        group.throughput(Throughput::Elements(n as u64));
+        let mut arena = Arena::<Small>::new();
+        let indices: Vec<_> = (0..1024).map(|i| arena.insert(Small(i))).collect();
+        let hit = indices[512];
+        let stale = indices[256];
+        arena.remove(stale);
+        arena.insert(Small(0));
+        let out_of_bounds = Index::from_raw_parts(4096, 0);
+
+        group.bench_with_input(BenchmarkId::new("get-hit", n), &n, |b, n| {
+            b.iter(|| get_many(&arena, hit, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("get-stale", n), &n, |b, n| {
+            b.iter(|| get_many(&arena, stale, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("get-out-of-bounds", n), &n, |b, n| {
+            b.iter(|| get_many(&arena, out_of_bounds, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("contains-hit", n), &n, |b, n| {
+            b.iter(|| contains_many(&arena, hit, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("contains-stale", n), &n, |b, n| {
+            b.iter(|| contains_many(&arena, stale, *n))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("collect");
+    for n in sizes() {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("collect-small", n), &n, |b, n| {
+            let mut small_arena = Arena::<Small>::new();
+            for _ in 0..1024 {
+                small_arena.insert(Default::default());
+            }
+            b.iter(|| collect(&small_arena, *n))
+        });
+        group.bench_with_input(BenchmarkId::new("collect-big", n), &n, |b, n| {
+            let mut big_arena = Arena::<Big>::new();
+            for _ in 0..1024 {
+                big_arena.insert(Default::default());
+            }
+            b.iter(|| collect(&big_arena, *n))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("churn");
+    for n in [1_000, 100_000] {
+        let batch = 64;
+        group.throughput(Throughput::Elements(batch as u64));
+        for (name, reuse) in [
+            ("lifo", ReusePolicy::Lifo),
+            ("fifo", ReusePolicy::Fifo),
+            ("stack", ReusePolicy::Stack),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("churn-{}", name), n),
+                &n,
+                |b, &n| {
+                    let mut arena = Arena::<Big>::builder().capacity(n).reuse(reuse).build();
+                    let mut indices: Vec<_> =
+                        (0..n).map(|_| arena.insert(Default::default())).collect();
+                    let mut round = 0;
+                    b.iter(|| churn(&mut arena, &mut indices, batch, &mut round))
+                },
+            );
+        }
+    }
+    // Slot 0 stays live while the top slot is removed and reinserted over a
+    // long run of free slots, which each remove skips a word at a time.
+    for n in [1_000, 1_000_000] {
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(BenchmarkId::new("churn-far-end", n), &n, |b, &n| {
+            let mut arena = Arena::<usize>::with_capacity(n);
+            let indices: Vec<_> = (0..n).map(|i| arena.insert(i)).collect();
+            for &idx in &indices[1..n - 1] {
+                arena.remove(idx);
+            }
+            let mut top = indices[n - 1];
+            b.iter(|| {
+                let value = arena.remove(top).unwrap();
+                top = arena.insert(criterion::black_box(value));
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("sum");
+    for n in [1_000, 100_000] {
+        group.throughput(Throughput::Elements(n as u64));
+        let mut arena = Arena::with_capacity(n);
+        let mut soa_arena = SoaArena::with_capacity(n);
+        for i in 0..n {
+            let a = arena.insert(i);
+            let b = soa_arena.insert(i);
+            // Leave every fourth slot free, as in an arena with some churn.
+            if i % 4 == 0 {
+                arena.remove(a);
+                soa_arena.remove(b);
+            }
+        }
+        group.bench_with_input(BenchmarkId::new("sum-arena", n), &n, |b, _| {
+            b.iter(|| arena.iter().map(|(_, v)| *v).sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("sum-soa-arena", n), &n, |b, _| {
+            b.iter(|| soa_arena.values().sum::<usize>())
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,173 @@
+use super::{Arena, Index};
+use core::cell::{Ref, RefCell, RefMut};
+
+/// An arena behind a `RefCell`, for sharing one arena between many parts of
+/// a program that each only hold a shared reference to it.
+///
+/// A common pattern is a world that owns an arena of entities, with several
+/// systems that each need to look up and modify entities through a shared
+/// `&World`. `ArenaCell` wraps the arena in a `RefCell` and offers guarded
+/// access to single elements with `borrow_get` and `borrow_get_mut`, and to
+/// the whole arena with `borrow` and `borrow_mut`.
+///
+/// Borrows are tracked for the arena as a whole, not per element: while any
+/// guard returned by `borrow_get_mut` or `borrow_mut` is alive, every other
+/// borrow panics, even of a different element. Keep guards short-lived, or
+/// use `try_borrow_get` and `try_borrow_get_mut` to check first.
+///
+/// Like `RefCell`, an `ArenaCell` is `Send` if `T` is, but never `Sync`.
+/// Share an arena between threads with a `Mutex` or `RwLock` instead.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::ArenaCell;
+///
+/// let entities = ArenaCell::new();
+/// let player = entities.insert(100);
+/// let enemy = entities.insert(50);
+///
+/// // Two systems reading at once is fine.
+/// let hp = entities.borrow_get(player).unwrap();
+/// assert_eq!(*hp + *entities.borrow_get(enemy).unwrap(), 150);
+/// drop(hp);
+///
+/// // Writing needs every other borrow to have ended.
+/// *entities.borrow_get_mut(enemy).unwrap() -= 20;
+/// assert_eq!(*entities.borrow_get(enemy).unwrap(), 30);
+/// ```
+#[derive(Debug, Default)]
+pub struct ArenaCell<T> {
+    arena: RefCell<Arena<T>>,
+}
+
+impl<T> From<Arena<T>> for ArenaCell<T> {
+    fn from(arena: Arena<T>) -> ArenaCell<T> {
+        ArenaCell {
+            arena: RefCell::new(arena),
+        }
+    }
+}
+
+impl<T> ArenaCell<T> {
+    /// Constructs a new, empty `ArenaCell`.
+    pub fn new() -> ArenaCell<T> {
+        ArenaCell::from(Arena::new())
+    }
+
+    /// Constructs a new, empty `ArenaCell` with the specified capacity.
+    pub fn with_capacity(n: usize) -> ArenaCell<T> {
+        ArenaCell::from(Arena::with_capacity(n))
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently borrowed.
+    pub fn insert(&self, value: T) -> Index {
+        self.arena.borrow_mut().insert(value)
+    }
+
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently borrowed.
+    pub fn remove(&self, i: Index) -> Option<T> {
+        self.arena.borrow_mut().remove(i)
+    }
+
+    /// Is the element at index `i` in the arena?
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently mutably borrowed.
+    pub fn contains(&self, i: Index) -> bool {
+        self.arena.borrow().contains(i)
+    }
+
+    /// Get the number of elements in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently mutably borrowed.
+    pub fn len(&self) -> usize {
+        self.arena.borrow().len()
+    }
+
+    /// Returns true if the arena contains no elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently mutably borrowed.
+    pub fn is_empty(&self) -> bool {
+        self.arena.borrow().is_empty()
+    }
+
+    /// Get a guarded shared reference to the element at index `i` if it is
+    /// in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently mutably borrowed.
+    pub fn borrow_get(&self, i: Index) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.arena.borrow(), |arena| arena.get(i)).ok()
+    }
+
+    /// Get a guarded exclusive reference to the element at index `i` if it
+    /// is in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently borrowed.
+    pub fn borrow_get_mut(&self, i: Index) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.arena.borrow_mut(), |arena| arena.get_mut(i)).ok()
+    }
+
+    /// Like `borrow_get`, but returns `Err(BorrowError)` instead of
+    /// panicking if the arena is currently mutably borrowed.
+    pub fn try_borrow_get(&self, i: Index) -> Result<Option<Ref<'_, T>>, core::cell::BorrowError> {
+        let arena = self.arena.try_borrow()?;
+        Ok(Ref::filter_map(arena, |arena| arena.get(i)).ok())
+    }
+
+    /// Like `borrow_get_mut`, but returns `Err(BorrowMutError)` instead of
+    /// panicking if the arena is currently borrowed.
+    pub fn try_borrow_get_mut(
+        &self,
+        i: Index,
+    ) -> Result<Option<RefMut<'_, T>>, core::cell::BorrowMutError> {
+        let arena = self.arena.try_borrow_mut()?;
+        Ok(RefMut::filter_map(arena, |arena| arena.get_mut(i)).ok())
+    }
+
+    /// Borrow the whole arena, e.g. to iterate over it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, Arena<T>> {
+        self.arena.borrow()
+    }
+
+    /// Mutably borrow the whole arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is currently borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, Arena<T>> {
+        self.arena.borrow_mut()
+    }
+
+    /// Get an exclusive reference to the arena. No runtime check is needed,
+    /// as `&mut self` guarantees there are no outstanding borrows.
+    pub fn get_mut(&mut self) -> &mut Arena<T> {
+        self.arena.get_mut()
+    }
+
+    /// Unwrap the arena.
+    pub fn into_inner(self) -> Arena<T> {
+        self.arena.into_inner()
+    }
+}
@@ -0,0 +1,113 @@
+//! Arena identity tags, used to catch an `Index` being used with an arena
+//! other than the one that created it.
+//!
+//! With the `arena-id` feature enabled, every arena draws a unique `ArenaId`
+//! when it is created and stamps it into each `Index` it hands out. Lookups
+//! then panic if given an `Index` stamped by a different arena, rather than
+//! silently returning whatever element happens to live at the same slot and
+//! generation. Without the feature, `ArenaId` is zero-sized and every check
+//! compiles away.
+//!
+//! The tag never takes part in comparing or hashing indices, so turning the
+//! feature on does not change which indices are equal to each other.
+
+use core::cmp;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "arena-id")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ArenaId(usize);
+
+#[cfg(feature = "arena-id")]
+impl ArenaId {
+    /// The id of indices that were not created by an arena, e.g. those built
+    /// with `Index::from_raw_parts` or deserialized, and of deserialized
+    /// arenas. Untagged indices are accepted by every arena, and untagged
+    /// arenas accept every index.
+    pub(crate) const UNTAGGED: ArenaId = ArenaId(0);
+
+    /// The id of an arena that has not drawn one yet, because it was created
+    /// by `Arena::new`, which is `const`. It is replaced with a fresh id
+    /// before the arena hands out any index. Until then the arena holds no
+    /// elements, so, like an untagged arena, it accepts every index.
+    pub(crate) const PENDING: ArenaId = ArenaId(usize::MAX);
+
+    /// Draw an id that no other arena in this process has.
+    pub(crate) fn fresh() -> ArenaId {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+        ArenaId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Draw a fresh id if this one is `PENDING`.
+    pub(crate) fn or_fresh(self) -> ArenaId {
+        if self.0 == ArenaId::PENDING.0 {
+            ArenaId::fresh()
+        } else {
+            self
+        }
+    }
+
+    /// Could an index tagged with `self` have come from an arena tagged with
+    /// `arena`?
+    #[inline]
+    pub(crate) fn matches(self, arena: ArenaId) -> bool {
+        self.0 == ArenaId::UNTAGGED.0
+            || arena.0 == ArenaId::UNTAGGED.0
+            || arena.0 == ArenaId::PENDING.0
+            || self.0 == arena.0
+    }
+}
+
+#[cfg(not(feature = "arena-id"))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ArenaId;
+
+#[cfg(not(feature = "arena-id"))]
+impl ArenaId {
+    pub(crate) const UNTAGGED: ArenaId = ArenaId;
+    pub(crate) const PENDING: ArenaId = ArenaId;
+
+    #[inline]
+    pub(crate) fn fresh() -> ArenaId {
+        ArenaId
+    }
+
+    #[inline]
+    pub(crate) fn or_fresh(self) -> ArenaId {
+        self
+    }
+
+    #[inline]
+    pub(crate) fn matches(self, _arena: ArenaId) -> bool {
+        true
+    }
+}
+
+impl PartialEq for ArenaId {
+    #[inline]
+    fn eq(&self, _other: &ArenaId) -> bool {
+        true
+    }
+}
+
+impl Eq for ArenaId {}
+
+impl PartialOrd for ArenaId {
+    #[inline]
+    fn partial_cmp(&self, other: &ArenaId) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArenaId {
+    #[inline]
+    fn cmp(&self, _other: &ArenaId) -> cmp::Ordering {
+        cmp::Ordering::Equal
+    }
+}
+
+impl Hash for ArenaId {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
@@ -0,0 +1,87 @@
+use super::{Arena, Vec};
+
+/// A pool of empty arenas whose allocations are reused.
+///
+/// Code that builds and throws away many short-lived arenas, such as a
+/// scratch graph per frame, can `acquire` an arena from the pool and
+/// `release` it back when done instead of dropping it. Released arenas are
+/// cleared but keep their allocations, so a later `acquire` gets one that
+/// already has room, without allocating.
+///
+/// Clearing an arena advances its generation, so indices into its old
+/// elements never refer to elements inserted after it is acquired again.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::ArenaPool;
+///
+/// let mut pool = ArenaPool::new();
+///
+/// let mut scratch = pool.acquire();
+/// let stale = scratch.insert(1);
+/// scratch.extend(0..99);
+/// let capacity = scratch.capacity();
+/// pool.release(scratch);
+///
+/// // The next frame reuses the same allocation.
+/// let scratch = pool.acquire();
+/// assert!(scratch.is_empty());
+/// assert_eq!(scratch.capacity(), capacity);
+/// assert!(!scratch.contains(stale));
+/// ```
+#[derive(Debug)]
+pub struct ArenaPool<T> {
+    idle: Vec<Arena<T>>,
+}
+
+impl<T> Default for ArenaPool<T> {
+    fn default() -> ArenaPool<T> {
+        ArenaPool::new()
+    }
+}
+
+impl<T> ArenaPool<T> {
+    /// Constructs a new, empty `ArenaPool`.
+    pub fn new() -> ArenaPool<T> {
+        ArenaPool { idle: Vec::new() }
+    }
+
+    /// Get an empty arena, reusing a released one if there is any.
+    ///
+    /// Released arenas are reused most recently released first. If the pool
+    /// has none, a new arena is created.
+    pub fn acquire(&mut self) -> Arena<T> {
+        self.idle.pop().unwrap_or_default()
+    }
+
+    /// Get an empty arena with room for at least `n` elements, reusing a
+    /// released one if there is any.
+    pub fn acquire_with_capacity(&mut self, n: usize) -> Arena<T> {
+        match self.idle.pop() {
+            Some(mut arena) => {
+                arena.reserve(n);
+                arena
+            }
+            None => Arena::with_capacity(n),
+        }
+    }
+
+    /// Give `arena` back to the pool, to be reused by a later `acquire`.
+    ///
+    /// The arena's elements are dropped, but its allocation is kept.
+    pub fn release(&mut self, mut arena: Arena<T>) {
+        arena.clear();
+        self.idle.push(arena);
+    }
+
+    /// Get the number of released arenas waiting to be reused.
+    pub fn idle(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Drop every released arena, freeing their allocations.
+    pub fn clear(&mut self) {
+        self.idle.clear();
+    }
+}
@@ -0,0 +1,193 @@
+use super::{Arena, Index, Vec};
+use core::fmt;
+
+/// A queued mutation of an arena, applied by `Arena::apply_batch` or
+/// `Arena::try_apply_batch`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArenaOp<T> {
+    /// Insert a value.
+    Insert(T),
+    /// Remove the element at an index.
+    Remove(Index),
+    /// Replace the value of the element at an index, keeping its index.
+    Update(Index, T),
+}
+
+impl<T> ArenaOp<T> {
+    /// The index this operation targets, if it targets an existing element.
+    fn target(&self) -> Option<Index> {
+        match *self {
+            ArenaOp::Insert(_) => None,
+            ArenaOp::Remove(i) | ArenaOp::Update(i, _) => Some(i),
+        }
+    }
+}
+
+/// An error returned by `Arena::try_apply_batch` when an operation in the
+/// batch targets an element that will not be in the arena when the
+/// operation is reached.
+///
+/// Nothing in the batch was applied, and it can be recovered with
+/// `into_ops`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BatchError<T> {
+    position: usize,
+    ops: Vec<ArenaOp<T>>,
+}
+
+impl<T> BatchError<T> {
+    /// Get the position in the batch of the first invalid operation.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the index targeted by the first invalid operation.
+    pub fn index(&self) -> Index {
+        self.ops[self.position]
+            .target()
+            .expect("only removals and updates can be invalid")
+    }
+
+    /// Get back the operations of the batch, none of which were applied.
+    pub fn into_ops(self) -> Vec<ArenaOp<T>> {
+        self.ops
+    }
+}
+
+// Like `CapacityError`, don't require `T: Debug` just to report the error.
+impl<T> fmt::Debug for BatchError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BatchError")
+            .field("position", &self.position)
+            .field("index", &self.index())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for BatchError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "operation {} of the batch targets {}, which will not be in the arena",
+            self.position,
+            self.index()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for BatchError<T> {}
+
+impl<T> Arena<T> {
+    /// Apply a batch of operations in order, returning the indices of the
+    /// inserted elements in the order they were inserted.
+    ///
+    /// Removals and updates of elements that are not in the arena when they
+    /// are reached are skipped, and the values of skipped updates are
+    /// dropped. Use `try_apply_batch` to check the whole batch before
+    /// applying any of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, ArenaOp};
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    ///
+    /// let queued = vec![
+    ///     ArenaOp::Remove(a),
+    ///     ArenaOp::Update(b, "B"),
+    ///     ArenaOp::Insert("c"),
+    /// ];
+    /// let inserted = arena.apply_batch(queued);
+    ///
+    /// assert!(!arena.contains(a));
+    /// assert_eq!(arena[b], "B");
+    /// assert_eq!(arena[inserted[0]], "c");
+    /// ```
+    pub fn apply_batch(&mut self, ops: impl IntoIterator<Item = ArenaOp<T>>) -> Vec<Index> {
+        let mut inserted = Vec::new();
+        for op in ops {
+            match op {
+                ArenaOp::Insert(value) => inserted.push(self.insert(value)),
+                ArenaOp::Remove(i) => {
+                    self.remove(i);
+                }
+                ArenaOp::Update(i, value) => {
+                    if let Some(slot) = self.get_mut(i) {
+                        *slot = value;
+                    }
+                }
+            }
+        }
+        inserted
+    }
+
+    /// Check a batch of operations, and apply them in order only if every
+    /// one of them is valid, returning the indices of the inserted elements
+    /// in the order they were inserted.
+    ///
+    /// A removal or update is valid if its element is in the arena and is
+    /// not removed by an earlier operation in the batch. If any operation is
+    /// invalid, the arena is left untouched, and a `BatchError` reports the
+    /// first invalid one and gives back the batch.
+    ///
+    /// Checking sorts the targeted indices, so takes `O(n log n)` time for a
+    /// batch of `n` operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, ArenaOp};
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    ///
+    /// let queued = vec![ArenaOp::Insert(2), ArenaOp::Remove(a), ArenaOp::Update(a, 3)];
+    /// let err = arena.try_apply_batch(queued).unwrap_err();
+    /// assert_eq!(err.position(), 2);
+    /// assert_eq!(err.index(), a);
+    ///
+    /// // Nothing was applied.
+    /// assert_eq!(arena.len(), 1);
+    /// assert_eq!(arena[a], 1);
+    /// ```
+    pub fn try_apply_batch(
+        &mut self,
+        ops: impl IntoIterator<Item = ArenaOp<T>>,
+    ) -> Result<Vec<Index>, BatchError<T>> {
+        let ops: Vec<_> = ops.into_iter().collect();
+        if let Some(position) = self.first_invalid_op(&ops) {
+            return Err(BatchError { position, ops });
+        }
+        Ok(self.apply_batch(ops))
+    }
+
+    /// Find the position of the first removal or update in `ops` whose
+    /// element is missing from the arena or removed earlier in the batch.
+    fn first_invalid_op(&self, ops: &[ArenaOp<T>]) -> Option<usize> {
+        let mut targets: Vec<(Index, usize)> = ops
+            .iter()
+            .enumerate()
+            .filter_map(|(position, op)| Some((op.target()?, position)))
+            .collect();
+        // Group the operations on each element together, in batch order.
+        targets.sort_unstable();
+
+        let mut first_invalid: Option<usize> = None;
+        let mut removed: Option<Index> = None;
+        for &(index, position) in &targets {
+            if removed != Some(index) {
+                removed = None;
+            }
+            if removed.is_some() || !self.contains(index) {
+                first_invalid = Some(first_invalid.map_or(position, |p| p.min(position)));
+            } else if let ArenaOp::Remove(_) = ops[position] {
+                removed = Some(index);
+            }
+        }
+        first_invalid
+    }
+}
@@ -0,0 +1,185 @@
+use super::{Arena, DEFAULT_CAPACITY, DEFAULT_GROWTH_PERCENT};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// The order in which an arena reuses the slots of removed elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ReusePolicy {
+    /// Reuse the most recently freed slot first.
+    ///
+    /// This keeps recently touched memory hot, and is the default.
+    #[default]
+    Lifo,
+    /// Reuse the least recently freed slot first.
+    ///
+    /// This spreads reuse across all free slots, so any given slot is reused
+    /// as rarely as possible. Slots that have never been used are handed out
+    /// before any freed slot is reused.
+    Fifo,
+    /// Reuse the most recently freed slot first, like `Lifo`, but keep the
+    /// free slots in a separate stack instead of threading a list through
+    /// the free entries.
+    ///
+    /// Popping a slot off the stack doesn't need to read the slot's entry
+    /// first, which saves a cache miss per insertion when many removals and
+    /// insertions are interleaved across a large arena. In exchange, the
+    /// stack takes up to one more `usize` per slot, and removals may
+    /// allocate to grow it.
+    Stack,
+}
+
+/// A builder for configuring and constructing an `Arena`.
+///
+/// Created with `Arena::builder`.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::Arena;
+///
+/// let mut arena = Arena::builder().capacity(2).fifo_reuse().build();
+/// assert_eq!(arena.capacity(), 2);
+///
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// arena.remove(a);
+/// arena.remove(b);
+///
+/// // With FIFO reuse, the first slot freed is the first slot reused.
+/// let c = arena.insert("c");
+/// assert_eq!(c.into_raw_parts().0, a.into_raw_parts().0);
+/// ```
+#[must_use = "builders do nothing unless `build` is called"]
+pub struct ArenaBuilder<T> {
+    capacity: usize,
+    reuse: ReusePolicy,
+    generation_start: u64,
+    growth_percent: usize,
+    marker: PhantomData<fn() -> Arena<T>>,
+}
+
+impl<T> Arena<T> {
+    /// Create an `ArenaBuilder` for configuring a new arena.
+    pub fn builder() -> ArenaBuilder<T> {
+        ArenaBuilder::new()
+    }
+}
+
+impl<T> Default for ArenaBuilder<T> {
+    fn default() -> ArenaBuilder<T> {
+        ArenaBuilder::new()
+    }
+}
+
+impl<T> Clone for ArenaBuilder<T> {
+    fn clone(&self) -> ArenaBuilder<T> {
+        ArenaBuilder {
+            capacity: self.capacity,
+            reuse: self.reuse,
+            generation_start: self.generation_start,
+            growth_percent: self.growth_percent,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for ArenaBuilder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArenaBuilder")
+            .field("capacity", &self.capacity)
+            .field("reuse", &self.reuse)
+            .field("generation_start", &self.generation_start)
+            .field("growth_percent", &self.growth_percent)
+            .finish()
+    }
+}
+
+impl<T> ArenaBuilder<T> {
+    /// Create a builder with the default configuration.
+    ///
+    /// This builds the same arena as `Arena::with_capacity(4)`. Unlike
+    /// `Arena::new`, which allocates nothing until the first insertion, the
+    /// built arena allocates room for 4 elements up front.
+    pub fn new() -> ArenaBuilder<T> {
+        ArenaBuilder {
+            capacity: DEFAULT_CAPACITY,
+            reuse: ReusePolicy::default(),
+            generation_start: 0,
+            growth_percent: DEFAULT_GROWTH_PERCENT,
+            marker: PhantomData,
+        }
+    }
+
+    /// Set the number of elements the arena can hold without further
+    /// allocation.
+    pub fn capacity(mut self, capacity: usize) -> ArenaBuilder<T> {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the order in which slots of removed elements are reused.
+    pub fn reuse(mut self, reuse: ReusePolicy) -> ArenaBuilder<T> {
+        self.reuse = reuse;
+        self
+    }
+
+    /// Reuse the most recently freed slot first. This is the default.
+    pub fn lifo_reuse(self) -> ArenaBuilder<T> {
+        self.reuse(ReusePolicy::Lifo)
+    }
+
+    /// Reuse the least recently freed slot first.
+    pub fn fifo_reuse(self) -> ArenaBuilder<T> {
+        self.reuse(ReusePolicy::Fifo)
+    }
+
+    /// Reuse the most recently freed slot first, keeping free slots in a
+    /// separate stack. See `ReusePolicy::Stack`.
+    pub fn stack_reuse(self) -> ArenaBuilder<T> {
+        self.reuse(ReusePolicy::Stack)
+    }
+
+    /// Set the generation that the arena's generation counter starts at.
+    ///
+    /// See `Arena::with_generation_start`.
+    pub fn generation_start(mut self, generation: u64) -> ArenaBuilder<T> {
+        self.generation_start = generation;
+        self
+    }
+
+    /// Set how much the arena grows when it runs out of space, as a
+    /// percentage of its capacity at the time.
+    ///
+    /// The default, 200, doubles the capacity. Smaller values trade more
+    /// frequent reallocation for less unused memory in large arenas. Either
+    /// way, the arena grows by at least its current capacity or 4 slots,
+    /// whichever is fewer, so that small percentages don't reallocate on
+    /// nearly every insertion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percent` is not more than 100.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::builder().capacity(100).growth_percent(125).build();
+    /// arena.extend(0..101);
+    /// assert_eq!(arena.capacity(), 125);
+    /// ```
+    pub fn growth_percent(mut self, percent: usize) -> ArenaBuilder<T> {
+        assert!(percent > 100, "growth percentage must be more than 100");
+        self.growth_percent = percent;
+        self
+    }
+
+    /// Construct the configured `Arena`.
+    pub fn build(self) -> Arena<T> {
+        let mut arena = Arena::with_capacity_and_reuse(self.capacity, self.reuse);
+        arena.generation = self.generation_start;
+        arena.growth_percent = self.growth_percent;
+        arena
+    }
+}
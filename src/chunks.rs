@@ -0,0 +1,295 @@
+use super::{Arena, ArenaId, Entry, Index};
+use core::iter::FusedIterator;
+use core::mem;
+
+impl<T> Arena<T> {
+    /// Iterate over runs of up to `n` elements in consecutive slots.
+    ///
+    /// Each `Chunk` covers occupied slots only, in ascending slot order; free
+    /// slots split runs and are skipped. This lets batch-oriented code, such
+    /// as a loop that copies elements into a staging buffer, work a run at a
+    /// time. Every slot also stores its element's generation, so a run is not
+    /// a plain `&[T]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let indices: Vec<_> = (0..7).map(|n| arena.insert(n)).collect();
+    /// arena.remove(indices[4]);
+    ///
+    /// let runs: Vec<Vec<i32>> = arena
+    ///     .iter_chunks(3)
+    ///     .map(|chunk| chunk.values().copied().collect())
+    ///     .collect();
+    /// assert_eq!(runs, [vec![0, 1, 2], vec![3], vec![5, 6]]);
+    /// ```
+    pub fn iter_chunks(&self, n: usize) -> Chunks<'_, T> {
+        assert!(n > 0, "chunk size must be non-zero");
+        Chunks {
+            offset: 0,
+            size: n,
+            arena: self.id,
+            entries: &self.items,
+        }
+    }
+
+    /// Iterate over runs of up to `n` elements in consecutive slots, with
+    /// exclusive access to them.
+    ///
+    /// This is the exclusive counterpart of `iter_chunks`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..10);
+    ///
+    /// for mut chunk in arena.iter_chunks_mut(4) {
+    ///     for value in chunk.values_mut() {
+    ///         *value *= 2;
+    ///     }
+    /// }
+    /// assert!(arena.iter().all(|(_, n)| n % 2 == 0));
+    /// ```
+    pub fn iter_chunks_mut(&mut self, n: usize) -> ChunksMut<'_, T> {
+        assert!(n > 0, "chunk size must be non-zero");
+        ChunksMut {
+            offset: 0,
+            size: n,
+            arena: self.id,
+            entries: &mut self.items,
+        }
+    }
+}
+
+/// The length of the run of occupied entries at the start of `entries`, up
+/// to `size`.
+fn run_len<T>(entries: &[Entry<T>], size: usize) -> usize {
+    entries
+        .iter()
+        .take(size)
+        .take_while(|entry| matches!(entry, Entry::Occupied { .. }))
+        .count()
+}
+
+/// The number of free entries at the start of `entries`.
+fn gap_len<T>(entries: &[Entry<T>]) -> usize {
+    entries
+        .iter()
+        .take_while(|entry| matches!(entry, Entry::Free { .. }))
+        .count()
+}
+
+fn occupied<T>(entry: &Entry<T>) -> (u64, &T) {
+    match entry {
+        Entry::Occupied { generation, value } => (*generation, value),
+        Entry::Free { .. } => unreachable!("chunks only cover occupied slots"),
+    }
+}
+
+fn occupied_mut<T>(entry: &mut Entry<T>) -> (u64, &mut T) {
+    match entry {
+        Entry::Occupied { generation, value } => (*generation, value),
+        Entry::Free { .. } => unreachable!("chunks only cover occupied slots"),
+    }
+}
+
+/// A run of elements in consecutive slots of an arena.
+///
+/// Yielded by `Arena::iter_chunks`.
+#[derive(Debug)]
+pub struct Chunk<'a, T: 'a> {
+    start: usize,
+    arena: ArenaId,
+    entries: &'a [Entry<T>],
+}
+
+impl<'a, T> Clone for Chunk<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Chunk<'a, T> {}
+
+impl<'a, T> Chunk<'a, T> {
+    /// The slot of the first element in this run.
+    pub fn start_slot(&self) -> usize {
+        self.start
+    }
+
+    /// The number of elements in this run.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this run has no elements. Runs yielded by
+    /// `iter_chunks` never are.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the elements in this run, along with their indices.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &'a T)> + 'a {
+        let (start, arena) = (self.start, self.arena);
+        self.entries.iter().enumerate().map(move |(i, entry)| {
+            let (generation, value) = occupied(entry);
+            let index = Index {
+                index: start + i,
+                generation,
+                arena,
+            };
+            (index, value)
+        })
+    }
+
+    /// Iterate over the elements in this run.
+    pub fn values(&self) -> impl Iterator<Item = &'a T> + 'a {
+        self.entries.iter().map(|entry| occupied(entry).1)
+    }
+}
+
+/// An iterator over runs of elements in consecutive slots of an arena.
+///
+/// Created by `Arena::iter_chunks`.
+#[derive(Clone, Debug)]
+pub struct Chunks<'a, T: 'a> {
+    offset: usize,
+    size: usize,
+    arena: ArenaId,
+    entries: &'a [Entry<T>],
+}
+
+impl<'a, T> Iterator for Chunks<'a, T> {
+    type Item = Chunk<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let gap = gap_len(self.entries);
+        self.offset += gap;
+        self.entries = &self.entries[gap..];
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let (run, rest) = self.entries.split_at(run_len(self.entries, self.size));
+        let chunk = Chunk {
+            start: self.offset,
+            arena: self.arena,
+            entries: run,
+        };
+        self.offset += run.len();
+        self.entries = rest;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entries.len()))
+    }
+}
+
+impl<'a, T> FusedIterator for Chunks<'a, T> {}
+
+/// A run of elements in consecutive slots of an arena, with exclusive access
+/// to them.
+///
+/// Yielded by `Arena::iter_chunks_mut`.
+#[derive(Debug)]
+pub struct ChunkMut<'a, T: 'a> {
+    start: usize,
+    arena: ArenaId,
+    entries: &'a mut [Entry<T>],
+}
+
+impl<'a, T> ChunkMut<'a, T> {
+    /// The slot of the first element in this run.
+    pub fn start_slot(&self) -> usize {
+        self.start
+    }
+
+    /// The number of elements in this run.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this run has no elements. Runs yielded by
+    /// `iter_chunks_mut` never are.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over exclusive references to the elements in this run, along
+    /// with their indices.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &mut T)> + '_ {
+        let (start, arena) = (self.start, self.arena);
+        self.entries.iter_mut().enumerate().map(move |(i, entry)| {
+            let (generation, value) = occupied_mut(entry);
+            let index = Index {
+                index: start + i,
+                generation,
+                arena,
+            };
+            (index, value)
+        })
+    }
+
+    /// Iterate over exclusive references to the elements in this run.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.entries.iter_mut().map(|entry| occupied_mut(entry).1)
+    }
+}
+
+/// An iterator over runs of elements in consecutive slots of an arena, with
+/// exclusive access to them.
+///
+/// Created by `Arena::iter_chunks_mut`.
+#[derive(Debug)]
+pub struct ChunksMut<'a, T: 'a> {
+    offset: usize,
+    size: usize,
+    arena: ArenaId,
+    entries: &'a mut [Entry<T>],
+}
+
+impl<'a, T> Iterator for ChunksMut<'a, T> {
+    type Item = ChunkMut<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entries = mem::take(&mut self.entries);
+        let gap = gap_len(entries);
+        self.offset += gap;
+        let entries = &mut entries[gap..];
+        if entries.is_empty() {
+            return None;
+        }
+
+        let len = run_len(entries, self.size);
+        let (run, rest) = entries.split_at_mut(len);
+        let chunk = ChunkMut {
+            start: self.offset,
+            arena: self.arena,
+            entries: run,
+        };
+        self.offset += len;
+        self.entries = rest;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entries.len()))
+    }
+}
+
+impl<'a, T> FusedIterator for ChunksMut<'a, T> {}
@@ -0,0 +1,178 @@
+use super::{Arena, Entry, Index, Vec};
+use core::cmp;
+
+/// The differences between two arenas, as computed by `Arena::diff`.
+///
+/// A `Diff` can be sent elsewhere (e.g. over the network) and applied to a
+/// copy of the original arena with `Arena::apply_diff` to bring it up to date.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::Arena;
+///
+/// let mut old = Arena::new();
+/// let a = old.insert("a");
+/// let b = old.insert("b");
+///
+/// let mut new = old.clone();
+/// new.remove(a);
+/// new[b] = "B";
+/// let c = new.insert("c");
+///
+/// let diff = old.diff(&new);
+/// assert_eq!(diff.removed(), &[a]);
+/// assert_eq!(diff.changed(), &[(b, "B")]);
+/// assert_eq!(diff.inserted(), &[(c, "c")]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diff<T> {
+    inserted: Vec<(Index, T)>,
+    removed: Vec<Index>,
+    changed: Vec<(Index, T)>,
+    generation: u64,
+}
+
+impl<T> Diff<T> {
+    /// The entries that are present in the new arena but not in the old one.
+    pub fn inserted(&self) -> &[(Index, T)] {
+        &self.inserted
+    }
+
+    /// The entries that are present in the old arena but not in the new one.
+    pub fn removed(&self) -> &[Index] {
+        &self.removed
+    }
+
+    /// The entries that are present in both arenas with different values,
+    /// along with their new values.
+    pub fn changed(&self) -> &[(Index, T)] {
+        &self.changed
+    }
+
+    /// Returns true if the two arenas had the same entries.
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<T> Arena<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Compute the differences between this arena and `other`.
+    ///
+    /// Entries are matched up by `Index`, so an entry whose slot was reused
+    /// since this arena was captured shows up as both removed and inserted.
+    /// Each list in the resulting `Diff` is in ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert(1);
+    /// let snapshot = arena.clone();
+    ///
+    /// assert!(snapshot.diff(&arena).is_empty());
+    ///
+    /// arena.insert(2);
+    /// assert_eq!(snapshot.diff(&arena).inserted().len(), 1);
+    /// ```
+    pub fn diff(&self, other: &Arena<T>) -> Diff<T> {
+        let mut diff = Diff {
+            inserted: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            generation: other.generation,
+        };
+
+        for slot in 0..cmp::max(self.items.len(), other.items.len()) {
+            let old = self.get_unknown_gen(slot);
+            let new = other.get_unknown_gen(slot);
+            match (old, new) {
+                (Some((old_value, old_index)), Some((new_value, new_index)))
+                    if old_index == new_index =>
+                {
+                    if old_value != new_value {
+                        diff.changed.push((new_index, new_value.clone()));
+                    }
+                }
+                (old, new) => {
+                    if let Some((_, old_index)) = old {
+                        diff.removed.push(old_index);
+                    }
+                    if let Some((new_value, new_index)) = new {
+                        diff.inserted.push((new_index, new_value.clone()));
+                    }
+                }
+            }
+        }
+
+        diff
+    }
+
+    /// Apply a `Diff` computed by `Arena::diff` to this arena.
+    ///
+    /// The diff should be applied to an arena holding the same entries as the
+    /// one it was computed from. Afterwards, this arena holds the same entries
+    /// as the arena the diff was computed against, and inserting into it will
+    /// never produce an `Index` that was valid in either arena.
+    ///
+    /// Inserted entries are placed at exactly their recorded `Index`,
+    /// overwriting whatever occupied that slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut server = Arena::new();
+    /// let a = server.insert("a");
+    /// let mut client = server.clone();
+    ///
+    /// let before = server.clone();
+    /// server.remove(a);
+    /// let b = server.insert("b");
+    ///
+    /// client.apply_diff(before.diff(&server));
+    /// assert!(!client.contains(a));
+    /// assert_eq!(client[b], "b");
+    /// ```
+    pub fn apply_diff(&mut self, diff: Diff<T>) {
+        // The diff's indices may well come from other arenas, so look them
+        // up as if this arena had created them.
+        for index in diff.removed {
+            self.remove(Index {
+                arena: self.id,
+                ..index
+            });
+        }
+
+        for (index, value) in diff.changed {
+            if let Some(v) = self.get_mut(Index {
+                arena: self.id,
+                ..index
+            }) {
+                *v = value;
+            }
+        }
+
+        if !diff.inserted.is_empty() {
+            for (index, value) in diff.inserted {
+                if index.index >= self.items.len() {
+                    self.items
+                        .resize_with(index.index + 1, || Entry::Free { next_free: None });
+                }
+                self.items[index.index] = Entry::Occupied {
+                    generation: index.generation,
+                    value,
+                };
+            }
+            self.rebuild_free_list();
+        }
+
+        self.generation = cmp::max(self.generation, diff.generation);
+    }
+}
@@ -0,0 +1,241 @@
+use super::{Arena, ArenaId, Entry, Index};
+use core::fmt;
+use core::iter::{self, FusedIterator};
+use core::slice;
+
+/// A read-only view of a single slot in an arena's underlying storage.
+///
+/// Yielded by `Arena::entries`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotView<'a, T: 'a> {
+    /// The slot holds an element.
+    Occupied {
+        /// The element's index, including its slot and generation.
+        index: Index,
+        /// The element itself.
+        value: &'a T,
+    },
+    /// The slot is free.
+    Free {
+        /// The position of this slot.
+        slot: usize,
+        /// The next slot in the free list, if any. Always `None` in arenas
+        /// with `ReusePolicy::Stack`, which keep no free list.
+        next_free: Option<usize>,
+    },
+}
+
+impl<'a, T> SlotView<'a, T> {
+    /// The position of this slot in the arena's storage.
+    pub fn slot(&self) -> usize {
+        match *self {
+            SlotView::Occupied { index, .. } => index.index,
+            SlotView::Free { slot, .. } => slot,
+        }
+    }
+
+    /// Returns true if this slot holds an element.
+    pub fn is_occupied(&self) -> bool {
+        matches!(self, SlotView::Occupied { .. })
+    }
+}
+
+impl<T> Arena<T> {
+    /// Iterate over every slot in the arena's storage, free or occupied, in
+    /// slot order.
+    ///
+    /// This exposes the arena's internal layout, including the free list, and
+    /// is meant for debugging and visualization tools. Unlike `iter`, it
+    /// yields one item per used slot, as counted by `slot_count`. Capacity
+    /// that has never been used is not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, SlotView};
+    ///
+    /// let mut arena = Arena::with_capacity(4);
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    /// arena.remove(a);
+    /// arena.remove(c);
+    ///
+    /// let slots: Vec<_> = arena.entries().collect();
+    /// assert_eq!(slots, vec![
+    ///     SlotView::Free { slot: 0, next_free: None },
+    ///     SlotView::Occupied { index: b, value: &'b' },
+    ///     SlotView::Free { slot: 2, next_free: Some(0) },
+    /// ]);
+    /// ```
+    pub fn entries(&self) -> Entries<'_, T> {
+        Entries {
+            arena: self.id,
+            inner: self.items.iter().enumerate(),
+        }
+    }
+
+    /// Get a `Debug` adapter that prints the arena's slots, as yielded by
+    /// `entries`, formatting each element with `fmt_value`.
+    ///
+    /// This dumps the structure of an arena whose element type doesn't
+    /// implement `Debug`, or whose `Debug` output is too noisy for logs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::fmt;
+    ///
+    /// struct Texture(Vec<u8>);
+    ///
+    /// let mut textures = Arena::new();
+    /// let a = textures.insert(Texture(vec![0; 1024]));
+    /// textures.insert(Texture(vec![0; 16]));
+    /// textures.remove(a);
+    ///
+    /// let dump = format!(
+    ///     "{:?}",
+    ///     textures.debug_with(|t, f| write!(f, "{} bytes", t.0.len()))
+    /// );
+    /// assert!(dump.contains("Free { slot: 0"));
+    /// assert!(dump.contains("value: 16 bytes"));
+    /// ```
+    pub fn debug_with<'a, F>(&'a self, fmt_value: F) -> impl fmt::Debug + 'a
+    where
+        F: Fn(&T, &mut fmt::Formatter) -> fmt::Result + 'a,
+    {
+        DebugWith {
+            arena: self,
+            fmt_value,
+        }
+    }
+}
+
+/// An iterator over every slot in an arena's storage.
+///
+/// Yields a `SlotView` per slot, in slot order.
+///
+/// Created by `Arena::entries`.
+#[derive(Clone, Debug)]
+pub struct Entries<'a, T: 'a> {
+    arena: ArenaId,
+    inner: iter::Enumerate<slice::Iter<'a, Entry<T>>>,
+}
+
+fn slot_view<T>(arena: ArenaId, (slot, entry): (usize, &Entry<T>)) -> SlotView<'_, T> {
+    match *entry {
+        Entry::Occupied {
+            generation,
+            ref value,
+        } => SlotView::Occupied {
+            index: Index {
+                index: slot,
+                generation,
+                arena,
+            },
+            value,
+        },
+        Entry::Free { next_free } => SlotView::Free { slot, next_free },
+    }
+}
+
+impl<'a, T> Iterator for Entries<'a, T> {
+    type Item = SlotView<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let arena = self.arena;
+        self.inner.next().map(|entry| slot_view(arena, entry))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Entries<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let arena = self.arena;
+        self.inner.next_back().map(|entry| slot_view(arena, entry))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Entries<'a, T> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'a, T> FusedIterator for Entries<'a, T> {}
+
+/// The adapter returned by `Arena::debug_with`.
+struct DebugWith<'a, T, F> {
+    arena: &'a Arena<T>,
+    fmt_value: F,
+}
+
+impl<'a, T, F> fmt::Debug for DebugWith<'a, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("len", &self.arena.len())
+            .field("generation", &self.arena.generation)
+            .field("slots", &SlotsWith(self))
+            .finish()
+    }
+}
+
+/// Prints the slots of a `DebugWith`'s arena as a list.
+struct SlotsWith<'d, 'a, T, F>(&'d DebugWith<'a, T, F>);
+
+impl<'d, 'a, T, F> fmt::Debug for SlotsWith<'d, 'a, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let DebugWith { arena, fmt_value } = self.0;
+        let slots = arena.entries().map(|slot| SlotWith { slot, fmt_value });
+        f.debug_list().entries(slots).finish()
+    }
+}
+
+/// Prints a `SlotView` like its derived `Debug` impl, but formats the value
+/// with `fmt_value`.
+struct SlotWith<'a, 'f, T, F> {
+    slot: SlotView<'a, T>,
+    fmt_value: &'f F,
+}
+
+impl<'a, 'f, T, F> fmt::Debug for SlotWith<'a, 'f, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.slot {
+            SlotView::Occupied { index, value } => f
+                .debug_struct("Occupied")
+                .field("index", &index)
+                .field("value", &ValueWith(value, self.fmt_value))
+                .finish(),
+            SlotView::Free { slot, next_free } => f
+                .debug_struct("Free")
+                .field("slot", &slot)
+                .field("next_free", &next_free)
+                .finish(),
+        }
+    }
+}
+
+/// Prints a value with a user-supplied formatter.
+struct ValueWith<'a, T, F>(&'a T, &'a F);
+
+impl<'a, T, F> fmt::Debug for ValueWith<'a, T, F>
+where
+    F: Fn(&T, &mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.1)(self.0, f)
+    }
+}
@@ -0,0 +1,90 @@
+use super::{Arena, Index, Vec};
+use core::iter::FusedIterator;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::stream::{FusedStream, Stream};
+
+impl<T: Clone> Arena<T> {
+    /// Stream clones of the elements in this arena, along with their indices,
+    /// in chunks of up to `n` elements.
+    ///
+    /// Each chunk only costs as much as the slots it covers, so an async task
+    /// can send a large arena's contents, e.g. to sync a client's initial
+    /// state, one chunk at a time, awaiting the network in between rather
+    /// than serializing everything in one call. Chunks are in ascending slot
+    /// order. The returned `SnapshotStream` is also an `Iterator` over the
+    /// same chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..5);
+    ///
+    /// let sizes: Vec<_> = arena.snapshot_stream(2).map(|chunk| chunk.len()).collect();
+    /// assert_eq!(sizes, [2, 2, 1]);
+    /// ```
+    pub fn snapshot_stream(&self, n: usize) -> SnapshotStream<'_, T> {
+        assert!(n > 0, "chunk size must be non-zero");
+        SnapshotStream {
+            arena: self,
+            next_slot: 0,
+            size: n,
+        }
+    }
+}
+
+/// A stream of chunks of clones of an arena's elements, along with their
+/// indices.
+///
+/// Every poll is immediately ready, and the stream ends once every element
+/// has been yielded.
+///
+/// Created by `Arena::snapshot_stream`.
+#[derive(Clone, Debug)]
+pub struct SnapshotStream<'a, T: 'a> {
+    arena: &'a Arena<T>,
+    next_slot: usize,
+    size: usize,
+}
+
+impl<'a, T: Clone> Iterator for SnapshotStream<'a, T> {
+    type Item = Vec<(Index, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut iter = self.arena.iter_occupied_from(self.next_slot);
+        let chunk: Vec<_> = iter
+            .by_ref()
+            .take(self.size)
+            .map(|(i, value)| (i, value.clone()))
+            .collect();
+        self.next_slot = iter.next_slot();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+impl<'a, T: Clone> FusedIterator for SnapshotStream<'a, T> {}
+
+impl<'a, T: Clone> Stream for SnapshotStream<'a, T> {
+    type Item = Vec<(Index, T)>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().next())
+    }
+}
+
+impl<'a, T: Clone> FusedStream for SnapshotStream<'a, T> {
+    fn is_terminated(&self) -> bool {
+        self.next_slot >= self.arena.slot_count()
+    }
+}
@@ -0,0 +1,246 @@
+use super::{Arena, Index, Vec};
+use core::slice;
+
+/// The index of a node in an `ArenaGraph`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeIndex(Index);
+
+/// The index of an edge in an `ArenaGraph`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeIndex(Index);
+
+impl From<NodeIndex> for Index {
+    fn from(node: NodeIndex) -> Index {
+        node.0
+    }
+}
+
+impl From<EdgeIndex> for Index {
+    fn from(edge: EdgeIndex) -> Index {
+        edge.0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<N> {
+    weight: N,
+    outgoing: Vec<EdgeIndex>,
+    incoming: Vec<EdgeIndex>,
+}
+
+#[derive(Clone, Debug)]
+struct Edge<E> {
+    weight: E,
+    from: NodeIndex,
+    to: NodeIndex,
+}
+
+/// A directed graph whose nodes and edges live in arenas.
+///
+/// Nodes carry weights of type `N` and edges carry weights of type `E`. Both
+/// are referred to by generational indices, so removing a node or an edge
+/// never invalidates the indices of any other, and a stale index is simply
+/// not found. Removing a node also removes every edge into or out of it.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::ArenaGraph;
+///
+/// let mut graph = ArenaGraph::new();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// let ab = graph.add_edge(a, b, 1).unwrap();
+/// graph.add_edge(a, c, 2).unwrap();
+///
+/// let mut neighbors: Vec<_> = graph.neighbors(a).collect();
+/// neighbors.sort();
+/// assert_eq!(neighbors, [b, c]);
+///
+/// // Removing a node removes its edges too.
+/// assert_eq!(graph.remove_node(b), Some("b"));
+/// assert_eq!(graph.edge(ab), None);
+/// assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [c]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArenaGraph<N, E> {
+    nodes: Arena<Node<N>>,
+    edges: Arena<Edge<E>>,
+}
+
+impl<N, E> Default for ArenaGraph<N, E> {
+    fn default() -> ArenaGraph<N, E> {
+        ArenaGraph::new()
+    }
+}
+
+impl<N, E> ArenaGraph<N, E> {
+    /// Constructs a new, empty graph.
+    pub fn new() -> ArenaGraph<N, E> {
+        ArenaGraph {
+            nodes: Arena::new(),
+            edges: Arena::new(),
+        }
+    }
+
+    /// Get the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Add a node with the given weight to the graph.
+    pub fn add_node(&mut self, weight: N) -> NodeIndex {
+        NodeIndex(self.nodes.insert(Node {
+            weight,
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }))
+    }
+
+    /// Add an edge with the given weight from node `from` to node `to`.
+    ///
+    /// Returns `None` if either node is not in the graph.
+    pub fn add_edge(&mut self, from: NodeIndex, to: NodeIndex, weight: E) -> Option<EdgeIndex> {
+        if !self.contains_node(from) || !self.contains_node(to) {
+            return None;
+        }
+        let edge = EdgeIndex(self.edges.insert(Edge { weight, from, to }));
+        self.nodes[from.0].outgoing.push(edge);
+        self.nodes[to.0].incoming.push(edge);
+        Some(edge)
+    }
+
+    /// Remove node `n` and every edge into or out of it from the graph.
+    ///
+    /// Returns the node's weight, or `None` if it was not in the graph.
+    pub fn remove_node(&mut self, n: NodeIndex) -> Option<N> {
+        let node = self.nodes.remove(n.0)?;
+        for edge in node.outgoing.into_iter().chain(node.incoming) {
+            // Self loops appear in both lists, so may already be gone.
+            self.remove_edge(edge);
+        }
+        Some(node.weight)
+    }
+
+    /// Remove edge `e` from the graph.
+    ///
+    /// Returns the edge's weight, or `None` if it was not in the graph.
+    pub fn remove_edge(&mut self, e: EdgeIndex) -> Option<E> {
+        let edge = self.edges.remove(e.0)?;
+        if let Some(from) = self.nodes.get_mut(edge.from.0) {
+            from.outgoing.retain(|&other| other != e);
+        }
+        if let Some(to) = self.nodes.get_mut(edge.to.0) {
+            to.incoming.retain(|&other| other != e);
+        }
+        Some(edge.weight)
+    }
+
+    /// Is node `n` in the graph?
+    pub fn contains_node(&self, n: NodeIndex) -> bool {
+        self.nodes.contains(n.0)
+    }
+
+    /// Is edge `e` in the graph?
+    pub fn contains_edge(&self, e: EdgeIndex) -> bool {
+        self.edges.contains(e.0)
+    }
+
+    /// Get a shared reference to the weight of node `n`, if it is in the
+    /// graph.
+    pub fn node(&self, n: NodeIndex) -> Option<&N> {
+        self.nodes.get(n.0).map(|node| &node.weight)
+    }
+
+    /// Get an exclusive reference to the weight of node `n`, if it is in the
+    /// graph.
+    pub fn node_mut(&mut self, n: NodeIndex) -> Option<&mut N> {
+        self.nodes.get_mut(n.0).map(|node| &mut node.weight)
+    }
+
+    /// Get a shared reference to the weight of edge `e`, if it is in the
+    /// graph.
+    pub fn edge(&self, e: EdgeIndex) -> Option<&E> {
+        self.edges.get(e.0).map(|edge| &edge.weight)
+    }
+
+    /// Get an exclusive reference to the weight of edge `e`, if it is in the
+    /// graph.
+    pub fn edge_mut(&mut self, e: EdgeIndex) -> Option<&mut E> {
+        self.edges.get_mut(e.0).map(|edge| &mut edge.weight)
+    }
+
+    /// Get the `(from, to)` nodes of edge `e`, if it is in the graph.
+    pub fn edge_endpoints(&self, e: EdgeIndex) -> Option<(NodeIndex, NodeIndex)> {
+        self.edges.get(e.0).map(|edge| (edge.from, edge.to))
+    }
+
+    /// Iterate over the nodes that node `n` has edges to.
+    ///
+    /// A node with several edges to the same neighbor yields that neighbor
+    /// once per edge. If `n` is not in the graph, nothing is yielded.
+    pub fn neighbors(&self, n: NodeIndex) -> Neighbors<'_, N, E> {
+        Neighbors {
+            graph: self,
+            edges: self.edges_from(n),
+        }
+    }
+
+    /// Get the edges out of node `n`. If `n` is not in the graph, this is
+    /// empty.
+    pub fn edges_from(&self, n: NodeIndex) -> slice::Iter<'_, EdgeIndex> {
+        match self.nodes.get(n.0) {
+            Some(node) => node.outgoing.iter(),
+            None => [].iter(),
+        }
+    }
+
+    /// Get the edges into node `n`. If `n` is not in the graph, this is
+    /// empty.
+    pub fn edges_to(&self, n: NodeIndex) -> slice::Iter<'_, EdgeIndex> {
+        match self.nodes.get(n.0) {
+            Some(node) => node.incoming.iter(),
+            None => [].iter(),
+        }
+    }
+
+    /// Iterate over every node in the graph, along with its weight.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, &N)> {
+        self.nodes.iter().map(|(i, node)| (NodeIndex(i), &node.weight))
+    }
+
+    /// Iterate over every edge in the graph, along with its weight.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeIndex, &E)> {
+        self.edges.iter().map(|(i, edge)| (EdgeIndex(i), &edge.weight))
+    }
+}
+
+/// An iterator over the nodes that a node has edges to.
+///
+/// Created by `ArenaGraph::neighbors`.
+#[derive(Clone, Debug)]
+pub struct Neighbors<'a, N: 'a, E: 'a> {
+    graph: &'a ArenaGraph<N, E>,
+    edges: slice::Iter<'a, EdgeIndex>,
+}
+
+impl<'a, N, E> Iterator for Neighbors<'a, N, E> {
+    type Item = NodeIndex;
+
+    fn next(&mut self) -> Option<NodeIndex> {
+        let edge = self.edges.next()?;
+        Some(self.graph.edges[edge.0].to)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
+    }
+}
+
+impl<'a, N, E> ExactSizeIterator for Neighbors<'a, N, E> {}
@@ -0,0 +1,192 @@
+use super::{ArenaHooks, Index, ObservedArena, Vec};
+use core::iter::FusedIterator;
+
+/// Tracks the order in which an arena's elements were inserted.
+///
+/// An arena iterates in slot order, and once slots are reused that says
+/// nothing about which element came first. An `InsertionOrder` is a set of
+/// `ArenaHooks` that links the elements of an `ObservedArena` into a list,
+/// oldest first, as they are inserted and removed. Both take constant time.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{InsertionOrder, ObservedArena};
+///
+/// let mut arena = ObservedArena::new(InsertionOrder::new());
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// arena.remove(a);
+/// // This reuses the slot `a` was in.
+/// let c = arena.insert("c");
+///
+/// let order: Vec<_> = arena.iter_insertion_order().map(|(_, v)| *v).collect();
+/// assert_eq!(order, ["b", "c"]);
+/// assert_eq!(arena.last_inserted(), Some(c));
+/// assert_eq!(arena.hooks().first_inserted(), Some(b));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct InsertionOrder {
+    // Indexed by slot.
+    links: Vec<Option<Link>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Link {
+    index: Index,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl InsertionOrder {
+    /// Constructs a new, empty `InsertionOrder`.
+    pub fn new() -> InsertionOrder {
+        InsertionOrder::default()
+    }
+
+    /// Get the index of the oldest element, if there is one.
+    pub fn first_inserted(&self) -> Option<Index> {
+        self.head.map(|slot| self.link(slot).index)
+    }
+
+    /// Get the index of the newest element, if there is one.
+    pub fn last_inserted(&self) -> Option<Index> {
+        self.tail.map(|slot| self.link(slot).index)
+    }
+
+    /// Iterate over the indices of the elements, oldest first.
+    pub fn iter(&self) -> InsertionOrderIter<'_> {
+        InsertionOrderIter {
+            order: self,
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+        }
+    }
+
+    /// Get the number of elements being tracked.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no elements are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn link(&self, slot: usize) -> &Link {
+        self.links[slot]
+            .as_ref()
+            .expect("the insertion order list only links tracked slots")
+    }
+
+    fn link_mut(&mut self, slot: usize) -> &mut Link {
+        self.links[slot]
+            .as_mut()
+            .expect("the insertion order list only links tracked slots")
+    }
+}
+
+impl<T> ArenaHooks<T> for InsertionOrder {
+    fn on_insert(&mut self, i: Index, _value: &T) {
+        let slot = i.index;
+        if self.links.len() <= slot {
+            self.links.resize(slot + 1, None);
+        }
+        self.links[slot] = Some(Link {
+            index: i,
+            prev: self.tail,
+            next: None,
+        });
+        match self.tail {
+            Some(tail) => self.link_mut(tail).next = Some(slot),
+            None => self.head = Some(slot),
+        }
+        self.tail = Some(slot);
+        self.len += 1;
+    }
+
+    fn on_remove(&mut self, i: Index, _value: &T) {
+        let link = match self.links.get(i.index) {
+            Some(&Some(link)) if link.index == i => link,
+            _ => return,
+        };
+        self.links[i.index] = None;
+        match link.prev {
+            Some(prev) => self.link_mut(prev).next = link.next,
+            None => self.head = link.next,
+        }
+        match link.next {
+            Some(next) => self.link_mut(next).prev = link.prev,
+            None => self.tail = link.prev,
+        }
+        self.len -= 1;
+    }
+}
+
+/// An iterator over the indices of an arena's elements, oldest first.
+///
+/// Created by `InsertionOrder::iter`.
+#[derive(Clone, Debug)]
+pub struct InsertionOrderIter<'a> {
+    order: &'a InsertionOrder,
+    front: Option<usize>,
+    back: Option<usize>,
+    len: usize,
+}
+
+impl<'a> Iterator for InsertionOrderIter<'a> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let link = self.order.link(self.front?);
+        self.front = link.next;
+        self.len -= 1;
+        Some(link.index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for InsertionOrderIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let link = self.order.link(self.back?);
+        self.back = link.prev;
+        self.len -= 1;
+        Some(link.index)
+    }
+}
+
+impl<'a> ExactSizeIterator for InsertionOrderIter<'a> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a> FusedIterator for InsertionOrderIter<'a> {}
+
+impl<T> ObservedArena<T, InsertionOrder> {
+    /// Iterate over the elements in the order they were inserted, oldest
+    /// first, along with their indices.
+    pub fn iter_insertion_order(&self) -> impl DoubleEndedIterator<Item = (Index, &T)> + '_ {
+        let arena = self.arena();
+        self.hooks().iter().map(move |i| (i, &arena[i]))
+    }
+
+    /// Get the index of the most recently inserted element that is still in
+    /// the arena, if there is one.
+    pub fn last_inserted(&self) -> Option<Index> {
+        self.hooks().last_inserted()
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Arena, Index};
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::ops;
+use std::collections::HashMap;
+
+/// An arena whose elements are also looked up by a unique key, such as a
+/// name.
+///
+/// Each element is inserted along with a key. After that, it can be found
+/// by either its key or its `Index`, and removing it by either one removes
+/// it from both, so a key never maps to a removed element. This suits
+/// elements that are looked up by name once, e.g. when spawned or loaded,
+/// and by handle from then on.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::KeyedArena;
+///
+/// let mut entities = KeyedArena::new();
+/// let player = entities.insert("player", 100).unwrap();
+/// entities.insert("enemy", 50).unwrap();
+///
+/// assert_eq!(entities.index_of_key("player"), Some(player));
+/// entities[player] -= 10;
+/// assert_eq!(entities.get_by_key("player"), Some(&90));
+///
+/// // Keys are unique.
+/// assert_eq!(entities.insert("player", 0), Err(("player", 0)));
+///
+/// assert_eq!(entities.remove(player), Some(("player", 90)));
+/// assert_eq!(entities.index_of_key("player"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyedArena<K, V> {
+    arena: Arena<(K, V)>,
+    keys: HashMap<K, Index>,
+}
+
+impl<K, V> Default for KeyedArena<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> KeyedArena<K, V> {
+        KeyedArena::new()
+    }
+}
+
+impl<K, V> KeyedArena<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Constructs a new, empty `KeyedArena`.
+    pub fn new() -> KeyedArena<K, V> {
+        KeyedArena {
+            arena: Arena::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Constructs a new, empty `KeyedArena` with the specified capacity.
+    pub fn with_capacity(n: usize) -> KeyedArena<K, V> {
+        KeyedArena {
+            arena: Arena::with_capacity(n),
+            keys: HashMap::with_capacity(n),
+        }
+    }
+
+    /// Insert `value` under `key`, allocating more capacity if necessary.
+    ///
+    /// If `key` is not in use, the new element's index is returned. If it
+    /// is, nothing is inserted, and `Err((key, value))` is returned to give
+    /// ownership of both back to the caller.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Index, (K, V)> {
+        if self.keys.contains_key(&key) {
+            return Err((key, value));
+        }
+        let idx = self.arena.insert((key.clone(), value));
+        self.keys.insert(key, idx);
+        Ok(idx)
+    }
+
+    /// Remove the element at index `i`, returning its key and value.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is
+    /// returned.
+    pub fn remove(&mut self, i: Index) -> Option<(K, V)> {
+        let (key, value) = self.arena.remove(i)?;
+        self.keys.remove(&key);
+        Some((key, value))
+    }
+
+    /// Remove the element with the given key, returning its index and value.
+    ///
+    /// If no element has that key, then `None` is returned.
+    pub fn remove_by_key<Q>(&mut self, key: &Q) -> Option<(Index, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.keys.remove(key)?;
+        let (_, value) = self
+            .arena
+            .remove(idx)
+            .expect("every key maps to an element");
+        Some((idx, value))
+    }
+
+    /// Get the index of the element with the given key, if there is one.
+    pub fn index_of_key<Q>(&self, key: &Q) -> Option<Index>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys.get(key).copied()
+    }
+
+    /// Get a shared reference to the element with the given key, if there
+    /// is one.
+    pub fn get_by_key<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(self.index_of_key(key)?)
+    }
+
+    /// Get an exclusive reference to the element with the given key, if
+    /// there is one.
+    pub fn get_by_key_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.index_of_key(key)?;
+        self.get_mut(idx)
+    }
+
+    /// Is there an element with the given key?
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys.contains_key(key)
+    }
+}
+
+impl<K, V> KeyedArena<K, V> {
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index) -> Option<&V> {
+        self.arena.get(i).map(|(_, value)| value)
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut V> {
+        self.arena.get_mut(i).map(|(_, value)| value)
+    }
+
+    /// Get the key of the element at index `i` if it is in the arena.
+    pub fn key_of(&self, i: Index) -> Option<&K> {
+        self.arena.get(i).map(|(key, _)| key)
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index) -> bool {
+        self.arena.contains(i)
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns true if the arena contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Iterate over the elements in this arena, along with their indices
+    /// and keys.
+    ///
+    /// Order of iteration is not defined.
+    pub fn iter(&self) -> impl Iterator<Item = (Index, &K, &V)> {
+        self.arena.iter().map(|(i, (key, value))| (i, key, value))
+    }
+
+    /// Iterate over exclusive references to the elements in this arena,
+    /// along with their indices and keys.
+    ///
+    /// Order of iteration is not defined.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Index, &K, &mut V)> {
+        self.arena
+            .iter_mut()
+            .map(|(i, (key, value))| (i, &*key, value))
+    }
+}
+
+impl<K, V> ops::Index<Index> for KeyedArena<K, V> {
+    type Output = V;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        &self.arena[index].1
+    }
+}
+
+impl<K, V> ops::IndexMut<Index> for KeyedArena<K, V> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        &mut self.arena[index].1
+    }
+}
@@ -138,6 +138,76 @@ To enable serialization/deserialization support, enable the "serde" feature.
 [dependencies]
 generational-arena = { version = "0.2", features = ["serde"] }
 ```
+
+For data that must stay readable across releases of this crate, the
+[`versioned`](./versioned/index.html) module provides an explicitly versioned
+format for use with `#[serde(with = "generational_arena::versioned")]`.
+
+Arenas of borrowed elements, such as `Arena<&str>` and `Arena<&[u8]>`,
+deserialize without copying from formats that can lend out their input, like
+`bincode`. For string tables that must also load from formats that can't,
+`Arena::deserialize_cow_str` and `Arena::deserialize_cow_bytes` borrow each
+element when possible and copy it otherwise.
+
+### Parallel retention with [`rayon`](https://crates.io/crates/rayon)
+
+To evaluate `retain`-style predicates in parallel with `Arena::par_retain`,
+enable the "rayon" feature.
+
+```toml
+[dependencies]
+generational-arena = { version = "0.2", features = ["rayon"] }
+```
+
+### Streaming snapshots with [`futures`](https://crates.io/crates/futures)
+
+To stream clones of an arena's elements in bounded chunks with
+`Arena::snapshot_stream`, which implements `futures::Stream`, enable the
+"futures" feature.
+
+```toml
+[dependencies]
+generational-arena = { version = "0.2", features = ["futures"] }
+```
+
+### Migrating from [`slab`](https://crates.io/crates/slab)
+
+To convert between `slab::Slab` and `Arena` with `From`, enable the "slab"
+feature.
+
+```toml
+[dependencies]
+generational-arena = { version = "0.2", features = ["slab"] }
+```
+
+Elements keep their keys as slots, and `Index::from_slab_key` turns a slab
+key into the index of its element in the converted arena.
+
+### Catching indices used with the wrong arena
+
+An `Index` is just a slot and a generation, so using one with an arena other
+than the one that created it usually returns `None`, but can return an
+unrelated element if the slot and generation happen to line up. To catch such
+bugs, enable the "arena-id" feature.
+
+```toml
+[dependencies]
+generational-arena = { version = "0.2", features = ["arena-id"] }
+```
+
+Each arena then stamps its indices with a unique id, and `get`, `get_mut`,
+`get2_mut`, `remove` and everything built on them panic when handed an index
+stamped by a different arena. Indices built with `Index::from_raw_parts`,
+parsed from strings, or deserialized carry no id and are accepted by every
+arena, and deserialized arenas accept every index. The feature makes `Index`
+larger, so it is best enabled only for debugging.
+
+Some arenas share an id, so that indices handed out before they were made
+keep working: a clone shares its original's id, and so do the two arenas
+made by `split_off_slots`, `take` and `partition`. Each starts out handing
+out generations newer than any index it already holds, but once both have
+gone on inserting and removing, they can hand out equal indices, and using
+one with the wrong arena is not caught.
  */
 
 #![forbid(unsafe_code, missing_docs, missing_debug_implementations)]
@@ -146,32 +216,188 @@ generational-arena = { version = "0.2", features = ["serde"] }
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
         extern crate std;
+        use std::boxed::Box;
+        use std::rc::Rc;
         use std::vec::{self, Vec};
     } else {
         extern crate alloc;
+        use alloc::boxed::Box;
+        use alloc::rc::Rc;
         use alloc::vec::{self, Vec};
     }
 }
 
 use core::cmp;
-use core::iter::{self, Extend, FromIterator, FusedIterator};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{Extend, FromIterator, FusedIterator};
 use core::mem;
-use core::ops;
+use core::ops::{self, ControlFlow};
 use core::slice;
+use core::str;
 
 #[cfg(feature = "serde")]
 mod serde_impl;
+#[cfg(feature = "serde")]
+pub use serde_impl::MigrateArena;
+#[cfg(feature = "serde")]
+pub mod versioned;
+
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+
+#[cfg(feature = "slab")]
+mod slab_impl;
+
+mod macros;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
+
+pub mod prelude;
+
+mod arena_cell;
+pub use arena_cell::ArenaCell;
+
+mod arena_id;
+use arena_id::ArenaId;
+
+mod arena_pool;
+pub use arena_pool::ArenaPool;
+
+mod batch;
+pub use batch::{ArenaOp, BatchError};
+
+mod builder;
+pub use builder::{ArenaBuilder, ReusePolicy};
+
+mod chunks;
+pub use chunks::{Chunk, ChunkMut, Chunks, ChunksMut};
+
+mod diff;
+pub use diff::Diff;
+
+mod entries;
+pub use entries::{Entries, SlotView};
+
+#[cfg(feature = "futures")]
+mod futures_impl;
+#[cfg(feature = "futures")]
+pub use futures_impl::SnapshotStream;
+
+mod graph;
+pub use graph::{ArenaGraph, EdgeIndex, Neighbors, NodeIndex};
+
+mod insertion_order;
+pub use insertion_order::{InsertionOrder, InsertionOrderIter};
+
+#[cfg(feature = "std")]
+mod keyed_arena;
+#[cfg(feature = "std")]
+pub use keyed_arena::KeyedArena;
+
+mod observed;
+pub use observed::{ArenaHooks, ObservedArena};
+
+mod option_index;
+pub use option_index::OptionIndex;
+
+mod pairs;
+pub use pairs::Get2MutIter;
+
+mod pinned;
+
+mod raw_index;
+pub use raw_index::{RawIndex, TryFromIndexError};
+
+mod remap;
+pub use remap::IndexRemap;
+
+mod rc_arena;
+pub use rc_arena::{InUse, RcArena, StrongIndex};
+
+mod snapshot;
+pub use snapshot::Snapshot;
+
+mod soa_arena;
+pub use soa_arena::SoaArena;
+
+mod small_arena;
+pub use small_arena::SmallArena;
+
+#[cfg(feature = "std")]
+mod thread_local;
+#[cfg(feature = "std")]
+pub use thread_local::{ShardRouter, ThreadLocalArena};
+
+mod tree;
+pub use tree::{ArenaTree, Children};
+
+mod validate;
+pub use validate::{Corruption, CorruptionReport};
+
+#[cfg(feature = "std")]
+mod value_index;
+#[cfg(feature = "std")]
+pub use value_index::ValueIndex;
+
+mod zip;
+pub use zip::ZipIndicesOf;
 
 /// The `Arena` allows inserting and removing elements that are referred to by
 /// `Index`.
 ///
 /// [See the module-level documentation for example usage and motivation.](./index.html)
-#[derive(Clone, Debug)]
+///
+/// # Element addresses
+///
+/// Elements are stored inline, in one allocation. An element stays at the
+/// same address for as long as it is in the arena, unless either
+///
+/// * the arena's allocation changes, which only happens when its capacity
+///   changes, e.g. because an insertion found no room, or because of
+///   `reserve` or `truncate_capacity`; or
+/// * the element itself is moved, by `swap_remove` or `split_off_slots`.
+///
+/// In particular, inserting while there is spare capacity and removing other
+/// elements never move an element. `index_of_ref` relies on this to map a
+/// reference back to its element's `Index`.
+///
+/// # Thread safety
+///
+/// An `Arena<T>` is `Send` if `T` is `Send`, and `Sync` if `T` is `Sync`,
+/// just like a `Vec<T>`. An `Index` is `Send + Sync` regardless of `T`, as
+/// it is just a slot number and a generation, so it can be sent to another
+/// thread and used there with the arena it came from.
+///
+/// Mutating an arena from several places takes the usual tools: a `Mutex`
+/// or `RwLock` around the arena to share it between threads, or an
+/// `ArenaCell` to share it within one thread.
+#[derive(Debug)]
 pub struct Arena<T> {
     items: Vec<Entry<T>>,
     generation: u64,
     free_list_head: Option<usize>,
+    free_list_tail: Option<usize>,
+    // The free slots, most recently freed last, when reusing slots with
+    // `ReusePolicy::Stack`. Empty otherwise.
+    free_stack: Vec<usize>,
     len: usize,
+    // The lowest occupied slot through one past the highest, or `0..0` when
+    // the arena is empty, so iteration can skip free slots at either end.
+    occupied: ops::Range<usize>,
+    // One bit per slot, set while it is occupied, so that removing the lowest
+    // or highest element finds the next one a word at a time. Slots past its
+    // end are free.
+    occupancy: Vec<u64>,
+    reuse: ReusePolicy,
+    id: ArenaId,
+    removals: u64,
+    growth_percent: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -185,6 +411,11 @@ enum Entry<T> {
 /// To get an `Index`, insert an element into an `Arena`, and the `Index` for
 /// that element will be returned.
 ///
+/// Indices are ordered by slot first and generation second. This order is
+/// stable, so a range of `Index::min_for_slot(a)..=Index::max_for_slot(b)`
+/// over an index-keyed `BTreeMap` finds every entry in slots `a` through `b`,
+/// whatever their generation.
+///
 /// # Examples
 ///
 /// ```
@@ -194,13 +425,34 @@ enum Entry<T> {
 /// let idx = arena.insert(123);
 /// assert_eq!(arena[idx], 123);
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Index {
     index: usize,
     generation: u64,
+    // The arena that handed out this index, when the `arena-id` feature is
+    // enabled. Ignored by comparisons and hashing.
+    arena: ArenaId,
+}
+
+impl fmt::Debug for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Index")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
 }
 
 impl Index {
+    /// The size of an `Index` in bytes, in this build.
+    ///
+    /// `Index` has no fixed layout: its size depends on the platform's
+    /// pointer width and grows with the "arena-id" feature. Code that needs
+    /// a layout that never changes, such as FFI or file formats, should
+    /// convert indices to `RawIndex`, whose size is always
+    /// `RawIndex::SIZE_BYTES`.
+    pub const SIZE_BYTES: usize = mem::size_of::<Index>();
+
     /// Create a new `Index` from its raw parts.
     ///
     /// The parts must have been returned from an earlier call to
@@ -208,123 +460,72 @@ impl Index {
     ///
     /// Providing arbitrary values will lead to malformed indices and ultimately
     /// panics.
+    #[inline]
     pub fn from_raw_parts(a: usize, b: u64) -> Index {
         Index {
             index: a,
             generation: b,
+            arena: ArenaId::UNTAGGED,
         }
     }
 
-    /// Convert this `Index` into its raw parts.
-    ///
-    /// This niche method is useful for converting an `Index` into another
-    /// identifier type. Usually, you should prefer a newtype wrapper around
-    /// `Index` like `pub struct MyIdentifier(Index);`.  However, for external
-    /// types whose definition you can't customize, but which you can construct
-    /// instances of, this method can be useful.
-    pub fn into_raw_parts(self) -> (usize, u64) {
-        (self.index, self.generation)
-    }
-}
-
-const DEFAULT_CAPACITY: usize = 4;
-
-impl<T> Default for Arena<T> {
-    fn default() -> Arena<T> {
-        Arena::new()
-    }
-}
-
-impl<T> Arena<T> {
-    /// Constructs a new, empty `Arena`.
+    /// Create a new `Index` from its raw parts, checking that `arena` could
+    /// have handed it out.
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// use generational_arena::Arena;
-    ///
-    /// let mut arena = Arena::<usize>::new();
-    /// # let _ = arena;
-    /// ```
-    pub fn new() -> Arena<T> {
-        Arena::with_capacity(DEFAULT_CAPACITY)
-    }
-
-    /// Constructs a new, empty `Arena<T>` with the specified capacity.
+    /// Returns `None` unless `slot` is one of `arena`'s slots and
+    /// `generation` is not newer than that slot's: the generation of the
+    /// element in it, or the arena's current generation if the slot is
+    /// free. Unlike `from_raw_parts`, untrusted parts, e.g. read back from
+    /// disk or passed over FFI, can never produce an index that `arena`
+    /// misinterprets.
     ///
-    /// The `Arena<T>` will be able to hold `n` elements without further allocation.
+    /// A returned index may still be stale, in which case lookups with it
+    /// return `None` as usual.
     ///
     /// # Examples
     ///
     /// ```
-    /// use generational_arena::Arena;
-    ///
-    /// let mut arena = Arena::with_capacity(10);
+    /// use generational_arena::{Arena, Index};
     ///
-    /// // These insertions will not require further allocation.
-    /// for i in 0..10 {
-    ///     assert!(arena.try_insert(i).is_ok());
-    /// }
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert("a");
+    /// let (slot, generation) = idx.into_raw_parts();
     ///
-    /// // But now we are at capacity, and there is no more room.
-    /// assert!(arena.try_insert(99).is_err());
+    /// assert_eq!(Index::try_from_raw_parts(slot, generation, &arena), Some(idx));
+    /// assert_eq!(Index::try_from_raw_parts(slot + 1, generation, &arena), None);
+    /// assert_eq!(Index::try_from_raw_parts(slot, generation + 1, &arena), None);
     /// ```
-    pub fn with_capacity(n: usize) -> Arena<T> {
-        let n = cmp::max(n, 1);
-        let mut arena = Arena {
-            items: Vec::new(),
-            generation: 0,
-            free_list_head: None,
-            len: 0,
+    pub fn try_from_raw_parts<T>(slot: usize, generation: u64, arena: &Arena<T>) -> Option<Index> {
+        let newest = match arena.items.get(slot)? {
+            Entry::Occupied { generation, .. } => *generation,
+            Entry::Free { .. } => arena.generation,
         };
-        arena.reserve(n);
-        arena
+        if generation > newest {
+            return None;
+        }
+        Some(Index {
+            index: slot,
+            generation,
+            arena: arena.id,
+        })
     }
 
-    /// Clear all the items inside the arena, but keep its allocation.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// use generational_arena::Arena;
-    ///
-    /// let mut arena = Arena::with_capacity(1);
-    /// arena.insert(42);
-    /// arena.insert(43);
-    ///
-    /// arena.clear();
+    /// Convert this `Index` into its raw parts.
     ///
-    /// assert_eq!(arena.capacity(), 2);
-    /// ```
-    pub fn clear(&mut self) {
-        self.items.clear();
-
-        let end = self.items.capacity();
-        self.items.extend((0..end).map(|i| {
-            if i == end - 1 {
-                Entry::Free { next_free: None }
-            } else {
-                Entry::Free {
-                    next_free: Some(i + 1),
-                }
-            }
-        }));
-        if !self.is_empty() {
-            // Increment generation, but if there are no elements, do nothing to
-            // avoid unnecessary incrementing generation.
-            self.generation += 1;
-        }
-        self.free_list_head = Some(0);
-        self.len = 0;
+    /// This niche method is useful for converting an `Index` into another
+    /// identifier type. Usually, you should prefer a newtype wrapper around
+    /// `Index` like `pub struct MyIdentifier(Index);`.  However, for external
+    /// types whose definition you can't customize, but which you can construct
+    /// instances of, this method can be useful.
+    #[inline]
+    pub fn into_raw_parts(self) -> (usize, u64) {
+        (self.index, self.generation)
     }
 
-    /// Attempts to insert `value` into the arena using existing capacity.
+    /// Get the slot of this index, without its generation.
     ///
-    /// This method will never allocate new capacity in the arena.
-    ///
-    /// If insertion succeeds, then the `value`'s index is returned. If
-    /// insertion fails, then `Err(value)` is returned to give ownership of
-    /// `value` back to the caller.
+    /// This is what the unknown-generation methods of `Arena`, such as
+    /// `get_unknown_gen`, take.
     ///
     /// # Examples
     ///
@@ -332,40 +533,25 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
     ///
-    /// match arena.try_insert(42) {
-    ///     Ok(idx) => {
-    ///         // Insertion succeeded.
-    ///         assert_eq!(arena[idx], 42);
-    ///     }
-    ///     Err(x) => {
-    ///         // Insertion failed.
-    ///         assert_eq!(x, 42);
-    ///     }
-    /// };
+    /// assert_eq!(arena.get_unknown_gen(idx.slot()), Some((&42, idx)));
     /// ```
     #[inline]
-    pub fn try_insert(&mut self, value: T) -> Result<Index, T> {
-        match self.try_alloc_next_index() {
-            None => Err(value),
-            Some(index) => {
-                self.items[index.index] = Entry::Occupied {
-                    generation: self.generation,
-                    value,
-                };
-                Ok(index)
-            },
-        }
+    pub fn slot(self) -> Slot {
+        Slot(self.index)
     }
 
-    /// Attempts to insert the value returned by `create` into the arena using existing capacity.
-    /// `create` is called with the new value's associated index, allowing values that know their own index.
+    /// Pack this `Index` into a single `u64`, with the slot in the low 32
+    /// bits and the generation in the high 32 bits.
     ///
-    /// This method will never allocate new capacity in the arena.
+    /// This is the same layout as `RawIndex`, and is handy for storing
+    /// indices in GPU buffers, FFI structs, and other places that want plain
+    /// integers.
     ///
-    /// If insertion succeeds, then the new index is returned. If
-    /// insertion fails, then `Err(create)` is returned to give ownership of
-    /// `create` back to the caller.
+    /// Returns an error if the slot or the generation does not fit in 32
+    /// bits, as the generation will once an arena has seen more than
+    /// `u32::MAX` removals.
     ///
     /// # Examples
     ///
@@ -373,130 +559,2283 @@ impl<T> Arena<T> {
     /// use generational_arena::{Arena, Index};
     ///
     /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
     ///
-    /// match arena.try_insert_with(|idx| (42, idx)) {
-    ///     Ok(idx) => {
-    ///         // Insertion succeeded.
-    ///         assert_eq!(arena[idx].0, 42);
-    ///         assert_eq!(arena[idx].1, idx);
-    ///     }
-    ///     Err(x) => {
-    ///         // Insertion failed.
-    ///     }
-    /// };
+    /// let bits = idx.to_bits().unwrap();
+    /// assert_eq!(Index::from_bits(bits), Some(idx));
+    ///
+    /// let old = Index::from_raw_parts(0, u64::from(u32::MAX) + 1);
+    /// assert!(old.to_bits().is_err());
     /// ```
-    #[inline]
-    pub fn try_insert_with<F: FnOnce(Index) -> T>(&mut self, create: F) -> Result<Index, F> {
-        match self.try_alloc_next_index() {
-            None => Err(create),
-            Some(index) => {
-                self.items[index.index] = Entry::Occupied {
-                    generation: self.generation,
-                    value: create(index),
-                };
-                Ok(index)
-            },
-        }
+    pub fn to_bits(self) -> Result<u64, TryFromIndexError> {
+        RawIndex::try_from(self).map(RawIndex::to_bits)
     }
 
-    #[inline]
-    fn try_alloc_next_index(&mut self) -> Option<Index> {
-        match self.free_list_head {
-            None => None,
-            Some(i) => match self.items[i] {
-                Entry::Occupied { .. } => panic!("corrupt free list"),
-                Entry::Free { next_free } => {
-                    self.free_list_head = next_free;
-                    self.len += 1;
-                    Some(Index {
-                        index: i,
-                        generation: self.generation,
-                    })
-                }
-            }
-        }
+    /// Unpack an `Index` from the `u64` layout produced by `to_bits`.
+    ///
+    /// Returns `None` if the slot in the low 32 bits does not fit in a
+    /// `usize` on this platform.
+    pub fn from_bits(bits: u64) -> Option<Index> {
+        let raw = RawIndex::from_bits(bits);
+        let index = usize::try_from(raw.slot()).ok()?;
+        Some(Index {
+            index,
+            generation: u64::from(raw.generation()),
+            arena: ArenaId::UNTAGGED,
+        })
     }
 
-    /// Insert `value` into the arena, allocating more capacity if necessary.
-    ///
-    /// The `value`'s associated index in the arena is returned.
+    /// The smallest `Index` in slot `slot`, i.e. the one with the oldest
+    /// possible generation.
     ///
     /// # Examples
     ///
     /// ```
-    /// use generational_arena::Arena;
+    /// use generational_arena::{Arena, Index};
+    /// use std::collections::BTreeMap;
     ///
     /// let mut arena = Arena::new();
+    /// let mut labels = BTreeMap::new();
+    /// for n in 0..10 {
+    ///     labels.insert(arena.insert(n), format!("#{}", n));
+    /// }
     ///
-    /// let idx = arena.insert(42);
-    /// assert_eq!(arena[idx], 42);
+    /// let in_slots_3_to_5: Vec<_> = labels
+    ///     .range(Index::min_for_slot(3)..=Index::max_for_slot(5))
+    ///     .map(|(_, label)| label.as_str())
+    ///     .collect();
+    /// assert_eq!(in_slots_3_to_5, ["#3", "#4", "#5"]);
     /// ```
+    pub fn min_for_slot(slot: usize) -> Index {
+        Index::from_raw_parts(slot, 0)
+    }
+
+    /// The largest `Index` in slot `slot`, i.e. the one with the newest
+    /// possible generation.
+    pub fn max_for_slot(slot: usize) -> Index {
+        Index::from_raw_parts(slot, u64::MAX)
+    }
+
+    /// Panic if this index was handed out by an arena other than `arena`.
+    /// This can only happen with the `arena-id` feature enabled.
     #[inline]
-    pub fn insert(&mut self, value: T) -> Index {
-        match self.try_insert(value) {
-            Ok(i) => i,
-            Err(value) => self.insert_slow_path(value),
+    fn check_arena(self, arena: ArenaId) {
+        if !self.arena.matches(arena) {
+            wrong_arena(self);
         }
     }
+}
 
-    /// Insert the value returned by `create` into the arena, allocating more capacity if necessary.
-    /// `create` is called with the new value's associated index, allowing values that know their own index.
-    ///
+// Kept out of line so that the checks on hot lookups stay small.
+#[cold]
+#[inline(never)]
+fn wrong_arena(index: Index) -> ! {
+    panic!("{:?} was created by a different arena", index);
+}
+
+/// A slot of an arena, without a generation.
+///
+/// The unknown-generation methods of `Arena` look elements up by slot alone,
+/// for indexing schemes such as hierarchical bitmaps that track slots rather
+/// than `Index`es. Getting a `Slot` from `Index::slot` instead of passing a
+/// bare `usize` keeps lengths and other unrelated integers from being mixed
+/// up with slots. Those methods still accept a `usize`, too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slot(usize);
+
+impl Slot {
+    /// Create a `Slot` from its raw position in the arena's storage.
+    #[inline]
+    pub fn new(slot: usize) -> Slot {
+        Slot(slot)
+    }
+
+    /// Get the raw position of this slot in the arena's storage.
+    #[inline]
+    pub fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for Slot {
+    fn from(slot: usize) -> Slot {
+        Slot(slot)
+    }
+}
+
+impl From<Slot> for usize {
+    fn from(slot: Slot) -> usize {
+        slot.0
+    }
+}
+
+/// Formats an `Index` as `<slot>v<generation>`, e.g. `12v3`.
+///
+/// The output can be parsed back into the same `Index` with `str::parse`.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::Index;
+///
+/// let idx = Index::from_raw_parts(12, 3);
+/// assert_eq!(idx.to_string(), "12v3");
+/// assert_eq!(idx.to_string().parse::<Index>(), Ok(idx));
+/// ```
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+impl str::FromStr for Index {
+    type Err = ParseIndexError;
+
+    fn from_str(s: &str) -> Result<Index, ParseIndexError> {
+        let mut parts = s.splitn(2, 'v');
+        let index = parts.next().ok_or(ParseIndexError { _private: () })?;
+        let generation = parts.next().ok_or(ParseIndexError { _private: () })?;
+        // Integer parsing accepts a leading `+`, which `Display` never
+        // writes, so only let digits through.
+        let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if !digits(index) || !digits(generation) {
+            return Err(ParseIndexError { _private: () });
+        }
+        match (index.parse(), generation.parse()) {
+            (Ok(index), Ok(generation)) => Ok(Index {
+                index,
+                generation,
+                arena: ArenaId::UNTAGGED,
+            }),
+            _ => Err(ParseIndexError { _private: () }),
+        }
+    }
+}
+
+/// An error returned when parsing an `Index` from a string fails.
+///
+/// Indices are parsed from the `<slot>v<generation>` format produced by their
+/// `Display` implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseIndexError {
+    _private: (),
+}
+
+impl fmt::Display for ParseIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid arena index, expected `<slot>v<generation>`")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIndexError {}
+
+/// An error returned when there is no element at a requested `Index`.
+///
+/// Besides the requested index, it records what the arena's slot held at
+/// the time, to help track down where a dangling index came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LookupError {
+    index: Index,
+    state: SlotState,
+}
+
+/// What an arena's slot held when a lookup in it failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotState {
+    /// The slot has never been used.
+    OutOfBounds {
+        /// The number of slots the arena has used, as reported by
+        /// `Arena::slot_count`.
+        slot_count: usize,
+    },
+    /// The slot is free.
+    Free,
+    /// The slot holds an element of another generation.
+    Occupied {
+        /// The generation of the element in the slot.
+        generation: u64,
+    },
+}
+
+impl LookupError {
+    fn new<T>(index: Index, items: &[Entry<T>]) -> LookupError {
+        let state = match items.get(index.index) {
+            None => SlotState::OutOfBounds {
+                slot_count: items.len(),
+            },
+            Some(Entry::Free { .. }) => SlotState::Free,
+            Some(Entry::Occupied { generation, .. }) => SlotState::Occupied {
+                generation: *generation,
+            },
+        };
+        LookupError { index, state }
+    }
+
+    /// Get the `Index` that was looked up.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    /// Get what the slot held when the lookup failed.
+    pub fn state(&self) -> SlotState {
+        self.state
+    }
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no element at index {}: ", self.index)?;
+        match self.state {
+            SlotState::OutOfBounds { slot_count } => {
+                write!(f, "the arena only has {} slots", slot_count)
+            }
+            SlotState::Free => write!(f, "slot {} is free", self.index.index),
+            SlotState::Occupied { generation } => write!(
+                f,
+                "slot {} holds generation {}",
+                self.index.index, generation
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LookupError {}
+
+/// An error returned by `Arena::truncate_capacity` when an element lives in
+/// a slot that would be cut off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TruncateCapacityError {
+    index: Index,
+}
+
+impl TruncateCapacityError {
+    /// Get the `Index` of the first element above the requested capacity.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+}
+
+impl fmt::Display for TruncateCapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot truncate arena capacity: element {} is above the cutoff",
+            self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TruncateCapacityError {}
+
+/// An error returned by `Arena::try_insert_grow` when the arena is full and
+/// cannot grow any further.
+///
+/// It gives back the value that could not be inserted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    value: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Get back the value that could not be inserted.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+// Like `Vec`'s errors, don't require `T: Debug` just to report the error.
+impl<T> fmt::Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CapacityError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "arena is full and cannot grow any further")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for CapacityError<T> {}
+
+const DEFAULT_CAPACITY: usize = 4;
+const DEFAULT_GROWTH_PERCENT: usize = 200;
+
+/// The number of `u64` words of `Arena::occupancy` that cover `slots` slots.
+fn occupancy_words(slots: usize) -> usize {
+    slots.div_ceil(64)
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Arena<T> {
+        Arena::new()
+    }
+}
+
+impl<T: Clone> Clone for Arena<T> {
+    fn clone(&self) -> Arena<T> {
+        // Cloning the `Vec` directly would drop its spare capacity, and with
+        // it every slot that has not been used yet.
+        let mut items = Vec::with_capacity(self.items.capacity());
+        items.extend(self.items.iter().cloned());
+        let mut occupancy = Vec::with_capacity(self.occupancy.capacity());
+        occupancy.extend_from_slice(&self.occupancy);
+        Arena {
+            items,
+            generation: self.generation,
+            free_list_head: self.free_list_head,
+            free_list_tail: self.free_list_tail,
+            free_stack: self.free_stack.clone(),
+            len: self.len,
+            occupied: self.occupied.clone(),
+            occupancy,
+            reuse: self.reuse,
+            id: self.id,
+            removals: self.removals,
+            growth_percent: self.growth_percent,
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    /// Constructs a new, empty `Arena`.
+    ///
+    /// This does not allocate: the first insertion does. Since it is a
+    /// `const fn`, an arena can be created in a `static` item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::sync::Mutex;
+    ///
+    /// static NAMES: Mutex<Arena<&str>> = Mutex::new(Arena::new());
+    ///
+    /// let mut names = NAMES.lock().unwrap();
+    /// assert_eq!(names.capacity(), 0);
+    /// let idx = names.insert("alice");
+    /// assert_eq!(names[idx], "alice");
+    /// ```
+    pub const fn new() -> Arena<T> {
+        Arena {
+            items: Vec::new(),
+            generation: 0,
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len: 0,
+            occupied: 0..0,
+            occupancy: Vec::new(),
+            reuse: ReusePolicy::Lifo,
+            id: ArenaId::PENDING,
+            removals: 0,
+            growth_percent: DEFAULT_GROWTH_PERCENT,
+        }
+    }
+
+    /// Constructs a new, empty `Arena<T>` with the specified capacity.
+    ///
+    /// The `Arena<T>` will be able to hold `n` elements without further allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(10);
+    ///
+    /// // These insertions will not require further allocation.
+    /// for i in 0..10 {
+    ///     assert!(arena.try_insert(i).is_ok());
+    /// }
+    ///
+    /// // But now we are at capacity, and there is no more room.
+    /// assert!(arena.try_insert(99).is_err());
+    /// ```
+    pub fn with_capacity(n: usize) -> Arena<T> {
+        Arena::with_capacity_and_reuse(n, ReusePolicy::default())
+    }
+
+    /// Constructs a new, empty `Arena<T>` whose generation counter starts at
+    /// `generation` instead of zero.
+    ///
+    /// Generations only ever increase, so arenas whose counters start far
+    /// enough apart never hand out the same `Index`. This lets entities be
+    /// sharded across several arenas while keeping every handle distinct,
+    /// e.g. by starting shard `k` at generation `k << 48`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut shard_a = Arena::with_generation_start(0);
+    /// let mut shard_b = Arena::with_generation_start(1 << 48);
+    ///
+    /// // Same slot, but distinguishable handles.
+    /// let a = shard_a.insert("a");
+    /// let b = shard_b.insert("b");
+    /// assert_eq!(a.into_raw_parts().0, b.into_raw_parts().0);
+    /// assert_ne!(a, b);
+    /// assert_eq!(b.into_raw_parts().1, 1 << 48);
+    /// ```
+    pub fn with_generation_start(generation: u64) -> Arena<T> {
+        Arena::builder().generation_start(generation).build()
+    }
+
+    fn with_capacity_and_reuse(n: usize, reuse: ReusePolicy) -> Arena<T> {
+        let n = cmp::max(n, 1);
+        Arena {
+            items: Vec::with_capacity(n),
+            generation: 0,
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len: 0,
+            occupied: 0..0,
+            occupancy: Vec::with_capacity(occupancy_words(n)),
+            reuse,
+            id: ArenaId::fresh(),
+            removals: 0,
+            growth_percent: DEFAULT_GROWTH_PERCENT,
+        }
+    }
+
+    /// Clear all the items inside the arena, but keep its allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(1);
+    /// arena.insert(42);
+    /// arena.insert(43);
+    ///
+    /// arena.clear();
+    ///
+    /// assert_eq!(arena.capacity(), 2);
+    /// ```
+    pub fn clear(&mut self) {
+        self.items.clear();
+        if !self.is_empty() {
+            // Increment generation, but if there are no elements, do nothing to
+            // avoid unnecessary incrementing generation.
+            self.generation += 1;
+        }
+        self.removals += self.len as u64;
+        self.free_list_head = None;
+        self.free_list_tail = None;
+        self.free_stack.clear();
+        self.len = 0;
+        self.occupied = 0..0;
+        self.occupancy.clear();
+    }
+
+    /// Attempts to insert `value` into the arena using existing capacity.
+    ///
+    /// This method will never allocate new capacity in the arena.
+    ///
+    /// If insertion succeeds, then the `value`'s index is returned. If
+    /// insertion fails, then `Err(value)` is returned to give ownership of
+    /// `value` back to the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// match arena.try_insert(42) {
+    ///     Ok(idx) => {
+    ///         // Insertion succeeded.
+    ///         assert_eq!(arena[idx], 42);
+    ///     }
+    ///     Err(x) => {
+    ///         // Insertion failed.
+    ///         assert_eq!(x, 42);
+    ///     }
+    /// };
+    /// ```
+    #[inline]
+    pub fn try_insert(&mut self, value: T) -> Result<Index, T> {
+        match self.try_alloc_next_index() {
+            None => Err(value),
+            Some(index) => {
+                self.items[index.index] = Entry::Occupied {
+                    generation: self.generation,
+                    value,
+                };
+                Ok(index)
+            },
+        }
+    }
+
+    /// Attempts to insert the value returned by `create` into the arena using existing capacity.
+    /// `create` is called with the new value's associated index, allowing values that know their own index.
+    ///
+    /// This method will never allocate new capacity in the arena.
+    ///
+    /// If insertion succeeds, then the new index is returned. If
+    /// insertion fails, then `Err(create)` is returned to give ownership of
+    /// `create` back to the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, Index};
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// match arena.try_insert_with(|idx| (42, idx)) {
+    ///     Ok(idx) => {
+    ///         // Insertion succeeded.
+    ///         assert_eq!(arena[idx].0, 42);
+    ///         assert_eq!(arena[idx].1, idx);
+    ///     }
+    ///     Err(x) => {
+    ///         // Insertion failed.
+    ///     }
+    /// };
+    /// ```
+    #[inline]
+    pub fn try_insert_with<F: FnOnce(Index) -> T>(&mut self, create: F) -> Result<Index, F> {
+        match self.try_alloc_next_index() {
+            None => Err(create),
+            Some(index) => {
+                self.items[index.index] = Entry::Occupied {
+                    generation: self.generation,
+                    value: create(index),
+                };
+                Ok(index)
+            },
+        }
+    }
+
+    #[inline]
+    fn try_alloc_next_index(&mut self) -> Option<Index> {
+        let i = match self.reuse {
+            ReusePolicy::Lifo => self.pop_free().or_else(|| self.materialize_slot())?,
+            ReusePolicy::Fifo => self.materialize_slot().or_else(|| self.pop_free())?,
+            ReusePolicy::Stack => self.free_stack.pop().or_else(|| self.materialize_slot())?,
+        };
+        self.len += 1;
+        self.occupied = if self.occupied.is_empty() {
+            i..i + 1
+        } else {
+            cmp::min(self.occupied.start, i)..cmp::max(self.occupied.end, i + 1)
+        };
+        if self.occupancy.len() <= i / 64 {
+            self.occupancy.resize(i / 64 + 1, 0);
+        }
+        self.occupancy[i / 64] |= 1 << (i % 64);
+        Some(Index {
+            index: i,
+            generation: self.generation,
+            arena: self.id,
+        })
+    }
+
+    /// Take the slot at the head of the free list, if any.
+    #[inline]
+    fn pop_free(&mut self) -> Option<usize> {
+        let i = self.free_list_head?;
+        match self.items[i] {
+            Entry::Occupied { .. } => panic!("corrupt free list"),
+            Entry::Free { next_free } => {
+                self.free_list_head = next_free;
+                if next_free.is_none() {
+                    self.free_list_tail = None;
+                }
+                Some(i)
+            }
+        }
+    }
+
+    /// Append a never-used slot from the spare capacity of `items`, if it
+    /// has any, without reallocating.
+    #[inline]
+    fn materialize_slot(&mut self) -> Option<usize> {
+        if self.items.len() == self.items.capacity() {
+            return None;
+        }
+        self.items.push(Entry::Free { next_free: None });
+        Some(self.items.len() - 1)
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary.
+    ///
+    /// The `value`'s associated index in the arena is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena[idx], 42);
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, value: T) -> Index {
+        match self.try_insert(value) {
+            Ok(i) => i,
+            Err(value) => self.insert_slow_path(value),
+        }
+    }
+
+    /// Insert the value returned by `create` into the arena, allocating more capacity if necessary.
+    /// `create` is called with the new value's associated index, allowing values that know their own index.
+    ///
     /// The new value's associated index in the arena is returned.
     ///
     /// # Examples
     ///
     /// ```
-    /// use generational_arena::{Arena, Index};
+    /// use generational_arena::{Arena, Index};
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// let idx = arena.insert_with(|idx| (42, idx));
+    /// assert_eq!(arena[idx].0, 42);
+    /// assert_eq!(arena[idx].1, idx);
+    /// ```
+    #[inline]
+    pub fn insert_with(&mut self, create: impl FnOnce(Index) -> T) -> Index {
+        match self.try_insert_with(create) {
+            Ok(i) => i,
+            Err(create) => self.insert_with_slow_path(create),
+        }
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary,
+    /// and return its index along with an exclusive reference to it.
+    ///
+    /// This saves a second lookup when the element needs more setup right
+    /// after being inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    ///
+    /// let (idx, names) = arena.insert_ref(vec![]);
+    /// names.push("alice");
+    /// assert_eq!(arena[idx], ["alice"]);
+    /// ```
+    pub fn insert_ref(&mut self, value: T) -> (Index, &mut T) {
+        let i = self.insert(value);
+        match &mut self.items[i.index] {
+            Entry::Occupied { value, .. } => (i, value),
+            Entry::Free { .. } => unreachable!(),
+        }
+    }
+
+    /// Insert a default value into the arena, allocating more capacity if
+    /// necessary, and return its index along with an exclusive reference to
+    /// it, so that it can be initialized in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// #[derive(Default)]
+    /// struct Player {
+    ///     name: String,
+    ///     score: u32,
+    /// }
+    ///
+    /// let mut arena = Arena::<Player>::new();
+    ///
+    /// let (idx, player) = arena.alloc();
+    /// player.name.push_str("alice");
+    /// player.score = 10;
+    /// assert_eq!(arena[idx].name, "alice");
+    /// assert_eq!(arena[idx].score, 10);
+    /// ```
+    pub fn alloc(&mut self) -> (Index, &mut T)
+    where
+        T: Default,
+    {
+        self.insert_ref(T::default())
+    }
+
+    #[inline(never)]
+    fn insert_slow_path(&mut self, value: T) -> Index {
+        self.reserve(1);
+        self.try_insert(value)
+            .map_err(|_| ())
+            .expect("inserting will always succeed after reserving additional space")
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary,
+    /// or give it back if the arena cannot grow.
+    ///
+    /// `insert` panics when the arena's capacity would overflow, either
+    /// because the number of slots no longer fits in a `usize` or because the
+    /// storage would exceed `isize::MAX` bytes, as with a `Vec`. This reports
+    /// either as a `CapacityError` instead. If the arena cannot grow by its
+    /// usual growth percentage but can still grow by one slot, it does that,
+    /// so the whole index space can be used. Failing to allocate memory for
+    /// a capacity that would otherwise be allowed is also reported as an
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(1);
+    /// arena.insert(1);
+    ///
+    /// let idx = arena.try_insert_grow(2).unwrap();
+    /// assert_eq!(arena[idx], 2);
+    /// assert_eq!(arena.capacity(), 2);
+    /// ```
+    pub fn try_insert_grow(&mut self, value: T) -> Result<Index, CapacityError<T>> {
+        let value = match self.try_insert(value) {
+            Ok(i) => return Ok(i),
+            Err(value) => value,
+        };
+        let amortized = self.growth_for(1);
+        if !self.try_grow(amortized) && !self.try_grow(1) {
+            return Err(CapacityError { value });
+        }
+        Ok(self
+            .try_insert(value)
+            .map_err(|_| ())
+            .expect("inserting will always succeed after growing"))
+    }
+
+    #[inline(never)]
+    fn insert_with_slow_path(&mut self, create: impl FnOnce(Index) -> T) -> Index {
+        self.reserve(1);
+        self.try_insert_with(create)
+            .map_err(|_| ())
+            .expect("inserting will always succeed after reserving additional space")
+    }
+
+    /// Replace the element at index `i` with the result of `f`, or insert a
+    /// new one if it is gone, returning the index of the result.
+    ///
+    /// If the element at index `i` is in the arena, `f` is called with it,
+    /// and what `f` returns takes its place under the same index, which is
+    /// returned. Otherwise `f` is called with `None` and what it returns is
+    /// inserted, and the new index is returned. This turns a stale handle
+    /// into a fresh one in a single call.
+    ///
+    /// If `f` panics while transforming an element, that element is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut cache = Arena::new();
+    /// let idx = cache.insert(1);
+    ///
+    /// let bump = |old: Option<i32>| old.map_or(0, |n| n + 1);
+    /// assert_eq!(cache.replace_with(idx, bump), idx);
+    /// assert_eq!(cache[idx], 2);
+    ///
+    /// cache.remove(idx);
+    /// let fresh = cache.replace_with(idx, bump);
+    /// assert_ne!(fresh, idx);
+    /// assert_eq!(cache[fresh], 0);
+    /// ```
+    pub fn replace_with(&mut self, i: Index, f: impl FnOnce(Option<T>) -> T) -> Index {
+        /// Finishes removing the element whose slot was emptied for `f`, in
+        /// case `f` panics.
+        struct Vacated<'a, T> {
+            arena: &'a mut Arena<T>,
+            slot: usize,
+            refilled: bool,
+        }
+
+        impl<'a, T> Drop for Vacated<'a, T> {
+            fn drop(&mut self) {
+                if !self.refilled {
+                    self.arena.generation += 1;
+                    self.arena.push_free(self.slot);
+                    self.arena.len -= 1;
+                    self.arena.removals += 1;
+                }
+            }
+        }
+
+        self.check_arena(i);
+        match self.items.get(i.index) {
+            Some(Entry::Occupied { generation, .. }) if *generation == i.generation => {}
+            _ => return self.insert(f(None)),
+        }
+        let old = match mem::replace(&mut self.items[i.index], Entry::Free { next_free: None }) {
+            Entry::Occupied { value, .. } => value,
+            Entry::Free { .. } => unreachable!(),
+        };
+
+        let mut vacated = Vacated {
+            arena: self,
+            slot: i.index,
+            refilled: false,
+        };
+        let value = f(Some(old));
+        vacated.arena.items[i.index] = Entry::Occupied {
+            generation: i.generation,
+            value,
+        };
+        vacated.refilled = true;
+        i
+    }
+
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// If the element at index `i` is still in the arena, then it is
+    /// returned. If it is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert_eq!(arena.remove(idx), None);
+    /// ```
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        self.remove_and_bump(i, true)
+    }
+
+    /// Remove the element at index `i` from the arena without advancing the
+    /// arena's generation.
+    ///
+    /// This is for hot paths that churn through elements and never keep an
+    /// `Index` around after removing its element. Normally every removal
+    /// advances the generation, so that a stale index cannot refer to a later
+    /// element. This skips that, so a stale copy of `i`, or of any other
+    /// index removed since the generation last advanced, may refer to the
+    /// next element inserted into the same slot. That is a logic error, but
+    /// not undefined behavior.
+    ///
+    /// If the element at index `i` is still in the arena, then it is
+    /// returned. If it is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let old = arena.insert("spark");
+    /// assert_eq!(arena.remove_reusable(old), Some("spark"));
+    ///
+    /// // The slot is reused with the same generation, so the stale index
+    /// // refers to the new element.
+    /// let new = arena.insert("ember");
+    /// assert_eq!(new, old);
+    /// assert_eq!(arena[old], "ember");
+    /// ```
+    pub fn remove_reusable(&mut self, i: Index) -> Option<T> {
+        self.remove_and_bump(i, false)
+    }
+
+    fn remove_and_bump(&mut self, i: Index, bump: bool) -> Option<T> {
+        self.check_arena(i);
+        if i.index >= self.items.len() {
+            return None;
+        }
+
+        match self.items[i.index] {
+            Entry::Occupied { generation, .. } if i.generation == generation => {
+                let entry = mem::replace(
+                    &mut self.items[i.index],
+                    Entry::Free { next_free: None },
+                );
+                if bump {
+                    self.generation += 1;
+                }
+                self.push_free(i.index);
+                self.len -= 1;
+                self.removals += 1;
+
+                match entry {
+                    Entry::Occupied { generation: _, value } => Some(value),
+                    _ => unreachable!(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Remove the element at index `i` from the arena, filling its slot with
+    /// the element in the highest occupied slot.
+    ///
+    /// This keeps the elements packed into the lowest slots, so an arena that
+    /// is only ever removed from this way never has gaps to skip over. The
+    /// cost is that the moved element gets a new index. If an element was
+    /// moved, its old and new indices are returned alongside the removed
+    /// value as `(moved_from, moved_to)`, so that the caller can patch any
+    /// references to it. Its old index is no longer valid.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    ///
+    /// let (value, moved) = arena.swap_remove(a).unwrap();
+    /// assert_eq!(value, 'a');
+    /// let (moved_from, moved_to) = moved.unwrap();
+    /// assert_eq!(moved_from, c);
+    /// assert_eq!(arena[moved_to], 'c');
+    /// assert_eq!(moved_to.into_raw_parts().0, a.into_raw_parts().0);
+    /// assert!(!arena.contains(c));
+    ///
+    /// // Removing the highest element moves nothing.
+    /// assert_eq!(arena.swap_remove(b), Some(('b', None)));
+    /// assert_eq!(arena.slot_count(), 1);
+    /// ```
+    pub fn swap_remove(&mut self, i: Index) -> Option<(T, Option<(Index, Index)>)> {
+        self.check_arena(i);
+        match self.items.get(i.index) {
+            Some(Entry::Occupied { generation, .. }) if *generation == i.generation => {}
+            _ => return None,
+        }
+
+        self.generation += 1;
+        self.len -= 1;
+        self.removals += 1;
+
+        let last = self.occupied.end - 1;
+        let (value, moved) = if last > i.index {
+            let (generation, moved_value) =
+                match mem::replace(&mut self.items[last], Entry::Free { next_free: None }) {
+                    Entry::Occupied { generation, value } => (generation, value),
+                    Entry::Free { .. } => unreachable!(),
+                };
+            // The moved element gets a fresh generation, since a stale index
+            // into its new slot may have the generation it had before.
+            let new_entry = Entry::Occupied {
+                generation: self.generation,
+                value: moved_value,
+            };
+            let removed = match mem::replace(&mut self.items[i.index], new_entry) {
+                Entry::Occupied { value, .. } => value,
+                Entry::Free { .. } => unreachable!(),
+            };
+            let moved_from = Index {
+                index: last,
+                generation,
+                arena: self.id,
+            };
+            let moved_to = Index {
+                index: i.index,
+                generation: self.generation,
+                arena: self.id,
+            };
+            (removed, Some((moved_from, moved_to)))
+        } else {
+            match mem::replace(&mut self.items[last], Entry::Free { next_free: None }) {
+                Entry::Occupied { value, .. } => (value, None),
+                Entry::Free { .. } => unreachable!(),
+            }
+        };
+
+        // Every slot above `last` is already free, so if `last` is the
+        // highest slot it can go back to being spare capacity.
+        if last + 1 == self.items.len() {
+            self.items.pop();
+            self.vacate(last);
+        } else {
+            self.push_free(last);
+        }
+        Some((value, moved))
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all indices such that `predicate(index, &value)` returns `false`.
+    ///
+    /// Elements are visited in ascending slot order. To visit them in
+    /// descending slot order instead, use `retain_rev`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut crew = Arena::new();
+    /// crew.extend(&["Jim Hawkins", "John Silver", "Alexander Smollett", "Israel Hands"]);
+    /// let pirates = ["John Silver", "Israel Hands"]; // too dangerous to keep them around
+    /// crew.retain(|_index, member| !pirates.contains(member));
+    /// let mut crew_members = crew.iter().map(|(_, member)| **member);
+    /// assert_eq!(crew_members.next(), Some("Jim Hawkins"));
+    /// assert_eq!(crew_members.next(), Some("Alexander Smollett"));
+    /// assert!(crew_members.next().is_none());
+    /// ```
+    pub fn retain(&mut self, mut predicate: impl FnMut(Index, &mut T) -> bool) {
+        self.retain_until(|index, value| ControlFlow::Continue(predicate(index, value)));
+    }
+
+    /// Retains only the elements whose indices the predicate selects.
+    ///
+    /// This is `retain` for predicates that only need the index, e.g. to
+    /// look it up in some other structure, and never see the elements.
+    ///
+    /// Elements are visited in ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::collections::HashSet;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    ///
+    /// let live: HashSet<_> = vec![b].into_iter().collect();
+    /// arena.retain_indices(|idx| live.contains(&idx));
+    /// assert!(!arena.contains(a));
+    /// assert!(arena.contains(b));
+    /// ```
+    pub fn retain_indices(&mut self, mut predicate: impl FnMut(Index) -> bool) {
+        self.retain(|index, _| predicate(index));
+    }
+
+    /// Retains only the elements specified by the predicate, stopping early
+    /// if the predicate asks to.
+    ///
+    /// Elements are visited in ascending slot order. For each one, the
+    /// predicate returns either `ControlFlow::Continue(keep)`, which removes
+    /// the element if `keep` is `false` and moves on to the next one, or
+    /// `ControlFlow::Break(())`, which keeps the element and stops. Elements
+    /// that are not visited are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..10);
+    ///
+    /// // Prune small elements from the front, stopping at the first big one.
+    /// arena.retain_until(|_, n| {
+    ///     if *n >= 3 {
+    ///         ControlFlow::Break(())
+    ///     } else {
+    ///         ControlFlow::Continue(false)
+    ///     }
+    /// });
+    /// assert_eq!(arena.len(), 7);
+    /// ```
+    pub fn retain_until(
+        &mut self,
+        predicate: impl FnMut(Index, &mut T) -> ControlFlow<(), bool>,
+    ) {
+        self.retain_slots(self.occupied.clone(), predicate);
+    }
+
+    /// Retains only the elements specified by the predicate, visiting them
+    /// in descending slot order.
+    ///
+    /// This is `retain`, but starting from the highest slot. As long as
+    /// nothing has been removed, slots are handed out in ascending order, so
+    /// this visits the most recently inserted elements first. Once removed
+    /// elements' slots are reused, slot order and insertion order part ways;
+    /// an `InsertionOrder` tracks the latter exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut undo = Arena::new();
+    /// undo.extend(vec!["open", "edit", "save"].into_iter());
+    ///
+    /// let mut dropped = vec![];
+    /// undo.retain_rev(|_, step| {
+    ///     dropped.push(*step);
+    ///     false
+    /// });
+    /// assert_eq!(dropped, ["save", "edit", "open"]);
+    /// ```
+    pub fn retain_rev(&mut self, mut predicate: impl FnMut(Index, &mut T) -> bool) {
+        self.retain_slots(self.occupied.clone().rev(), |index, value| {
+            ControlFlow::Continue(predicate(index, value))
+        });
+    }
+
+    /// Visit the elements in `slots`, in the order given, removing those the
+    /// predicate rejects, as described by `retain_until`.
+    fn retain_slots(
+        &mut self,
+        slots: impl Iterator<Item = usize>,
+        mut predicate: impl FnMut(Index, &mut T) -> ControlFlow<(), bool>,
+    ) {
+        let mut bumped = false;
+        for i in slots {
+            match &mut self.items[i] {
+                Entry::Occupied { generation, value } => {
+                    let index = Index {
+                        index: i,
+                        generation: *generation,
+                        arena: self.id,
+                    };
+                    match predicate(index, value) {
+                        ControlFlow::Continue(true) => continue,
+                        ControlFlow::Continue(false) => {}
+                        ControlFlow::Break(()) => return,
+                    }
+                }
+                Entry::Free { .. } => continue,
+            }
+            if !bumped {
+                // As in `remove_where`, one bump covers every removal.
+                self.generation += 1;
+                bumped = true;
+            }
+            self.items[i] = Entry::Free { next_free: None };
+            self.push_free(i);
+            self.len -= 1;
+            self.removals += 1;
+        }
+    }
+
+    /// Remove every element that the predicate selects, returning them along
+    /// with their indices.
+    ///
+    /// This is the opposite of `retain`: elements for which the predicate
+    /// returns `true` are removed. They are returned in ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    ///
+    /// let odd = arena.remove_where(|_, n| *n % 2 == 1);
+    /// assert_eq!(odd, [(indices[1], 1), (indices[3], 3), (indices[5], 5)]);
+    /// assert_eq!(arena.len(), 3);
+    /// ```
+    pub fn remove_where(
+        &mut self,
+        mut predicate: impl FnMut(Index, &mut T) -> bool,
+    ) -> Vec<(Index, T)> {
+        let mut removed = Vec::new();
+        for i in self.occupied.clone() {
+            let index = match &mut self.items[i] {
+                Entry::Occupied { generation, value } => {
+                    let index = Index {
+                        index: i,
+                        generation: *generation,
+                        arena: self.id,
+                    };
+                    if !predicate(index, value) {
+                        continue;
+                    }
+                    index
+                }
+                Entry::Free { .. } => continue,
+            };
+            if removed.is_empty() {
+                // One bump covers every removal, as nothing is inserted in
+                // between. Do it up front in case `predicate` panics later.
+                self.generation += 1;
+            }
+            match mem::replace(&mut self.items[i], Entry::Free { next_free: None }) {
+                Entry::Occupied { value, .. } => removed.push((index, value)),
+                Entry::Free { .. } => unreachable!(),
+            }
+            self.push_free(i);
+            self.len -= 1;
+            self.removals += 1;
+        }
+        removed
+    }
+
+    /// Lazily remove the elements that the predicate selects, yielding them
+    /// along with their indices.
+    ///
+    /// This is the lazy form of `remove_where`: elements are visited in
+    /// ascending slot order as the iterator is advanced, and those for which
+    /// the predicate returns `true` are removed and yielded. If the iterator
+    /// is dropped before it is exhausted, the elements it has not visited yet
+    /// are kept.
+    ///
+    /// However many elements one call removes, the arena's generation is
+    /// advanced only once, on the first removal. Nothing can be inserted
+    /// while the iterator borrows the arena, so one bump is enough to keep
+    /// every removed element's index from matching a later element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    ///
+    /// let mut odd = arena.drain_filter(|_, n| *n % 2 == 1);
+    /// assert_eq!(odd.next(), Some((indices[1], 1)));
+    /// assert_eq!(odd.next(), Some((indices[3], 3)));
+    /// drop(odd);
+    ///
+    /// // The element after the last one yielded was never visited.
+    /// assert_eq!(arena.len(), 4);
+    /// assert!(arena.contains(indices[5]));
+    /// ```
+    pub fn drain_filter<F>(&mut self, predicate: F) -> DrainFilter<'_, T, F>
+    where
+        F: FnMut(Index, &mut T) -> bool,
+    {
+        let end = self.items.len();
+        DrainFilter {
+            arena: self,
+            predicate,
+            next: 0,
+            end,
+            bumped: false,
+        }
+    }
+
+    /// Is the element at index `i` in the arena?
+    ///
+    /// Returns `true` if the element at `i` is in the arena, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert!(arena.contains(idx));
+    /// arena.remove(idx);
+    /// assert!(!arena.contains(idx));
+    /// ```
+    #[inline]
+    pub fn contains(&self, i: Index) -> bool {
+        self.get(i).is_some()
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.get(idx), Some(&42));
+    /// arena.remove(idx);
+    /// assert!(arena.get(idx).is_none());
+    /// ```
+    #[inline]
+    pub fn get(&self, i: Index) -> Option<&T> {
+        self.check_arena(i);
+        match self.items.get(i.index) {
+            Some(Entry::Occupied {
+                generation,
+                value,
+            }) if *generation == i.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// *arena.get_mut(idx).unwrap() += 1;
+    /// assert_eq!(arena.remove(idx), Some(43));
+    /// assert!(arena.get_mut(idx).is_none());
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        self.check_arena(i);
+        match self.items.get_mut(i.index) {
+            Some(Entry::Occupied {
+                generation,
+                value,
+            }) if *generation == i.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get a shared reference to the element at index `i`, or a
+    /// `LookupError` describing why it is not in the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, SlotState};
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena.try_get(idx), Ok(&42));
+    ///
+    /// arena.remove(idx);
+    /// let new = arena.insert(43);
+    /// let err = arena.try_get(idx).unwrap_err();
+    /// assert_eq!(err.state(), SlotState::Occupied { generation: 1 });
+    /// assert_eq!(err.to_string(), "no element at index 0v0: slot 0 holds generation 1");
+    /// # let _ = new;
+    /// ```
+    pub fn try_get(&self, i: Index) -> Result<&T, LookupError> {
+        self.get(i).ok_or_else(|| LookupError::new(i, &self.items))
+    }
+
+    /// Get an exclusive reference to the element at index `i`, or a
+    /// `LookupError` describing why it is not in the arena.
+    pub fn try_get_mut(&mut self, i: Index) -> Result<&mut T, LookupError> {
+        if !self.contains(i) {
+            return Err(LookupError::new(i, &self.items));
+        }
+        Ok(self.get_mut(i).expect("element was just found"))
+    }
+
+    /// Call `f` with a shared reference to the element at index `i`, if it is
+    /// in the arena, and return its result.
+    ///
+    /// This keeps the borrow of the element as short as the call to `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(String::from("hello"));
+    ///
+    /// assert_eq!(arena.get_with(idx, |s| s.len()), Some(5));
+    /// arena.remove(idx);
+    /// assert_eq!(arena.get_with(idx, |s| s.len()), None);
+    /// ```
+    pub fn get_with<U>(&self, i: Index, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.get(i).map(f)
+    }
+
+    /// Call `f` with an exclusive reference to the element at index `i`, if
+    /// it is in the arena, and return its result.
+    ///
+    /// This keeps the borrow of the element as short as the call to `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(vec![1, 2]);
+    ///
+    /// let len = arena.get_mut_with(idx, |v| {
+    ///     v.push(3);
+    ///     v.len()
+    /// });
+    /// assert_eq!(len, Some(3));
+    /// ```
+    pub fn get_mut_with<U>(&mut self, i: Index, f: impl FnOnce(&mut T) -> U) -> Option<U> {
+        self.get_mut(i).map(f)
+    }
+
+    /// Find the first element, in ascending slot order, that satisfies the
+    /// predicate, and return it along with its index.
+    ///
+    /// This visits every element until one matches. To look elements up by a
+    /// key on large arenas, maintain a `ValueIndex` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert(("alice", 10));
+    /// let bob = arena.insert(("bob", 20));
+    ///
+    /// assert_eq!(arena.find(|&(name, _)| name == "bob"), Some((bob, &("bob", 20))));
+    /// assert_eq!(arena.find(|&(name, _)| name == "carol"), None);
+    /// ```
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(Index, &T)> {
+        self.iter().find(|(_, value)| predicate(value))
+    }
+
+    /// Apply `f` to the elements in ascending slot order, and return the
+    /// first result that is not `None` along with the index of the element
+    /// that produced it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.insert("one");
+    /// let two = arena.insert("2");
+    ///
+    /// assert_eq!(arena.find_map(|s| s.parse::<u32>().ok()), Some((two, 2)));
+    /// ```
+    pub fn find_map<U>(&self, mut f: impl FnMut(&T) -> Option<U>) -> Option<(Index, U)> {
+        self.iter().find_map(|(i, value)| f(value).map(|u| (i, u)))
+    }
+
+    /// Get a pair of exclusive references to the elements at index `i1` and `i2` if it is in the
+    /// arena.
+    ///
+    /// If the element at index `i1` or `i2` is not in the arena, then `None` is returned for this
+    /// element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i1` and `i2` are pointing to the same item of the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx1 = arena.insert(0);
+    /// let idx2 = arena.insert(1);
+    ///
+    /// {
+    ///     let (item1, item2) = arena.get2_mut(idx1, idx2);
+    ///
+    ///     *item1.unwrap() = 3;
+    ///     *item2.unwrap() = 4;
+    /// }
+    ///
+    /// assert_eq!(arena[idx1], 3);
+    /// assert_eq!(arena[idx2], 4);
+    /// ```
+    pub fn get2_mut(&mut self, i1: Index, i2: Index) -> (Option<&mut T>, Option<&mut T>) {
+        self.check_arena(i1);
+        self.check_arena(i2);
+        let len = self.items.len();
+
+        if i1.index == i2.index {
+            assert!(i1.generation != i2.generation);
+
+            if i1.generation > i2.generation {
+                return (self.get_mut(i1), None);
+            }
+            return (None, self.get_mut(i2));
+        }
+
+        if i1.index >= len {
+            return (None, self.get_mut(i2));
+        } else if i2.index >= len {
+            return (self.get_mut(i1), None);
+        }
+
+        let (raw_item1, raw_item2) = {
+            let (xs, ys) = self.items.split_at_mut(cmp::max(i1.index, i2.index));
+            if i1.index < i2.index {
+                (&mut xs[i1.index], &mut ys[0])
+            } else {
+                (&mut ys[0], &mut xs[i2.index])
+            }
+        };
+
+        let item1 = match raw_item1 {
+            Entry::Occupied {
+                generation,
+                value,
+            } if *generation == i1.generation => Some(value),
+            _ => None,
+        };
+
+        let item2 = match raw_item2 {
+            Entry::Occupied {
+                generation,
+                value,
+            } if *generation == i2.generation => Some(value),
+            _ => None,
+        };
+
+        (item1, item2)
+    }
+
+    /// Like `get2_mut`, but does not panic if `i1` and `i2` are the same
+    /// index.
+    ///
+    /// If `i1 == i2`, both refer to the same element, and only one exclusive
+    /// reference to it can exist. It is returned as the first element of the
+    /// pair, and the second is `None`. If that element is not in the arena,
+    /// both are `None`. Otherwise, this behaves exactly like `get2_mut`.
+    ///
+    /// This suits code that may legitimately be handed the same index twice,
+    /// e.g. an element interacting with itself, and can treat a missing
+    /// second element as the aliasing case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// let (x, y) = arena.try_get2_mut(a, b);
+    /// assert_eq!((x, y), (Some(&mut 1), Some(&mut 2)));
+    ///
+    /// // The same index twice aliases: only the first reference is given.
+    /// let (x, y) = arena.try_get2_mut(a, a);
+    /// assert_eq!((x, y), (Some(&mut 1), None));
+    ///
+    /// arena.remove(a);
+    /// assert_eq!(arena.try_get2_mut(a, a), (None, None));
+    /// ```
+    pub fn try_get2_mut(&mut self, i1: Index, i2: Index) -> (Option<&mut T>, Option<&mut T>) {
+        if i1 == i2 {
+            self.check_arena(i2);
+            return (self.get_mut(i1), None);
+        }
+        self.get2_mut(i1, i2)
+    }
+
+    #[inline]
+    fn check_arena(&self, i: Index) {
+        i.check_arena(self.id);
+    }
+
+    /// Get the length of this arena.
+    ///
+    /// The length is the number of elements the arena holds. It is kept up
+    /// to date as elements come and go, so this takes constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// assert_eq!(arena.len(), 0);
+    ///
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena.len(), 1);
+    ///
+    /// let _ = arena.insert(0);
+    /// assert_eq!(arena.len(), 2);
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the arena contains no elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// assert!(arena.is_empty());
+    ///
+    /// let idx = arena.insert(42);
+    /// assert!(!arena.is_empty());
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert!(arena.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if inserting another element would allocate, because
+    /// every slot of capacity holds an element.
+    ///
+    /// This is the same as `spare_capacity() == 0`, and takes constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// let idx = arena.insert(1);
+    /// arena.insert(2);
+    /// assert!(arena.is_full());
+    ///
+    /// arena.remove(idx);
+    /// assert!(!arena.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Get the capacity of this arena.
+    ///
+    /// The capacity is the maximum number of elements the arena can hold
+    /// without further allocation, including however many it currently
+    /// contains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(10);
+    /// assert_eq!(arena.capacity(), 10);
+    ///
+    /// // `try_insert` does not allocate new capacity.
+    /// for i in 0..10 {
+    ///     assert!(arena.try_insert(1).is_ok());
+    ///     assert_eq!(arena.capacity(), 10);
+    /// }
+    ///
+    /// // But `insert` will if the arena is already at capacity.
+    /// arena.insert(0);
+    /// assert!(arena.capacity() > 10);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Get the number of slots the arena has used so far.
+    ///
+    /// Slots are only written to the first time an element is inserted into
+    /// them, so allocating capacity is cheap even when it is large. Every
+    /// slot below `slot_count` is either occupied or on the free list; every
+    /// slot above it, up to `capacity`, has never been used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(1_000_000);
+    /// assert_eq!(arena.slot_count(), 0);
+    ///
+    /// let idx = arena.insert("a");
+    /// arena.insert("b");
+    /// assert_eq!(arena.slot_count(), 2);
+    ///
+    /// // Freed slots still count.
+    /// arena.remove(idx);
+    /// assert_eq!(arena.slot_count(), 2);
+    /// ```
+    pub fn slot_count(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Get the number of elements that can be inserted into the arena
+    /// without further allocation.
+    ///
+    /// This counts both free slots and slots that have never been used, and
+    /// is always `capacity() - len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(4);
+    /// let idx = arena.insert(1);
+    /// arena.insert(2);
+    /// assert_eq!(arena.spare_capacity(), 2);
+    ///
+    /// arena.remove(idx);
+    /// assert_eq!(arena.spare_capacity(), 3);
+    /// ```
+    pub fn spare_capacity(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /// Move every element of `other` into this arena.
+    ///
+    /// The elements fill this arena's free slots first, and the arena grows
+    /// at most once to make room for the rest. For each element moved,
+    /// `remap` is called with its index in `other` and its new index in this
+    /// arena, so that references to it can be updated. Elements are moved in
+    /// ascending slot order of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut world = Arena::new();
+    /// world.insert("sun");
+    ///
+    /// let mut chunk = Arena::new();
+    /// let tree = chunk.insert("tree");
+    /// let rock = chunk.insert("rock");
+    ///
+    /// let mut moved = HashMap::new();
+    /// world.merge(chunk, |old, new| {
+    ///     moved.insert(old, new);
+    /// });
+    ///
+    /// assert_eq!(world.len(), 3);
+    /// assert_eq!(world[moved[&tree]], "tree");
+    /// assert_eq!(world[moved[&rock]], "rock");
+    /// ```
+    pub fn merge(&mut self, other: Arena<T>, mut remap: impl FnMut(Index, Index)) {
+        self.reserve_exact(other.len());
+
+        for (slot, entry) in other.items.into_iter().enumerate() {
+            if let Entry::Occupied { generation, value } = entry {
+                let old = Index {
+                    index: slot,
+                    generation,
+                    arena: other.id,
+                };
+                let new = self
+                    .try_insert(value)
+                    .map_err(|_| ())
+                    .expect("merging always has room after reserving space");
+                remap(old, new);
+            }
+        }
+    }
+
+    /// Reserve capacity for at least `additional_capacity` more elements to
+    /// be inserted into the arena.
+    ///
+    /// Like `Vec::reserve`, this does nothing if there is already enough
+    /// spare capacity. Otherwise, to avoid frequent reallocations, the arena
+    /// may grow by more than is needed: it grows to at least its growth
+    /// percentage of its current capacity, which is 200% unless configured
+    /// otherwise with `ArenaBuilder::growth_percent`, and by at least its
+    /// current capacity or 4 slots, whichever is fewer. This is the same
+    /// growth that inserting into a full arena triggers.
+    ///
+    /// The new slots are not written to until they are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this causes the capacity to overflow: the number of slots
+    /// must fit in a `usize`, and their storage must not exceed `isize::MAX`
+    /// bytes. Use `try_insert_grow` to insert without panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(10);
+    /// arena.extend(0..8);
+    ///
+    /// // There is only room for two more, so the arena doubles.
+    /// arena.reserve(5);
+    /// assert_eq!(arena.capacity(), 20);
+    ///
+    /// // Now there is room to spare.
+    /// arena.reserve(5);
+    /// assert_eq!(arena.capacity(), 20);
+    /// ```
+    pub fn reserve(&mut self, additional_capacity: usize) {
+        if additional_capacity > self.spare_capacity() {
+            self.grow(self.growth_for(additional_capacity));
+        }
+    }
+
+    /// The number of slots `reserve` adds to make room for
+    /// `additional_capacity` more elements, when there is not enough spare
+    /// capacity.
+    fn growth_for(&self, additional_capacity: usize) -> usize {
+        let capacity = self.capacity();
+        let step = if capacity == 0 {
+            DEFAULT_CAPACITY
+        } else {
+            let grown = capacity.saturating_mul(self.growth_percent) / 100;
+            // Small growth percentages round down to little or no growth at
+            // small capacities, which would reallocate on nearly every
+            // insertion.
+            let min_step = cmp::min(capacity, DEFAULT_CAPACITY);
+            cmp::max(grown.saturating_sub(capacity), min_step)
+        };
+        let needed = additional_capacity.saturating_sub(self.spare_capacity());
+        cmp::max(needed, step)
+    }
+
+    /// Reserve capacity for exactly `additional_capacity` more elements to be
+    /// inserted into the arena.
+    ///
+    /// Like `Vec::reserve_exact`, this does nothing if there is already
+    /// enough spare capacity. Prefer `reserve` when more insertions are
+    /// likely to follow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this causes the capacity to overflow: the number of slots
+    /// must fit in a `usize`, and their storage must not exceed `isize::MAX`
+    /// bytes. Use `try_insert_grow` to insert without panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(10);
+    /// arena.extend(0..8);
+    /// arena.reserve_exact(5);
+    /// assert_eq!(arena.capacity(), 13);
+    /// ```
+    pub fn reserve_exact(&mut self, additional_capacity: usize) {
+        let spare = self.spare_capacity();
+        if additional_capacity > spare {
+            self.grow(additional_capacity - spare);
+        }
+    }
+
+    /// Add exactly `extra` never-used slots to the arena's capacity.
+    fn grow(&mut self, extra: usize) {
+        // Arenas made by the `const` constructor only draw their id once
+        // they can hand out indices.
+        self.id = self.id.or_fresh();
+        let unused = self.items.capacity() - self.items.len();
+        let extra = unused.checked_add(extra).expect("capacity overflow");
+        self.items.reserve_exact(extra);
+        self.reserve_occupancy();
+    }
+
+    /// Like `grow`, but returns whether it succeeded instead of panicking or
+    /// aborting when the capacity would overflow or allocation fails.
+    fn try_grow(&mut self, extra: usize) -> bool {
+        self.id = self.id.or_fresh();
+        let unused = self.items.capacity() - self.items.len();
+        match unused.checked_add(extra) {
+            Some(extra) => {
+                self.items.try_reserve_exact(extra).is_ok() && self.try_reserve_occupancy()
+            }
+            None => false,
+        }
+    }
+
+    /// Make room in `occupancy` for every slot of capacity, so that inserting
+    /// without growing the arena never allocates.
+    fn reserve_occupancy(&mut self) {
+        let words = occupancy_words(self.items.capacity());
+        self.occupancy.reserve_exact(words - cmp::min(words, self.occupancy.len()));
+    }
+
+    /// Like `reserve_occupancy`, but returns whether it succeeded.
+    fn try_reserve_occupancy(&mut self) -> bool {
+        let words = occupancy_words(self.items.capacity());
+        self.occupancy
+            .try_reserve_exact(words - cmp::min(words, self.occupancy.len()))
+            .is_ok()
+    }
+
+    /// Shrink the arena to at most `max_slots` slots of capacity, releasing
+    /// the memory of the free slots above the cutoff.
+    ///
+    /// Every element must live below the cutoff. If one does not, the arena
+    /// is left untouched and its `Index` is reported in the error. Remaining
+    /// free slots are reused in ascending slot order afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// // A load spike grows the arena...
+    /// let spike: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    /// for idx in spike {
+    ///     arena.remove(idx);
+    /// }
+    /// assert!(arena.capacity() >= 1000);
+    ///
+    /// // ...but afterwards its memory can be bounded again.
+    /// arena.truncate_capacity(16).unwrap();
+    /// assert_eq!(arena.capacity(), 16);
+    ///
+    /// arena.remove(a);
+    /// assert_eq!(arena.truncate_capacity(1).unwrap_err().index(), b);
+    /// assert_eq!(arena.capacity(), 16);
+    /// ```
+    pub fn truncate_capacity(&mut self, max_slots: usize) -> Result<(), TruncateCapacityError> {
+        if max_slots >= self.capacity() {
+            return Ok(());
+        }
+        if max_slots >= self.items.len() {
+            self.items.shrink_to(max_slots);
+            self.occupancy.shrink_to(occupancy_words(max_slots));
+            return Ok(());
+        }
+        if let Some((_, index)) = self.items[max_slots..]
+            .iter()
+            .position(|entry| matches!(entry, Entry::Occupied { .. }))
+            .and_then(|i| self.get_unknown_gen(max_slots + i))
+        {
+            return Err(TruncateCapacityError { index });
+        }
+
+        self.items.truncate(max_slots);
+        self.items.shrink_to(max_slots);
+        self.rebuild_free_list();
+        self.occupancy.shrink_to(occupancy_words(max_slots));
+        Ok(())
+    }
+
+    /// Split the arena in two at raw slot `at`, moving every element in slot
+    /// `at` or above into a new arena, which is returned.
+    ///
+    /// Like `Vec::split_off`, but the moved elements keep their slots and
+    /// generations, so every `Index` stays valid in whichever arena its
+    /// element ended up in. The slots below `at` are free in the new arena.
+    /// Both arenas' free lists are rebuilt, and both go on to hand out
+    /// generations newer than any they held before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut west = Arena::new();
+    /// let indices: Vec<_> = (0..6).map(|n| west.insert(n)).collect();
+    ///
+    /// let east = west.split_off_slots(4);
+    /// assert_eq!(west.len(), 4);
+    /// assert_eq!(east.len(), 2);
+    /// assert_eq!(west[indices[1]], 1);
+    /// assert_eq!(east[indices[5]], 5);
+    /// assert!(!west.contains(indices[5]));
+    /// ```
+    pub fn split_off_slots(&mut self, at: usize) -> Arena<T> {
+        if !self.is_empty() {
+            // The two halves now share an id, so neither may hand out an
+            // index that the other already has.
+            self.generation += 1;
+        }
+        let at = cmp::min(at, self.items.len());
+        let mut items = Vec::with_capacity(self.items.len());
+        items.resize_with(at, || Entry::Free { next_free: None });
+        items.extend(self.items.drain(at..));
+
+        let mut other = Arena {
+            items,
+            generation: self.generation,
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len: 0,
+            occupied: 0..0,
+            occupancy: Vec::new(),
+            reuse: self.reuse,
+            // Indices handed out before the split must keep working in the
+            // new arena.
+            id: self.id,
+            removals: 0,
+            growth_percent: self.growth_percent,
+        };
+        other.rebuild_free_list();
+        self.rebuild_free_list();
+        other
+    }
+
+    /// Take every element out of the arena, returning them in a new arena
+    /// and leaving this one empty, with the same capacity as before.
+    ///
+    /// Unlike `drain`, the elements stay in an arena: they keep their
+    /// indices, which remain valid in the returned arena, and can be
+    /// processed there at leisure while this arena goes on receiving
+    /// insertions, as when a message queue hands off its backlog. This arena
+    /// allocates a fresh buffer of its old capacity, and never hands out an
+    /// index that the returned arena already has.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut inbox = Arena::with_capacity(8);
+    /// let hello = inbox.insert("hello");
+    /// inbox.insert("world");
+    ///
+    /// let batch = inbox.take();
+    /// assert!(inbox.is_empty());
+    /// assert_eq!(inbox.capacity(), 8);
+    /// assert_eq!(batch.len(), 2);
+    /// assert_eq!(batch[hello], "hello");
+    ///
+    /// let later = inbox.insert("again");
+    /// assert!(!batch.contains(later));
+    /// ```
+    pub fn take(&mut self) -> Arena<T> {
+        let empty = Arena {
+            items: Vec::with_capacity(self.items.capacity()),
+            // The two arenas share an id, so this one must not hand out an
+            // index that the taken elements already have.
+            generation: if self.is_empty() {
+                self.generation
+            } else {
+                self.generation + 1
+            },
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len: 0,
+            occupied: 0..0,
+            occupancy: Vec::with_capacity(self.occupancy.capacity()),
+            reuse: self.reuse,
+            id: self.id,
+            // The elements were moved, not removed.
+            removals: self.removals,
+            growth_percent: self.growth_percent,
+        };
+        let mut taken = mem::replace(self, empty);
+        taken.removals = 0;
+        taken
+    }
+
+    /// Split the arena in two by a predicate, returning the elements for
+    /// which `predicate(index, &mut value)` returns `true`, and then those
+    /// for which it returns `false`.
+    ///
+    /// This is the complement of `retain`: nothing is dropped. Like
+    /// `split_off_slots`, every element keeps its slot and generation, so
+    /// every `Index` stays valid in whichever arena its element ended up in,
+    /// and both arenas go on to hand out generations newer than any they
+    /// held before. Elements are visited in ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut objects = Arena::new();
+    /// let live = objects.insert(("ship", true));
+    /// let pooled = objects.insert(("bullet", false));
+    ///
+    /// let (live_objects, pool) = objects.partition(|_, object| object.1);
+    /// assert_eq!(live_objects[live].0, "ship");
+    /// assert_eq!(pool[pooled].0, "bullet");
+    /// assert!(!live_objects.contains(pooled));
+    /// ```
+    pub fn partition(
+        mut self,
+        mut predicate: impl FnMut(Index, &mut T) -> bool,
+    ) -> (Arena<T>, Arena<T>) {
+        if !self.is_empty() {
+            // The two halves now share an id, so neither may hand out an
+            // index that the other already has.
+            self.generation += 1;
+        }
+        let id = self.id;
+        let mut items = Vec::with_capacity(self.items.len());
+        for (slot, entry) in self.items.iter_mut().enumerate() {
+            let keep = match entry {
+                Entry::Occupied { generation, value } => {
+                    let index = Index {
+                        index: slot,
+                        generation: *generation,
+                        arena: id,
+                    };
+                    predicate(index, value)
+                }
+                Entry::Free { .. } => true,
+            };
+            items.push(if keep {
+                Entry::Free { next_free: None }
+            } else {
+                mem::replace(entry, Entry::Free { next_free: None })
+            });
+        }
+
+        let mut rejected = Arena {
+            items,
+            generation: self.generation,
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len: 0,
+            occupied: 0..0,
+            occupancy: Vec::new(),
+            reuse: self.reuse,
+            id,
+            removals: 0,
+            growth_percent: self.growth_percent,
+        };
+        rejected.rebuild_free_list();
+        self.rebuild_free_list();
+        (self, rejected)
+    }
+
+    /// Split the indices of the arena's elements in two by a predicate,
+    /// returning the indices for which `predicate(index, &value)` returns
+    /// `true`, and then those for which it returns `false`.
+    ///
+    /// This is `partition` without moving any elements. Both lists are in
+    /// ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// let c = arena.insert(3);
+    ///
+    /// let (odd, even) = arena.partition_indices(|_, n| n % 2 == 1);
+    /// assert_eq!(odd, [a, c]);
+    /// assert_eq!(even, [b]);
+    /// ```
+    pub fn partition_indices(
+        &self,
+        mut predicate: impl FnMut(Index, &T) -> bool,
+    ) -> (Vec<Index>, Vec<Index>) {
+        let mut accepted = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, value) in self {
+            if predicate(index, value) {
+                accepted.push(index);
+            } else {
+                rejected.push(index);
+            }
+        }
+        (accepted, rejected)
+    }
+
+    /// Iterate over shared references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index, &T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// The arena keeps track of its lowest and highest occupied slots, so
+    /// iterating in either direction never visits the free slots before the
+    /// first element or after the last one. An arena that grew large and
+    /// then emptied out except for a few elements is as cheap to iterate as
+    /// the span between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i * i);
+    /// }
     ///
-    /// let idx = arena.insert_with(|idx| (42, idx));
-    /// assert_eq!(arena[idx].0, 42);
-    /// assert_eq!(arena[idx].1, idx);
+    /// for (idx, value) in arena.iter() {
+    ///     println!("{} is at index {:?}", value, idx);
+    /// }
     /// ```
-    #[inline]
-    pub fn insert_with(&mut self, create: impl FnOnce(Index) -> T) -> Index {
-        match self.try_insert_with(create) {
-            Ok(i) => i,
-            Err(create) => self.insert_with_slow_path(create),
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            len: self.len,
+            offset: self.occupied.start,
+            arena: self.id,
+            inner: self.items[self.occupied.clone()].iter(),
         }
     }
 
-    #[inline(never)]
-    fn insert_slow_path(&mut self, value: T) -> Index {
-        let len = if self.capacity() == 0 {
-            // `drain()` sets the capacity to 0 and if the capacity is 0, the
-            // next `try_insert() `will refer to an out-of-range index because
-            // the next `reserve()` does not add element, resulting in a panic.
-            // So ensure that `self` have at least 1 capacity here.
-            //
-            // Ideally, this problem should be handled within `drain()`,but
-            // this problem cannot be handled within `drain()` because `drain()`
-            // returns an iterator that borrows `self` mutably.
-            1
-        } else {
-            self.items.len()
-        };
-        self.reserve(len);
-        self.try_insert(value)
-            .map_err(|_| ())
-            .expect("inserting will always succeed after reserving additional space")
+    /// Iterate over exclusive references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index, &mut T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i * i);
+    /// }
+    ///
+    /// for (_idx, value) in arena.iter_mut() {
+    ///     *value += 5;
+    /// }
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            len: self.len,
+            offset: self.occupied.start,
+            arena: self.id,
+            inner: self.items[self.occupied.clone()].iter_mut(),
+        }
     }
 
-    #[inline(never)]
-    fn insert_with_slow_path(&mut self, create: impl FnOnce(Index) -> T) -> Index {
-        let len = self.items.len();
-        self.reserve(len);
-        self.try_insert_with(create)
-            .map_err(|_| ())
-            .expect("inserting will always succeed after reserving additional space")
+    /// Iterate over the indices of the elements in this arena, in ascending
+    /// slot order.
+    ///
+    /// The iterator knows its exact length, so collecting it allocates once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    /// arena.remove(b);
+    ///
+    /// let indices: Vec<_> = arena.indices().collect();
+    /// assert_eq!(indices, [a, c]);
+    /// ```
+    pub fn indices(&self) -> Indices<'_, T> {
+        Indices { inner: self.iter() }
     }
 
-    /// Remove the element at index `i` from the arena.
+    /// Iterate over shared references to the elements in this arena,
+    /// starting at raw slot `slot`.
     ///
-    /// If the element at index `i` is still in the arena, then it is
-    /// returned. If it is not in the arena, then `None` is returned.
+    /// Yields pairs of `(Index, &T)` items in ascending slot order, skipping
+    /// every slot before `slot` without looking at it. `IterFrom::next_slot`
+    /// reports where to pick up again, so a sweep over a large arena can be
+    /// spread across several calls, each costing only as much as the slots
+    /// it visits.
     ///
     /// # Examples
     ///
@@ -504,79 +2843,426 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let idx = arena.insert(42);
+    /// arena.extend(0..10);
     ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert_eq!(arena.remove(idx), None);
+    /// // Visit at most three elements per step.
+    /// let mut slot = 0;
+    /// let mut seen = vec![];
+    /// loop {
+    ///     let mut iter = arena.iter_occupied_from(slot);
+    ///     seen.extend(iter.by_ref().take(3).map(|(_, n)| *n));
+    ///     slot = iter.next_slot();
+    ///     if slot == arena.slot_count() {
+    ///         break;
+    ///     }
+    /// }
+    /// assert_eq!(seen, (0..10).collect::<Vec<_>>());
     /// ```
-    pub fn remove(&mut self, i: Index) -> Option<T> {
-        if i.index >= self.items.len() {
+    pub fn iter_occupied_from(&self, slot: usize) -> IterFrom<'_, T> {
+        // Slots outside of `occupied` are all free, so skip them.
+        let end = self.occupied.end;
+        let slot = cmp::min(cmp::max(slot, self.occupied.start), end);
+        IterFrom {
+            offset: slot,
+            stop: self.items.len(),
+            arena: self.id,
+            inner: self.items[slot..end].iter(),
+        }
+    }
+
+    /// Iterate over shared references to the elements in the raw slots in
+    /// `range`.
+    ///
+    /// Yields pairs of `(Index, &T)` items in ascending slot order. Only the
+    /// slots in `range` are looked at, so this suits code that maps regions
+    /// of some other space, such as chunks of a world, to ranges of slots.
+    /// Parts of `range` beyond the arena's slots are ignored. Once the
+    /// iterator is exhausted, `IterFrom::next_slot` is the end of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let indices: Vec<_> = (0..10).map(|n| arena.insert(n)).collect();
+    /// arena.remove(indices[4]);
+    ///
+    /// let region: Vec<_> = arena.occupied_slots_in(3..6).map(|(_, n)| *n).collect();
+    /// assert_eq!(region, [3, 5]);
+    /// ```
+    pub fn occupied_slots_in(&self, range: impl ops::RangeBounds<usize>) -> IterFrom<'_, T> {
+        let range = self.slots_in(range);
+        // Slots outside of `occupied` are all free, so skip them.
+        let end = cmp::min(range.end, self.occupied.end);
+        let start = cmp::min(cmp::max(range.start, self.occupied.start), end);
+        IterFrom {
+            offset: start,
+            stop: range.end,
+            arena: self.id,
+            inner: self.items[start..end].iter(),
+        }
+    }
+
+    /// Clamp `range` to the arena's slots.
+    fn slots_in(&self, range: impl ops::RangeBounds<usize>) -> ops::Range<usize> {
+        let start = match range.start_bound() {
+            ops::Bound::Included(&start) => start,
+            ops::Bound::Excluded(&start) => start.saturating_add(1),
+            ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            ops::Bound::Included(&end) => end.saturating_add(1),
+            ops::Bound::Excluded(&end) => end,
+            ops::Bound::Unbounded => usize::MAX,
+        };
+        let end = cmp::min(end, self.items.len());
+        let start = cmp::min(start, end);
+        start..end
+    }
+
+    /// Get an exclusive reference to the element at index `i`, along with an
+    /// iterator over exclusive references to every element in a later slot.
+    ///
+    /// This is like `slice::split_first_mut`, and makes pairwise algorithms
+    /// that visit each element against all the ones after it possible
+    /// without juggling indices through `get2_mut`. The iterator yields
+    /// elements in ascending slot order.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let indices: Vec<_> = (1..=4).map(|n| arena.insert(n)).collect();
+    ///
+    /// // Add each element to every element after it.
+    /// for &idx in &indices {
+    ///     let (first, rest) = arena.split_iter_mut_at(idx).unwrap();
+    ///     for (_, other) in rest {
+    ///         *other += *first;
+    ///     }
+    /// }
+    ///
+    /// let values: Vec<_> = arena.iter().map(|(_, n)| *n).collect();
+    /// assert_eq!(values, [1, 3, 7, 15]);
+    /// ```
+    pub fn split_iter_mut_at(&mut self, i: Index) -> Option<(&mut T, IterMut<'_, T>)> {
+        self.check_arena(i);
+        if !self.contains(i) {
             return None;
         }
 
-        match self.items[i.index] {
-            Entry::Occupied { generation, .. } if i.generation == generation => {
-                let entry = mem::replace(
-                    &mut self.items[i.index],
-                    Entry::Free { next_free: self.free_list_head },
-                );
-                self.generation += 1;
-                self.free_list_head = Some(i.index);
-                self.len -= 1;
+        let (head, rest) = self.items.split_at_mut(i.index + 1);
+        let len = rest
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied { .. }))
+            .count();
+        match head.last_mut() {
+            Some(Entry::Occupied { value, .. }) => Some((
+                value,
+                IterMut {
+                    len,
+                    offset: i.index + 1,
+                    arena: self.id,
+                    inner: rest.iter_mut(),
+                },
+            )),
+            _ => unreachable!(),
+        }
+    }
 
-                match entry {
-                    Entry::Occupied { generation: _, value } => Some(value),
-                    _ => unreachable!(),
-                }
-            }
+    /// Iterate over elements of the arena and remove them.
+    ///
+    /// Yields pairs of `(Index, T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx_1 = arena.insert("hello");
+    /// let idx_2 = arena.insert("world");
+    ///
+    /// assert!(arena.get(idx_1).is_some());
+    /// assert!(arena.get(idx_2).is_some());
+    /// for (idx, value) in arena.drain() {
+    ///     assert!((idx == idx_1 && value == "hello") || (idx == idx_2 && value == "world"));
+    /// }
+    /// assert!(arena.get(idx_1).is_none());
+    /// assert!(arena.get(idx_2).is_none());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        let old_len = self.len;
+        if !self.is_empty() {
+            // Increment generation, but if there are no elements, do nothing to
+            // avoid unnecessary incrementing generation.
+            self.generation += 1;
+        }
+        self.free_list_head = None;
+        self.free_list_tail = None;
+        self.free_stack.clear();
+        self.len = 0;
+        self.occupied = 0..0;
+        self.occupancy.clear();
+        self.removals += old_len as u64;
+        Drain {
+            len: old_len,
+            offset: 0,
+            arena: self.id,
+            inner: self.items.drain(..),
+        }
+    }
+
+    /// Consume the arena, yielding each element along with its index.
+    ///
+    /// This is like `into_iter`, but keeps the indices, e.g. for moving the
+    /// elements into another structure keyed by `Index`. Elements are
+    /// yielded in ascending slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    ///
+    /// let map: HashMap<_, _> = arena.into_iter_indexed().collect();
+    /// assert_eq!(map[&a], "a");
+    /// assert_eq!(map[&b], "b");
+    /// ```
+    pub fn into_iter_indexed(self) -> IntoIterIndexed<T> {
+        IntoIterIndexed {
+            len: self.len,
+            offset: 0,
+            arena: self.id,
+            inner: self.items.into_iter(),
+        }
+    }
+
+    /// Remove every element of the arena, yielding only their indices.
+    ///
+    /// Each element is dropped in place as its index is yielded, without
+    /// being moved out of the arena, which is cheaper than `drain` for large
+    /// `T` when only the indices are needed.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::with_capacity(2);
+    /// let idx_1 = arena.insert([0u8; 1024]);
+    /// let idx_2 = arena.insert([1u8; 1024]);
+    ///
+    /// let removed: Vec<_> = arena.drain_indices().collect();
+    /// assert_eq!(removed, vec![idx_1, idx_2]);
+    /// assert!(arena.is_empty());
+    /// assert_eq!(arena.capacity(), 2);
+    /// ```
+    pub fn drain_indices(&mut self) -> DrainIndices<'_, T> {
+        if !self.is_empty() {
+            // Increment generation, but if there are no elements, do nothing to
+            // avoid unnecessary incrementing generation.
+            self.generation += 1;
+        }
+        let end = self.items.len();
+        DrainIndices {
+            arena: self,
+            start: 0,
+            end,
+        }
+    }
+
+    /// Remove the elements whose slots fall in `range`, yielding them along
+    /// with their indices.
+    ///
+    /// Yields pairs of `(Index, T)` items in ascending slot order. Elements
+    /// outside of `range` are untouched, and the freed slots are reused by
+    /// later insertions like any other. Parts of `range` beyond the arena's
+    /// capacity are ignored.
+    ///
+    /// This is useful when a batch of elements was inserted contiguously, for
+    /// example all the entities of one chunk of a streamed world, and later
+    /// needs to be removed together.
+    ///
+    /// Note: All elements in `range` are removed even if the iterator is only
+    /// partially consumed or not consumed at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..6);
+    ///
+    /// let chunk: Vec<_> = arena.drain_range(2..4).map(|(_, v)| v).collect();
+    /// assert_eq!(chunk, [2, 3]);
+    ///
+    /// let rest: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(rest, [0, 1, 4, 5]);
+    /// ```
+    pub fn drain_range(&mut self, range: impl ops::RangeBounds<usize>) -> DrainRange<'_, T> {
+        let ops::Range { start, end } = self.slots_in(range);
+        if !self.is_empty() {
+            // Increment generation, but if there are no elements, do nothing to
+            // avoid unnecessary incrementing generation.
+            self.generation += 1;
+        }
+        DrainRange {
+            arena: self,
+            start,
+            end,
+        }
+    }
+
+    /// Given a slot `i` without a generation, get a shared reference
+    /// to the element and the matching `Index` of the entry behind `i`.
+    ///
+    /// This method is useful when you know there might be an element at the
+    /// position i, but don't know its generation or precise Index.
+    ///
+    /// Use cases include using indexing such as Hierarchical BitMap Indexing or
+    /// other kinds of bit-efficient indexing. `i` can be a `Slot` or a raw
+    /// `usize`.
+    ///
+    /// You should use the `get` method instead most of the time.
+    pub fn get_unknown_gen(&self, i: impl Into<Slot>) -> Option<(&T, Index)> {
+        let i = i.into().0;
+        match self.items.get(i) {
+            Some(Entry::Occupied {
+                generation,
+                value,
+            }) => Some((
+                value,
+                Index {
+                    index: i,
+                    generation: *generation,
+                    arena: self.id,
+                },
+            )),
+            _ => None,
+        }
+    }
+
+    /// Given a slot `i` without a generation, get an exclusive reference
+    /// to the element and the matching `Index` of the entry behind `i`.
+    ///
+    /// This method is useful when you know there might be an element at the
+    /// position i, but don't know its generation or precise Index.
+    ///
+    /// Use cases include using indexing such as Hierarchical BitMap Indexing or
+    /// other kinds of bit-efficient indexing.
+    ///
+    /// You should use the `get_mut` method instead most of the time.
+    pub fn get_unknown_gen_mut(&mut self, i: impl Into<Slot>) -> Option<(&mut T, Index)> {
+        let i = i.into().0;
+        match self.items.get_mut(i) {
+            Some(Entry::Occupied {
+                generation,
+                value,
+            }) => Some((
+                value,
+                Index {
+                    index: i,
+                    generation: *generation,
+                    arena: self.id,
+                },
+            )),
             _ => None,
         }
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Given two slots `i1` and `i2` without generations, get exclusive
+    /// references to the elements behind them and their matching `Index`es.
     ///
-    /// In other words, remove all indices such that `predicate(index, &value)` returns `false`.
+    /// This is the unknown-generation counterpart of `get2_mut`. If slot `i1`
+    /// or `i2` is free or out of bounds, then `None` is returned for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i1` and `i2` are the same slot.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena::Arena;
     ///
-    /// let mut crew = Arena::new();
-    /// crew.extend(&["Jim Hawkins", "John Silver", "Alexander Smollett", "Israel Hands"]);
-    /// let pirates = ["John Silver", "Israel Hands"]; // too dangerous to keep them around
-    /// crew.retain(|_index, member| !pirates.contains(member));
-    /// let mut crew_members = crew.iter().map(|(_, member)| **member);
-    /// assert_eq!(crew_members.next(), Some("Jim Hawkins"));
-    /// assert_eq!(crew_members.next(), Some("Alexander Smollett"));
-    /// assert!(crew_members.next().is_none());
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// let (first, second) = arena.get2_unknown_gen_mut(1, 0);
+    /// let (first, second) = (first.unwrap(), second.unwrap());
+    /// assert_eq!((first.1, second.1), (b, a));
+    /// std::mem::swap(first.0, second.0);
+    ///
+    /// assert_eq!((arena[a], arena[b]), (2, 1));
+    /// assert!(arena.get2_unknown_gen_mut(0, 5).1.is_none());
     /// ```
-    pub fn retain(&mut self, mut predicate: impl FnMut(Index, &mut T) -> bool) {
-        for i in 0..self.capacity() {
-            let remove = match &mut self.items[i] {
-                Entry::Occupied { generation, value } => {
-                    let index = Index {
-                        index: i,
-                        generation: *generation,
-                    };
-                    if predicate(index, value) {
-                        None
-                    } else {
-                        Some(index)
-                    }
-                }
+    #[allow(clippy::type_complexity)]
+    pub fn get2_unknown_gen_mut(
+        &mut self,
+        i1: impl Into<Slot>,
+        i2: impl Into<Slot>,
+    ) -> (Option<(&mut T, Index)>, Option<(&mut T, Index)>) {
+        let (i1, i2) = (i1.into().0, i2.into().0);
+        assert!(i1 != i2, "slot {} requested more than once", i1);
 
-                _ => None,
-            };
-            if let Some(index) = remove {
-                self.remove(index);
+        let len = self.items.len();
+        if i1 >= len {
+            return (None, self.get_unknown_gen_mut(i2));
+        } else if i2 >= len {
+            return (self.get_unknown_gen_mut(i1), None);
+        }
+
+        let id = self.id;
+        let (raw_item1, raw_item2) = {
+            let (xs, ys) = self.items.split_at_mut(cmp::max(i1, i2));
+            if i1 < i2 {
+                (&mut xs[i1], &mut ys[0])
+            } else {
+                (&mut ys[0], &mut xs[i2])
+            }
+        };
+        fn found<T>(slot: usize, arena: ArenaId, entry: &mut Entry<T>) -> Option<(&mut T, Index)> {
+            match entry {
+                Entry::Occupied { generation, value } => Some((
+                    value,
+                    Index {
+                        index: slot,
+                        generation: *generation,
+                        arena,
+                    },
+                )),
+                Entry::Free { .. } => None,
             }
         }
+        (found(i1, id, raw_item1), found(i2, id, raw_item2))
     }
 
-    /// Is the element at index `i` in the arena?
+    /// Look up the element at each of the given raw slots, whatever its
+    /// generation.
     ///
-    /// Returns `true` if the element at `i` is in the arena, `false` otherwise.
+    /// This is the batch form of `get_unknown_gen`, yielding one item per
+    /// entry of `slots`, in the same order.
     ///
     /// # Examples
     ///
@@ -584,47 +3270,31 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let idx = arena.insert(42);
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
     ///
-    /// assert!(arena.contains(idx));
-    /// arena.remove(idx);
-    /// assert!(!arena.contains(idx));
+    /// let found: Vec<_> = arena.get_unknown_gen_many(&[1, 7, 0]).collect();
+    /// assert_eq!(found, [Some((&'b', b)), None, Some((&'a', a))]);
     /// ```
-    pub fn contains(&self, i: Index) -> bool {
-        self.get(i).is_some()
+    pub fn get_unknown_gen_many<'a, S>(
+        &'a self,
+        slots: &'a [S],
+    ) -> impl Iterator<Item = Option<(&'a T, Index)>> + 'a
+    where
+        S: Copy + Into<Slot>,
+    {
+        slots.iter().map(move |&slot| self.get_unknown_gen(slot))
     }
 
-    /// Get a shared reference to the element at index `i` if it is in the
-    /// arena.
-    ///
-    /// If the element at index `i` is not in the arena, then `None` is returned.
+    /// Get exclusive references to the elements at each of the given raw
+    /// slots, whatever their generations.
     ///
-    /// # Examples
-    ///
-    /// ```
-    /// use generational_arena::Arena;
+    /// This is the batch form of `get_unknown_gen_mut`, returning one item per
+    /// entry of `slots`, in the same order.
     ///
-    /// let mut arena = Arena::new();
-    /// let idx = arena.insert(42);
-    ///
-    /// assert_eq!(arena.get(idx), Some(&42));
-    /// arena.remove(idx);
-    /// assert!(arena.get(idx).is_none());
-    /// ```
-    pub fn get(&self, i: Index) -> Option<&T> {
-        match self.items.get(i.index) {
-            Some(Entry::Occupied {
-                generation,
-                value,
-            }) if *generation == i.generation => Some(value),
-            _ => None,
-        }
-    }
-
-    /// Get an exclusive reference to the element at index `i` if it is in the
-    /// arena.
+    /// # Panics
     ///
-    /// If the element at index `i` is not in the arena, then `None` is returned.
+    /// Panics if the same slot appears more than once in `slots`.
     ///
     /// # Examples
     ///
@@ -632,31 +3302,63 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let idx = arena.insert(42);
+    /// arena.extend(0..4);
     ///
-    /// *arena.get_mut(idx).unwrap() += 1;
-    /// assert_eq!(arena.remove(idx), Some(43));
-    /// assert!(arena.get_mut(idx).is_none());
+    /// for found in arena.get_unknown_gen_many_mut(&[3, 1, 9]).into_iter().flatten() {
+    ///     *found.0 *= 10;
+    /// }
+    /// let values: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(values, [0, 10, 2, 30]);
     /// ```
-    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
-        match self.items.get_mut(i.index) {
-            Some(Entry::Occupied {
-                generation,
-                value,
-            }) if *generation == i.generation => Some(value),
-            _ => None,
+    pub fn get_unknown_gen_many_mut<S>(&mut self, slots: &[S]) -> Vec<Option<(&mut T, Index)>>
+    where
+        S: Copy + Into<Slot>,
+    {
+        let slots: Vec<usize> = slots.iter().map(|&slot| slot.into().0).collect();
+        // Visit the requested slots in ascending order, so that a single pass
+        // over the storage can hand out each one.
+        let mut order: Vec<usize> = (0..slots.len()).collect();
+        order.sort_unstable_by_key(|&i| slots[i]);
+        for pair in order.windows(2) {
+            assert!(
+                slots[pair[0]] != slots[pair[1]],
+                "slot {} requested more than once",
+                slots[pair[0]]
+            );
+        }
+
+        let mut found: Vec<_> = slots.iter().map(|_| None).collect();
+        let mut order = order.into_iter().peekable();
+        let id = self.id;
+        for (slot, entry) in self.items.iter_mut().enumerate() {
+            let i = match order.peek() {
+                Some(&i) if slots[i] == slot => i,
+                Some(_) => continue,
+                None => break,
+            };
+            order.next();
+            if let Entry::Occupied { generation, value } = entry {
+                found[i] = Some((
+                    value,
+                    Index {
+                        index: slot,
+                        generation: *generation,
+                        arena: id,
+                    },
+                ));
+            }
         }
+        found
     }
 
-    /// Get a pair of exclusive references to the elements at index `i1` and `i2` if it is in the
-    /// arena.
-    ///
-    /// If the element at index `i1` or `i2` is not in the arena, then `None` is returned for this
-    /// element.
-    ///
-    /// # Panics
+    /// Get the index of the element that `value` refers to, if it is one of
+    /// this arena's elements.
     ///
-    /// Panics if `i1` and `i2` are pointing to the same item of the arena.
+    /// This is worked out from where `value` lies in the arena's storage, so
+    /// it takes constant time and does not compare values. A reference to an
+    /// equal value elsewhere gives `None`. See the [element
+    /// addresses](struct.Arena.html#element-addresses) contract for how long
+    /// an element's address stays valid.
     ///
     /// # Examples
     ///
@@ -664,68 +3366,45 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// let idx1 = arena.insert(0);
-    /// let idx2 = arena.insert(1);
-    ///
-    /// {
-    ///     let (item1, item2) = arena.get2_mut(idx1, idx2);
-    ///
-    ///     *item1.unwrap() = 3;
-    ///     *item2.unwrap() = 4;
-    /// }
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(1);
     ///
-    /// assert_eq!(arena[idx1], 3);
-    /// assert_eq!(arena[idx2], 4);
+    /// let found = arena.iter().map(|(_, v)| arena.index_of_ref(v)).collect::<Vec<_>>();
+    /// assert_eq!(found, [Some(a), Some(b)]);
+    /// assert_eq!(arena.index_of_ref(&1), None);
     /// ```
-    pub fn get2_mut(&mut self, i1: Index, i2: Index) -> (Option<&mut T>, Option<&mut T>) {
-        let len = self.items.len();
-
-        if i1.index == i2.index {
-            assert!(i1.generation != i2.generation);
-
-            if i1.generation > i2.generation {
-                return (self.get_mut(i1), None);
-            }
-            return (None, self.get_mut(i2));
-        }
-
-        if i1.index >= len {
-            return (None, self.get_mut(i2));
-        } else if i2.index >= len {
-            return (self.get_mut(i1), None);
-        }
-
-        let (raw_item1, raw_item2) = {
-            let (xs, ys) = self.items.split_at_mut(cmp::max(i1.index, i2.index));
-            if i1.index < i2.index {
-                (&mut xs[i1.index], &mut ys[0])
-            } else {
-                (&mut ys[0], &mut xs[i2.index])
-            }
-        };
-
-        let item1 = match raw_item1 {
-            Entry::Occupied {
-                generation,
-                value,
-            } if *generation == i1.generation => Some(value),
-            _ => None,
-        };
-
-        let item2 = match raw_item2 {
+    pub fn index_of_ref(&self, value: &T) -> Option<Index> {
+        let base = self.items.as_ptr() as usize;
+        let addr = value as *const T as usize;
+        let slot = addr.checked_sub(base)? / mem::size_of::<Entry<T>>();
+        match self.items.get(slot)? {
             Entry::Occupied {
                 generation,
-                value,
-            } if *generation == i2.generation => Some(value),
-            _ => None,
-        };
-
-        (item1, item2)
+                value: element,
+            } if core::ptr::eq(element, value) => Some(Index {
+                index: slot,
+                generation: *generation,
+                arena: self.id,
+            }),
+            _ => {
+                // Only elements of type `T` live in the storage, so a `&T`
+                // that points into it must be one of them, unless `T` has no
+                // size and the reference could point anywhere.
+                debug_assert!(
+                    mem::size_of::<T>() == 0,
+                    "reference points into slot {} but not at its element",
+                    slot
+                );
+                None
+            }
+        }
     }
 
-    /// Get the length of this arena.
+    /// Is there an element at slot `i`, whatever its generation?
     ///
-    /// The length is the number of elements the arena holds.
+    /// Like `get_unknown_gen`, this is meant for bitmap-style indexing schemes
+    /// that track raw slots rather than `Index`es. You should use the
+    /// `contains` method instead most of the time.
     ///
     /// # Examples
     ///
@@ -733,22 +3412,31 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// assert_eq!(arena.len(), 0);
-    ///
     /// let idx = arena.insert(42);
-    /// assert_eq!(arena.len(), 1);
+    /// let slot = idx.slot();
     ///
-    /// let _ = arena.insert(0);
-    /// assert_eq!(arena.len(), 2);
-    ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert_eq!(arena.len(), 1);
+    /// assert!(arena.contains_slot(slot));
+    /// arena.remove(idx);
+    /// assert!(!arena.contains_slot(slot));
     /// ```
-    pub fn len(&self) -> usize {
-        self.len
+    pub fn contains_slot(&self, i: impl Into<Slot>) -> bool {
+        matches!(self.items.get(i.into().0), Some(Entry::Occupied { .. }))
     }
 
-    /// Returns true if the arena contains no elements
+    /// Is there an element at slot `i`, whatever its generation?
+    ///
+    /// This is the same as `contains_slot`, named to match the other
+    /// unknown-generation methods.
+    pub fn contains_unknown_gen(&self, i: impl Into<Slot>) -> bool {
+        self.contains_slot(i)
+    }
+
+    /// Given a slot `i` without a generation, remove the element behind
+    /// `i` and return it along with the `Index` it had.
+    ///
+    /// Returns `None` if slot `i` is free or out of bounds.
+    ///
+    /// You should use the `remove` method instead most of the time.
     ///
     /// # Examples
     ///
@@ -756,86 +3444,79 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// assert!(arena.is_empty());
-    ///
     /// let idx = arena.insert(42);
-    /// assert!(!arena.is_empty());
+    /// let slot = idx.slot();
     ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert!(arena.is_empty());
+    /// assert_eq!(arena.remove_unknown_gen(slot), Some((42, idx)));
+    /// assert_eq!(arena.remove_unknown_gen(slot), None);
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    pub fn remove_unknown_gen(&mut self, i: impl Into<Slot>) -> Option<(T, Index)> {
+        let (_, index) = self.get_unknown_gen(i)?;
+        self.remove(index).map(|value| (value, index))
     }
 
-    /// Get the capacity of this arena.
+    /// Get the generation of the element at slot `i`, if there is one.
     ///
-    /// The capacity is the maximum number of elements the arena can hold
-    /// without further allocation, including however many it currently
-    /// contains.
+    /// Generations come from a single counter shared by the whole arena, which
+    /// advances on every removal. So this does not count how often slot `i`
+    /// has been reused, but comparing it against the arena's current
+    /// generation shows how many removals ago the slot was last filled. A
+    /// slot whose element keeps changing always has a recent generation.
+    ///
+    /// Returns `None` if slot `i` is free or out of bounds.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena::Arena;
     ///
-    /// let mut arena = Arena::with_capacity(10);
-    /// assert_eq!(arena.capacity(), 10);
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert('a');
+    /// let slot = idx.slot();
+    /// assert_eq!(arena.slot_generation(slot), Some(0));
     ///
-    /// // `try_insert` does not allocate new capacity.
-    /// for i in 0..10 {
-    ///     assert!(arena.try_insert(1).is_ok());
-    ///     assert_eq!(arena.capacity(), 10);
-    /// }
+    /// arena.remove(idx);
+    /// assert_eq!(arena.slot_generation(slot), None);
     ///
-    /// // But `insert` will if the arena is already at capacity.
-    /// arena.insert(0);
-    /// assert!(arena.capacity() > 10);
+    /// let idx = arena.insert('b');
+    /// assert_eq!(idx.slot(), slot);
+    /// assert_eq!(arena.slot_generation(slot), Some(1));
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.items.len()
+    pub fn slot_generation(&self, i: impl Into<Slot>) -> Option<u64> {
+        match self.items.get(i.into().0) {
+            Some(Entry::Occupied { generation, .. }) => Some(*generation),
+            _ => None,
+        }
     }
 
-    /// Allocate space for `additional_capacity` more elements in the arena.
-    ///
-    /// # Panics
+    /// Get the total number of elements removed from this arena since it was
+    /// created, by any means.
     ///
-    /// Panics if this causes the capacity to overflow.
+    /// Together with `len`, this helps to spot code that churns through
+    /// elements far faster than expected. Arenas created by deserialization
+    /// start counting from zero.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena::Arena;
     ///
-    /// let mut arena = Arena::with_capacity(10);
-    /// arena.reserve(5);
-    /// assert_eq!(arena.capacity(), 15);
-    /// # let _: Arena<usize> = arena;
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(1);
+    /// arena.remove(idx);
+    /// arena.extend(0..10);
+    /// arena.retain(|_, n| *n < 5);
+    /// assert_eq!(arena.removals(), 6);
+    ///
+    /// arena.clear();
+    /// assert_eq!(arena.removals(), 11);
     /// ```
-    pub fn reserve(&mut self, additional_capacity: usize) {
-        let start = self.items.len();
-        let end = self.items.len() + additional_capacity;
-        let old_head = self.free_list_head;
-        self.items.reserve_exact(additional_capacity);
-        self.items.extend((start..end).map(|i| {
-            if i == end - 1 {
-                Entry::Free {
-                    next_free: old_head,
-                }
-            } else {
-                Entry::Free {
-                    next_free: Some(i + 1),
-                }
-            }
-        }));
-        self.free_list_head = Some(start);
+    pub fn removals(&self) -> u64 {
+        self.removals
     }
 
-    /// Iterate over shared references to the elements in this arena.
-    ///
-    /// Yields pairs of `(Index, &T)` items.
-    ///
-    /// Order of iteration is not defined.
+    /// Get the arena's current generation, which the next inserted element
+    /// will be stamped with.
     ///
     /// # Examples
     ///
@@ -843,126 +3524,231 @@ impl<T> Arena<T> {
     /// use generational_arena::Arena;
     ///
     /// let mut arena = Arena::new();
-    /// for i in 0..10 {
-    ///     arena.insert(i * i);
-    /// }
+    /// assert_eq!(arena.generation(), 0);
     ///
-    /// for (idx, value) in arena.iter() {
-    ///     println!("{} is at index {:?}", value, idx);
-    /// }
+    /// let idx = arena.insert(1);
+    /// arena.remove(idx);
+    /// assert_eq!(arena.generation(), 1);
     /// ```
-    pub fn iter(&self) -> Iter<T> {
-        Iter {
-            len: self.len,
-            inner: self.items.iter().enumerate(),
-        }
+    pub fn generation(&self) -> u64 {
+        self.generation
     }
 
-    /// Iterate over exclusive references to the elements in this arena.
+    /// Feed the arena's contents into `hasher`, to fingerprint its state.
     ///
-    /// Yields pairs of `(Index, &mut T)` items.
+    /// Each element is hashed along with its slot and generation, in
+    /// ascending slot order, followed by the number of elements. Slots and
+    /// generations are always written as `u64`s, so two arenas with the same
+    /// contents produce the same sequence of writes on every platform, as long
+    /// as `T`'s own `Hash` implementation is platform independent. Free slots
+    /// and spare capacity do not affect the result.
     ///
-    /// Order of iteration is not defined.
+    /// This is meant for cheaply detecting whether two copies of an arena,
+    /// such as those of peers in a lockstep simulation, have diverged.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena::Arena;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
     ///
-    /// let mut arena = Arena::new();
-    /// for i in 0..10 {
-    ///     arena.insert(i * i);
+    /// fn fingerprint(arena: &Arena<u32>) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     arena.fingerprint(&mut hasher);
+    ///     hasher.finish()
     /// }
     ///
-    /// for (_idx, value) in arena.iter_mut() {
-    ///     *value += 5;
+    /// let mut ours = Arena::new();
+    /// let mut theirs = Arena::with_capacity(100);
+    /// for arena in [&mut ours, &mut theirs] {
+    ///     let idx = arena.insert(1);
+    ///     arena.insert(2);
+    ///     arena.remove(idx);
     /// }
+    /// assert_eq!(fingerprint(&ours), fingerprint(&theirs));
+    ///
+    /// theirs.insert(3);
+    /// assert_ne!(fingerprint(&ours), fingerprint(&theirs));
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        IterMut {
-            len: self.len,
-            inner: self.items.iter_mut().enumerate(),
+    pub fn fingerprint<H: Hasher>(&self, hasher: &mut H)
+    where
+        T: Hash,
+    {
+        for (slot, entry) in self.items.iter().enumerate() {
+            if let Entry::Occupied { generation, value } = entry {
+                hasher.write_u64(slot as u64);
+                hasher.write_u64(*generation);
+                value.hash(hasher);
+            }
         }
+        hasher.write_u64(self.len as u64);
     }
-
-    /// Iterate over elements of the arena and remove them.
-    ///
-    /// Yields pairs of `(Index, T)` items.
-    ///
-    /// Order of iteration is not defined.
+
+    /// Write a bitmap of which slots are occupied into `bitmap`.
     ///
-    /// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+    /// Bit `i % 64` of `bitmap[i / 64]` is set if slot `i` holds an element,
+    /// and cleared otherwise. Every word of `bitmap` is overwritten. Slots
+    /// past the end of `bitmap` are not recorded, so pass at least
+    /// `arena.capacity().div_ceil(64)` words to capture the whole arena.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena::Arena;
     ///
-    /// let mut arena = Arena::new();
-    /// let idx_1 = arena.insert("hello");
-    /// let idx_2 = arena.insert("world");
-    ///
-    /// assert!(arena.get(idx_1).is_some());
-    /// assert!(arena.get(idx_2).is_some());
-    /// for (idx, value) in arena.drain() {
-    ///     assert!((idx == idx_1 && value == "hello") || (idx == idx_2 && value == "world"));
+    /// let mut arena = Arena::with_capacity(70);
+    /// let a = arena.insert('a');
+    /// for _ in 0..68 {
+    ///     arena.insert('_');
     /// }
-    /// assert!(arena.get(idx_1).is_none());
-    /// assert!(arena.get(idx_2).is_none());
+    /// arena.remove(a);
+    ///
+    /// let mut bitmap = [0; 2];
+    /// arena.collect_occupancy(&mut bitmap);
+    /// assert_eq!(bitmap, [!1, 0b11111]);
     /// ```
-    pub fn drain(&mut self) -> Drain<T> {
-        let old_len = self.len;
-        if !self.is_empty() {
-            // Increment generation, but if there are no elements, do nothing to
-            // avoid unnecessary incrementing generation.
-            self.generation += 1;
+    pub fn collect_occupancy(&self, bitmap: &mut [u64]) {
+        for (i, word) in bitmap.iter_mut().enumerate() {
+            *word = self.occupancy.get(i).copied().unwrap_or(0);
         }
-        self.free_list_head = None;
-        self.len = 0;
-        Drain {
-            len: old_len,
-            inner: self.items.drain(..).enumerate(),
+    }
+
+    /// Add the free slot `i` to the free list, according to the arena's
+    /// reuse policy.
+    fn push_free(&mut self, i: usize) {
+        match self.reuse {
+            ReusePolicy::Lifo => {
+                self.items[i] = Entry::Free {
+                    next_free: self.free_list_head,
+                };
+                if self.free_list_head.is_none() {
+                    self.free_list_tail = Some(i);
+                }
+                self.free_list_head = Some(i);
+            }
+            ReusePolicy::Fifo => {
+                self.items[i] = Entry::Free { next_free: None };
+                match self.free_list_tail {
+                    Some(tail) => match &mut self.items[tail] {
+                        Entry::Free { next_free } => *next_free = Some(i),
+                        Entry::Occupied { .. } => panic!("corrupt free list"),
+                    },
+                    None => self.free_list_head = Some(i),
+                }
+                self.free_list_tail = Some(i);
+            }
+            ReusePolicy::Stack => {
+                self.items[i] = Entry::Free { next_free: None };
+                self.free_stack.push(i);
+            }
         }
+        self.vacate(i);
     }
 
-    /// Given an i of `usize` without a generation, get a shared reference
-    /// to the element and the matching `Index` of the entry behind `i`.
-    ///
-    /// This method is useful when you know there might be an element at the
-    /// position i, but don't know its generation or precise Index.
-    ///
-    /// Use cases include using indexing such as Hierarchical BitMap Indexing or
-    /// other kinds of bit-efficient indexing.
-    ///
-    /// You should use the `get` method instead most of the time.
-    pub fn get_unknown_gen(&self, i: usize) -> Option<(&T, Index)> {
-        match self.items.get(i) {
-            Some(Entry::Occupied {
-                generation,
-                value,
-            }) => Some((value, Index { generation: *generation, index: i})),
-            _ => None,
+    /// Clear slot `i`'s bit in `occupancy`, and shrink `occupied` past the
+    /// free slots next to it if it was the lowest or highest element.
+    fn vacate(&mut self, i: usize) {
+        if let Some(word) = self.occupancy.get_mut(i / 64) {
+            *word &= !(1 << (i % 64));
+        }
+        if i + 1 == self.occupied.end {
+            self.occupied = match self.last_occupied_before(i) {
+                Some(last) => self.occupied.start..last + 1,
+                None => 0..0,
+            };
+        } else if i == self.occupied.start {
+            let first = self
+                .first_occupied_from(i + 1)
+                .expect("the highest element is above the removed one");
+            self.occupied.start = first;
         }
     }
 
-    /// Given an i of `usize` without a generation, get an exclusive reference
-    /// to the element and the matching `Index` of the entry behind `i`.
-    ///
-    /// This method is useful when you know there might be an element at the
-    /// position i, but don't know its generation or precise Index.
-    ///
-    /// Use cases include using indexing such as Hierarchical BitMap Indexing or
-    /// other kinds of bit-efficient indexing.
+    /// The highest occupied slot below `end`, if any.
+    fn last_occupied_before(&self, end: usize) -> Option<usize> {
+        let mut word = cmp::min(end / 64, self.occupancy.len());
+        let mut bits = match self.occupancy.get(word) {
+            Some(bits) => bits & ((1 << (end % 64)) - 1),
+            None => 0,
+        };
+        while bits == 0 {
+            word = word.checked_sub(1)?;
+            bits = self.occupancy[word];
+        }
+        Some(word * 64 + 63 - bits.leading_zeros() as usize)
+    }
+
+    /// The lowest occupied slot at or above `start`, if any.
+    fn first_occupied_from(&self, start: usize) -> Option<usize> {
+        let mut word = start / 64;
+        let mut bits = self.occupancy.get(word)? & (!0 << (start % 64));
+        while bits == 0 {
+            word += 1;
+            bits = *self.occupancy.get(word)?;
+        }
+        Some(word * 64 + bits.trailing_zeros() as usize)
+    }
+
+    /// Recompute `occupancy` and `occupied` from the entries in `items`.
+    fn rebuild_occupancy(&mut self) {
+        self.occupancy.clear();
+        self.occupancy.resize(occupancy_words(self.items.len()), 0);
+        for (i, entry) in self.items.iter().enumerate() {
+            if let Entry::Occupied { .. } = entry {
+                self.occupancy[i / 64] |= 1 << (i % 64);
+            }
+        }
+        self.occupied = match self.first_occupied_from(0) {
+            Some(first) => first..self.last_occupied_before(self.items.len()).unwrap() + 1,
+            None => 0..0,
+        };
+    }
+
+    /// Recompute `len`, `occupied` and the free list from the entries in
+    /// `items`.
     ///
-    /// You should use the `get_mut` method instead most of the time.
-    pub fn get_unknown_gen_mut(&mut self, i: usize) -> Option<(&mut T, Index)> {
-        match self.items.get_mut(i) {
-            Some(Entry::Occupied {
-                generation,
-                value,
-            }) => Some((value, Index { generation: *generation, index: i})),
-            _ => None,
+    /// The free list is rebuilt in ascending slot order.
+    fn rebuild_free_list(&mut self) {
+        if self.reuse == ReusePolicy::Stack {
+            return self.rebuild_free_stack();
         }
+        let mut free_list_head = None;
+        let mut free_list_tail = None;
+        let mut len = self.items.len();
+        // Iterates `self.items` in reverse order so that the free list
+        // concatenates indices in ascending order.
+        for (idx, entry) in self.items.iter_mut().enumerate().rev() {
+            if let Entry::Free { next_free } = entry {
+                *next_free = free_list_head;
+                free_list_head = Some(idx);
+                free_list_tail = free_list_tail.or(Some(idx));
+                len -= 1;
+            }
+        }
+        self.free_list_head = free_list_head;
+        self.free_list_tail = free_list_tail;
+        self.len = len;
+        self.rebuild_occupancy();
+    }
+
+    /// Like `rebuild_free_list`, for arenas that keep their free slots in
+    /// `free_stack`.
+    fn rebuild_free_stack(&mut self) {
+        self.free_stack.clear();
+        let mut len = self.items.len();
+        // Pushes in reverse order so that the lowest slot is reused first.
+        for (idx, entry) in self.items.iter_mut().enumerate().rev() {
+            if let Entry::Free { next_free } = entry {
+                *next_free = None;
+                self.free_stack.push(idx);
+                len -= 1;
+            }
+        }
+        self.free_list_head = None;
+        self.free_list_tail = None;
+        self.len = len;
+        self.rebuild_occupancy();
     }
 }
 
@@ -1007,6 +3793,9 @@ impl<T> Iterator for IntoIter<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next() {
                 Some(Entry::Free { .. }) => continue,
@@ -1025,10 +3814,46 @@ impl<T> Iterator for IntoIter<T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.len, Some(self.len))
     }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // When every remaining slot is occupied, skip straight to the `n`th
+        // element rather than checking each entry on the way.
+        if n > 0 && self.len > 0 && self.len == self.inner.len() {
+            let n = cmp::min(n, self.len);
+            self.inner.nth(n - 1);
+            self.len -= n;
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, |acc, entry| match entry {
+            Entry::Occupied { value, .. } => f(acc, value),
+            Entry::Free { .. } => acc,
+        })
+    }
 }
 
 impl<T> DoubleEndedIterator for IntoIter<T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next_back() {
                 Some(Entry::Free { .. }) => continue,
@@ -1053,6 +3878,119 @@ impl<T> ExactSizeIterator for IntoIter<T> {
 
 impl<T> FusedIterator for IntoIter<T> {}
 
+/// An iterator that moves elements out of an arena, along with their
+/// indices.
+///
+/// Yields pairs of `(Index, T)` items, in ascending slot order.
+///
+/// Created by `Arena::into_iter_indexed`.
+#[derive(Clone, Debug)]
+pub struct IntoIterIndexed<T> {
+    len: usize,
+    offset: usize,
+    arena: ArenaId,
+    inner: vec::IntoIter<Entry<T>>,
+}
+
+impl<T> Iterator for IntoIterIndexed<T> {
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        loop {
+            match self.inner.next() {
+                Some(Entry::Free { .. }) => {
+                    self.offset += 1;
+                    continue;
+                }
+                Some(Entry::Occupied { generation, value }) => {
+                    let idx = Index {
+                        index: self.offset,
+                        generation,
+                        arena: self.arena,
+                    };
+                    self.offset += 1;
+                    self.len -= 1;
+                    return Some((idx, value));
+                }
+                None => {
+                    debug_assert_eq!(self.len, 0);
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let offset = self.offset;
+        let arena = self.arena;
+        self.inner
+            .enumerate()
+            .fold(init, |acc, (i, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    let idx = Index {
+                        index: offset + i,
+                        generation,
+                        arena,
+                    };
+                    f(acc, (idx, value))
+                }
+                Entry::Free { .. } => acc,
+            })
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIterIndexed<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        loop {
+            match self.inner.next_back() {
+                Some(Entry::Free { .. }) => continue,
+                Some(Entry::Occupied { generation, value }) => {
+                    let idx = Index {
+                        index: self.offset + self.inner.len(),
+                        generation,
+                        arena: self.arena,
+                    };
+                    self.len -= 1;
+                    return Some((idx, value));
+                }
+                None => {
+                    debug_assert_eq!(self.len, 0);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIterIndexed<T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> FusedIterator for IntoIterIndexed<T> {}
+
 impl<'a, T> IntoIterator for &'a Arena<T> {
     type Item = (Index, &'a T);
     type IntoIter = Iter<'a, T>;
@@ -1084,25 +4022,35 @@ impl<'a, T> IntoIterator for &'a Arena<T> {
 #[derive(Clone, Debug)]
 pub struct Iter<'a, T: 'a> {
     len: usize,
-    inner: iter::Enumerate<slice::Iter<'a, Entry<T>>>,
+    offset: usize,
+    arena: ArenaId,
+    inner: slice::Iter<'a, Entry<T>>,
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = (Index, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next() {
-                Some((_, &Entry::Free { .. })) => continue,
-                Some((
-                    index,
-                    &Entry::Occupied {
-                        generation,
-                        ref value,
-                    },
-                )) => {
+                Some(&Entry::Free { .. }) => {
+                    self.offset += 1;
+                    continue;
+                }
+                Some(&Entry::Occupied {
+                    generation,
+                    ref value,
+                }) => {
                     self.len -= 1;
-                    let idx = Index { index, generation };
+                    let idx = Index {
+                        index: self.offset,
+                        generation,
+                        arena: self.arena,
+                    };
+                    self.offset += 1;
                     return Some((idx, value));
                 }
                 None => {
@@ -1116,22 +4064,74 @@ impl<'a, T> Iterator for Iter<'a, T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.len, Some(self.len))
     }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // When every remaining slot is occupied, skip straight to the `n`th
+        // element rather than checking each entry on the way.
+        if n > 0 && self.len > 0 && self.len == self.inner.len() {
+            let n = cmp::min(n, self.len);
+            self.inner.nth(n - 1);
+            self.offset += n;
+            self.len -= n;
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let offset = self.offset;
+        let arena = self.arena;
+        self.inner
+            .enumerate()
+            .fold(init, |acc, (i, entry)| match entry {
+                Entry::Occupied {
+                    generation,
+                    value,
+                } => {
+                    let idx = Index {
+                        index: offset + i,
+                        generation: *generation,
+                        arena,
+                    };
+                    f(acc, (idx, value))
+                }
+                Entry::Free { .. } => acc,
+            })
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next_back() {
-                Some((_, &Entry::Free { .. })) => continue,
-                Some((
-                    index,
-                    &Entry::Occupied {
-                        generation,
-                        ref value,
-                    },
-                )) => {
+                Some(&Entry::Free { .. }) => continue,
+                Some(&Entry::Occupied {
+                    generation,
+                    ref value,
+                }) => {
                     self.len -= 1;
-                    let idx = Index { index, generation };
+                    let idx = Index {
+                        index: self.offset + self.inner.len(),
+                        generation,
+                        arena: self.arena,
+                    };
                     return Some((idx, value));
                 }
                 None => {
@@ -1141,15 +4141,129 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
             }
         }
     }
-}
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+/// An iterator over the indices of the elements in an arena.
+///
+/// Created by `Arena::indices`.
+#[derive(Clone, Debug)]
+pub struct Indices<'a, T: 'a> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Indices<'a, T> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(idx, _)| idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self.inner.count()
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.inner.last().map(|(idx, _)| idx)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.inner.nth(n).map(|(idx, _)| idx)
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.inner.fold(init, |acc, (idx, _)| f(acc, idx))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Indices<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(idx, _)| idx)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Indices<'a, T> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'a, T> FusedIterator for Indices<'a, T> {}
+
+/// An iterator over shared references to the elements of an arena, starting
+/// partway through its storage.
+///
+/// Yields pairs of `(Index, &T)` items in ascending slot order.
+///
+/// Created by `Arena::iter_occupied_from` and `Arena::occupied_slots_in`.
+#[derive(Clone, Debug)]
+pub struct IterFrom<'a, T: 'a> {
+    offset: usize,
+    // Where `offset` ends up once the iterator is exhausted.
+    stop: usize,
+    arena: ArenaId,
+    inner: slice::Iter<'a, Entry<T>>,
+}
+
+impl<'a, T> IterFrom<'a, T> {
+    /// The raw slot to pass to `Arena::iter_occupied_from` to resume
+    /// iteration where this iterator left off.
+    ///
+    /// Once the iterator is exhausted, this is the arena's slot count, or the
+    /// end of the range for `Arena::occupied_slots_in`.
+    pub fn next_slot(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a, T> Iterator for IterFrom<'a, T> {
+    type Item = (Index, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.inner.next() {
+                Some(entry) => entry,
+                None => {
+                    // Every slot after the last one visited is free.
+                    self.offset = self.stop;
+                    return None;
+                }
+            };
+            match entry {
+                Entry::Free { .. } => self.offset += 1,
+                Entry::Occupied { generation, value } => {
+                    let idx = Index {
+                        index: self.offset,
+                        generation: *generation,
+                        arena: self.arena,
+                    };
+                    self.offset += 1;
+                    return Some((idx, value));
+                }
+            }
+        }
+    }
 
-impl<'a, T> ExactSizeIterator for Iter<'a, T> {
-    fn len(&self) -> usize {
-        self.len
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.inner.len()))
     }
 }
 
-impl<'a, T> FusedIterator for Iter<'a, T> {}
+impl<'a, T> FusedIterator for IterFrom<'a, T> {}
 
 impl<'a, T> IntoIterator for &'a mut Arena<T> {
     type Item = (Index, &'a mut T);
@@ -1182,25 +4296,113 @@ impl<'a, T> IntoIterator for &'a mut Arena<T> {
 #[derive(Debug)]
 pub struct IterMut<'a, T: 'a> {
     len: usize,
-    inner: iter::Enumerate<slice::IterMut<'a, Entry<T>>>,
+    offset: usize,
+    arena: ArenaId,
+    inner: slice::IterMut<'a, Entry<T>>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    /// View the elements that have not been yielded yet as a shared iterator.
+    ///
+    /// This allows peeking ahead without advancing this iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// let mut iter = arena.iter_mut();
+    /// assert_eq!(iter.as_iter().next(), Some((a, &1)));
+    /// iter.next();
+    /// let remaining: Vec<_> = iter.as_iter().map(|(idx, _)| idx).collect();
+    /// assert_eq!(remaining, vec![b]);
+    /// ```
+    pub fn as_iter(&self) -> Iter<'_, T> {
+        Iter {
+            len: self.len,
+            offset: self.offset,
+            arena: self.arena,
+            inner: self.inner.as_slice().iter(),
+        }
+    }
+
+    /// Divide the elements that have not been yielded yet into two disjoint
+    /// iterators: one over those in slots before `slot`, and one over the
+    /// rest.
+    ///
+    /// The halves can be sent to different threads and consumed
+    /// concurrently, and split again to balance the work. If `slot` is
+    /// outside the remaining range of slots, one of the halves is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::thread;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..1000);
+    ///
+    /// let mid = arena.slot_count() / 2;
+    /// let (front, back) = arena.iter_mut().split_at(mid);
+    /// thread::scope(|s| {
+    ///     s.spawn(|| front.for_each(|(_, n)| *n *= 2));
+    ///     s.spawn(|| back.for_each(|(_, n)| *n *= 2));
+    /// });
+    /// assert!(arena.iter().all(|(_, n)| n % 2 == 0));
+    /// ```
+    pub fn split_at(self, slot: usize) -> (IterMut<'a, T>, IterMut<'a, T>) {
+        let entries = self.inner.into_slice();
+        let mid = cmp::min(slot.saturating_sub(self.offset), entries.len());
+        let (front, back) = entries.split_at_mut(mid);
+        let front_len = front
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied { .. }))
+            .count();
+        let front = IterMut {
+            len: front_len,
+            offset: self.offset,
+            arena: self.arena,
+            inner: front.iter_mut(),
+        };
+        let back = IterMut {
+            len: self.len - front_len,
+            offset: self.offset + mid,
+            arena: self.arena,
+            inner: back.iter_mut(),
+        };
+        (front, back)
+    }
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = (Index, &'a mut T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next() {
-                Some((_, &mut Entry::Free { .. })) => continue,
-                Some((
-                    index,
-                    &mut Entry::Occupied {
-                        generation,
-                        ref mut value,
-                    },
-                )) => {
+                Some(&mut Entry::Free { .. }) => {
+                    self.offset += 1;
+                    continue;
+                }
+                Some(&mut Entry::Occupied {
+                    generation,
+                    ref mut value,
+                }) => {
                     self.len -= 1;
-                    let idx = Index { index, generation };
+                    let idx = Index {
+                        index: self.offset,
+                        generation,
+                        arena: self.arena,
+                    };
+                    self.offset += 1;
                     return Some((idx, value));
                 }
                 None => {
@@ -1214,22 +4416,74 @@ impl<'a, T> Iterator for IterMut<'a, T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.len, Some(self.len))
     }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // When every remaining slot is occupied, skip straight to the `n`th
+        // element rather than checking each entry on the way.
+        if n > 0 && self.len > 0 && self.len == self.inner.len() {
+            let n = cmp::min(n, self.len);
+            self.inner.nth(n - 1);
+            self.offset += n;
+            self.len -= n;
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let offset = self.offset;
+        let arena = self.arena;
+        self.inner
+            .enumerate()
+            .fold(init, |acc, (i, entry)| match entry {
+                Entry::Occupied {
+                    generation,
+                    value,
+                } => {
+                    let idx = Index {
+                        index: offset + i,
+                        generation: *generation,
+                        arena,
+                    };
+                    f(acc, (idx, value))
+                }
+                Entry::Free { .. } => acc,
+            })
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next_back() {
-                Some((_, &mut Entry::Free { .. })) => continue,
-                Some((
-                    index,
-                    &mut Entry::Occupied {
-                        generation,
-                        ref mut value,
-                    },
-                )) => {
+                Some(&mut Entry::Free { .. }) => continue,
+                Some(&mut Entry::Occupied {
+                    generation,
+                    ref mut value,
+                }) => {
                     self.len -= 1;
-                    let idx = Index { index, generation };
+                    let idx = Index {
+                        index: self.offset + self.inner.len(),
+                        generation,
+                        arena: self.arena,
+                    };
                     return Some((idx, value));
                 }
                 None => {
@@ -1277,18 +4531,62 @@ impl<'a, T> FusedIterator for IterMut<'a, T> {}
 #[derive(Debug)]
 pub struct Drain<'a, T: 'a> {
     len: usize,
-    inner: iter::Enumerate<vec::Drain<'a, Entry<T>>>,
+    offset: usize,
+    arena: ArenaId,
+    inner: vec::Drain<'a, Entry<T>>,
+}
+
+impl<'a, T> Drain<'a, T> {
+    /// View the elements that have not been yielded yet as a shared iterator.
+    ///
+    /// This allows peeking ahead without removing anything from this
+    /// iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert("a");
+    /// arena.insert("b");
+    ///
+    /// let mut drain = arena.drain();
+    /// assert_eq!(drain.as_iter().len(), 2);
+    /// assert_eq!(drain.as_iter().next(), Some((a, &"a")));
+    /// assert_eq!(drain.next(), Some((a, "a")));
+    /// assert_eq!(drain.as_iter().len(), 1);
+    /// ```
+    pub fn as_iter(&self) -> Iter<'_, T> {
+        Iter {
+            len: self.len,
+            offset: self.offset,
+            arena: self.arena,
+            inner: self.inner.as_slice().iter(),
+        }
+    }
 }
 
 impl<'a, T> Iterator for Drain<'a, T> {
     type Item = (Index, T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next() {
-                Some((_, Entry::Free { .. })) => continue,
-                Some((index, Entry::Occupied { generation, value })) => {
-                    let idx = Index { index, generation };
+                Some(Entry::Free { .. }) => {
+                    self.offset += 1;
+                    continue;
+                }
+                Some(Entry::Occupied { generation, value }) => {
+                    let idx = Index {
+                        index: self.offset,
+                        generation,
+                        arena: self.arena,
+                    };
+                    self.offset += 1;
                     self.len -= 1;
                     return Some((idx, value));
                 }
@@ -1303,15 +4601,67 @@ impl<'a, T> Iterator for Drain<'a, T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.len, Some(self.len))
     }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // When every remaining slot is occupied, skip straight to the `n`th
+        // element rather than checking each entry on the way.
+        if n > 0 && self.len > 0 && self.len == self.inner.len() {
+            let n = cmp::min(n, self.len);
+            self.inner.nth(n - 1);
+            self.offset += n;
+            self.len -= n;
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let offset = self.offset;
+        let arena = self.arena;
+        self.inner
+            .enumerate()
+            .fold(init, |acc, (i, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    let idx = Index {
+                        index: offset + i,
+                        generation,
+                        arena,
+                    };
+                    f(acc, (idx, value))
+                }
+                Entry::Free { .. } => acc,
+            })
+    }
 }
 
 impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
         loop {
             match self.inner.next_back() {
-                Some((_, Entry::Free { .. })) => continue,
-                Some((index, Entry::Occupied { generation, value })) => {
-                    let idx = Index { index, generation };
+                Some(Entry::Free { .. }) => continue,
+                Some(Entry::Occupied { generation, value }) => {
+                    let idx = Index {
+                        index: self.offset + self.inner.len(),
+                        generation,
+                        arena: self.arena,
+                    };
                     self.len -= 1;
                     return Some((idx, value));
                 }
@@ -1332,8 +4682,263 @@ impl<'a, T> ExactSizeIterator for Drain<'a, T> {
 
 impl<'a, T> FusedIterator for Drain<'a, T> {}
 
+/// An iterator that removes elements from the arena, yielding only their
+/// indices.
+///
+/// Yields `Index` items.
+///
+/// Order of iteration is not defined.
+///
+/// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::Arena;
+///
+/// let mut arena = Arena::new();
+/// let idx = arena.insert("hello");
+///
+/// for removed in arena.drain_indices() {
+///     assert_eq!(removed, idx);
+/// }
+/// assert!(!arena.contains(idx));
+/// ```
+#[derive(Debug)]
+pub struct DrainIndices<'a, T: 'a> {
+    arena: &'a mut Arena<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T> DrainIndices<'a, T> {
+    /// Free the slot `i` if it is occupied, returning its index.
+    ///
+    /// The arena stays consistent after every call, so nothing is lost if
+    /// this iterator is leaked.
+    fn free(&mut self, i: usize) -> Option<Index> {
+        match self.arena.items[i] {
+            Entry::Occupied { generation, .. } => {
+                self.arena.push_free(i);
+                self.arena.len -= 1;
+                self.arena.removals += 1;
+                Some(Index {
+                    index: i,
+                    generation,
+                    arena: self.arena.id,
+                })
+            }
+            Entry::Free { .. } => None,
+        }
+    }
+}
+
+impl<'a, T> Iterator for DrainIndices<'a, T> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.start < self.end && self.arena.len > 0 {
+            let i = self.start;
+            self.start += 1;
+            if let Some(idx) = self.free(i) {
+                return Some(idx);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.arena.len, Some(self.arena.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DrainIndices<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.start < self.end && self.arena.len > 0 {
+            self.end -= 1;
+            let i = self.end;
+            if let Some(idx) = self.free(i) {
+                return Some(idx);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DrainIndices<'a, T> {
+    fn len(&self) -> usize {
+        self.arena.len
+    }
+}
+
+impl<'a, T> FusedIterator for DrainIndices<'a, T> {}
+
+impl<'a, T> Drop for DrainIndices<'a, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// An iterator that removes the elements in a range of slots from the arena.
+///
+/// Yields pairs of `(Index, T)` items in ascending slot order.
+///
+/// Note: All elements in the range are removed even if the iterator is only
+/// partially consumed or not consumed at all.
+///
+/// Created by `Arena::drain_range`.
+#[derive(Debug)]
+pub struct DrainRange<'a, T: 'a> {
+    arena: &'a mut Arena<T>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T> DrainRange<'a, T> {
+    /// Take the element out of slot `i` if it is occupied.
+    ///
+    /// The arena stays consistent after every call, so nothing is lost if
+    /// this iterator is leaked.
+    fn take(&mut self, i: usize) -> Option<(Index, T)> {
+        if !matches!(self.arena.items[i], Entry::Occupied { .. }) {
+            return None;
+        }
+        let entry = mem::replace(&mut self.arena.items[i], Entry::Free { next_free: None });
+        self.arena.push_free(i);
+        self.arena.len -= 1;
+        self.arena.removals += 1;
+        match entry {
+            Entry::Occupied { generation, value } => {
+                let idx = Index {
+                    index: i,
+                    generation,
+                    arena: self.arena.id,
+                };
+                Some((idx, value))
+            }
+            Entry::Free { .. } => unreachable!(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for DrainRange<'a, T> {
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.start < self.end {
+            let i = self.start;
+            self.start += 1;
+            if let Some(item) = self.take(i) {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DrainRange<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.start < self.end {
+            self.end -= 1;
+            let i = self.end;
+            if let Some(item) = self.take(i) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> FusedIterator for DrainRange<'a, T> {}
+
+impl<'a, T> Drop for DrainRange<'a, T> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// An iterator that removes the elements a predicate selects from the arena.
+///
+/// Yields pairs of `(Index, T)` items in ascending slot order. Elements the
+/// iterator has not visited when it is dropped are kept.
+///
+/// Created by `Arena::drain_filter`.
+pub struct DrainFilter<'a, T: 'a, F> {
+    arena: &'a mut Arena<T>,
+    predicate: F,
+    next: usize,
+    end: usize,
+    bumped: bool,
+}
+
+impl<'a, T: fmt::Debug, F> fmt::Debug for DrainFilter<'a, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DrainFilter")
+            .field("arena", &self.arena)
+            .field("next", &self.next)
+            .field("end", &self.end)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, F> Iterator for DrainFilter<'a, T, F>
+where
+    F: FnMut(Index, &mut T) -> bool,
+{
+    type Item = (Index, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.end {
+            let i = self.next;
+            self.next += 1;
+            let index = match &mut self.arena.items[i] {
+                Entry::Occupied { generation, value } => {
+                    let index = Index {
+                        index: i,
+                        generation: *generation,
+                        arena: self.arena.id,
+                    };
+                    if !(self.predicate)(index, value) {
+                        continue;
+                    }
+                    index
+                }
+                Entry::Free { .. } => continue,
+            };
+            if !self.bumped {
+                // As in `remove_where`, one bump covers every removal.
+                self.arena.generation += 1;
+                self.bumped = true;
+            }
+            let entry = mem::replace(&mut self.arena.items[i], Entry::Free { next_free: None });
+            self.arena.push_free(i);
+            self.arena.len -= 1;
+            self.arena.removals += 1;
+            match entry {
+                Entry::Occupied { value, .. } => return Some((index, value)),
+                Entry::Free { .. } => unreachable!(),
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.next))
+    }
+}
+
+impl<'a, T, F> FusedIterator for DrainFilter<'a, T, F> where F: FnMut(Index, &mut T) -> bool {}
+
 impl<T> Extend<T> for Arena<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // Like `Vec`, trust the lower bound, which is never too big for a
+        // correct iterator, and let later insertions grow the rest of the way.
+        self.reserve(iter.size_hint().0);
         for t in iter {
             self.insert(t);
         }
@@ -1352,16 +4957,106 @@ impl<T> FromIterator<T> for Arena<T> {
     }
 }
 
+/// Element `i` of the vector is placed in slot `i`, with generation 0, so its
+/// index is `Index::from_raw_parts(i, 0)`. This makes handles into arenas
+/// built from fixed data, e.g. test fixtures or lookup tables, predictable.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{Arena, Index};
+///
+/// let arena = Arena::from(vec!["zero", "one", "two"]);
+/// assert_eq!(arena[Index::from_raw_parts(1, 0)], "one");
+/// ```
+impl<T> From<Vec<T>> for Arena<T> {
+    fn from(values: Vec<T>) -> Arena<T> {
+        if values.is_empty() {
+            return Arena::new();
+        }
+        let items: Vec<_> = values
+            .into_iter()
+            .map(|value| Entry::Occupied {
+                generation: 0,
+                value,
+            })
+            .collect();
+        let len = items.len();
+        let mut arena = Arena {
+            items,
+            generation: 0,
+            free_list_head: None,
+            free_list_tail: None,
+            free_stack: Vec::new(),
+            len,
+            occupied: 0..len,
+            occupancy: Vec::new(),
+            reuse: ReusePolicy::default(),
+            id: ArenaId::fresh(),
+            removals: 0,
+            growth_percent: DEFAULT_GROWTH_PERCENT,
+        };
+        arena.rebuild_occupancy();
+        arena
+    }
+}
+
+/// Element `i` of the array is placed in slot `i`, with generation 0, as
+/// with `From<Vec<T>>`.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{Arena, Index};
+///
+/// let arena = Arena::from([10, 20, 30]);
+/// let total: i32 = (0..3).map(|i| arena[Index::from_raw_parts(i, 0)]).sum();
+/// assert_eq!(total, 60);
+/// ```
+impl<T, const N: usize> From<[T; N]> for Arena<T> {
+    fn from(values: [T; N]) -> Arena<T> {
+        Arena::from(Vec::from(values))
+    }
+}
+
 impl<T> ops::Index<Index> for Arena<T> {
     type Output = T;
 
+    #[inline]
     fn index(&self, index: Index) -> &Self::Output {
-        self.get(index).expect("No element at index")
+        match self.get(index) {
+            Some(value) => value,
+            None => lookup_failed(LookupError::new(index, &self.items)),
+        }
     }
 }
 
 impl<T> ops::IndexMut<Index> for Arena<T> {
+    #[inline]
     fn index_mut(&mut self, index: Index) -> &mut Self::Output {
-        self.get_mut(index).expect("No element at index")
+        index.check_arena(self.id);
+        let slot_count = self.items.len();
+        // Without going through `get_mut`, so that the miss paths can still
+        // describe the slot while the hit path returns a borrow of it.
+        let state = match self.items.get_mut(index.index) {
+            Some(Entry::Occupied { generation, value }) => {
+                if *generation == index.generation {
+                    return value;
+                }
+                SlotState::Occupied {
+                    generation: *generation,
+                }
+            }
+            Some(Entry::Free { .. }) => SlotState::Free,
+            None => SlotState::OutOfBounds { slot_count },
+        };
+        lookup_failed(LookupError { index, state })
     }
 }
+
+// Kept out of line so that indexing inlines to just the lookup.
+#[cold]
+#[inline(never)]
+fn lookup_failed(error: LookupError) -> ! {
+    panic!("{}", error);
+}
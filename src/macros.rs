@@ -0,0 +1,112 @@
+/// Define a newtype wrapper around `Index`.
+///
+/// The crate docs recommend wrapping `Index` in a newtype like `pub struct
+/// MyIdentifier(Index);` so that handles into different arenas can't be
+/// mixed up. This macro writes the boilerplate for such a wrapper: it derives
+/// `Clone`, `Copy`, `Debug`, `PartialEq`, `Eq`, `PartialOrd`, `Ord` and
+/// `Hash`, converts to and from `Index` with `From`, forwards `Display`, and
+/// provides `from_raw_parts` and `into_raw_parts`. With this crate's "serde"
+/// feature enabled, it also implements `Serialize` and `Deserialize` in the
+/// same format as `Index`.
+///
+/// Attributes and doc comments on the struct are kept.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{arena_index, Arena};
+///
+/// arena_index! {
+///     /// A handle to an entity.
+///     pub struct EntityId;
+/// }
+///
+/// let mut entities = Arena::new();
+/// let id = EntityId::from(entities.insert("player"));
+/// assert_eq!(entities[id.into()], "player");
+/// assert_eq!(id.to_string(), "0v0");
+///
+/// let (slot, generation) = id.into_raw_parts();
+/// assert_eq!(EntityId::from_raw_parts(slot, generation), id);
+/// ```
+#[macro_export]
+macro_rules! arena_index {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident;) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name($crate::Index);
+
+        impl $name {
+            /// Create a new index from its raw parts.
+            ///
+            /// See `Index::from_raw_parts`.
+            #[allow(dead_code)]
+            $vis fn from_raw_parts(a: usize, b: u64) -> $name {
+                $name($crate::Index::from_raw_parts(a, b))
+            }
+
+            /// Convert this index into its raw parts.
+            ///
+            /// See `Index::into_raw_parts`.
+            #[allow(dead_code)]
+            $vis fn into_raw_parts(self) -> (usize, u64) {
+                self.0.into_raw_parts()
+            }
+        }
+
+        impl ::core::convert::From<$crate::Index> for $name {
+            fn from(index: $crate::Index) -> $name {
+                $name(index)
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::Index {
+            fn from(index: $name) -> $crate::Index {
+                index.0
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        $crate::__arena_index_serde!($name);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arena_index_serde {
+    ($name:ident) => {
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<$name, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                <$crate::Index as $crate::__private::serde::Deserialize<'de>>::deserialize(
+                    deserializer,
+                )
+                .map($name)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __arena_index_serde {
+    ($name:ident) => {};
+}
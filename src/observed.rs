@@ -0,0 +1,231 @@
+use super::{Arena, Index, Iter, IterMut};
+use core::ops;
+
+/// Callbacks invoked by an `ObservedArena` whenever its set of elements
+/// changes.
+///
+/// Implement this to keep secondary structures, such as a spatial hash or a
+/// lookup by name, in sync with an arena.
+pub trait ArenaHooks<T> {
+    /// Called after `value` has been inserted into the arena at index `i`.
+    fn on_insert(&mut self, i: Index, value: &T);
+
+    /// Called when `value` at index `i` is about to be removed from the
+    /// arena, whether by `remove`, `retain`, or `clear`.
+    fn on_remove(&mut self, i: Index, value: &T);
+
+    /// Called after the arena's capacity grew from `old_capacity` to
+    /// `new_capacity`, whether by `insert` into a full arena or by `reserve`.
+    ///
+    /// Growth reallocates the arena's storage, so this is the place to log
+    /// or trace it. The default implementation does nothing.
+    fn on_grow(&mut self, old_capacity: usize, new_capacity: usize) {
+        let _ = (old_capacity, new_capacity);
+    }
+}
+
+/// An arena that reports every insertion and removal to a set of
+/// `ArenaHooks`.
+///
+/// Only changes to which elements are in the arena are reported: modifying an
+/// element in place through `get_mut` or `iter_mut` does not invoke any hook.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{ArenaHooks, Index, ObservedArena};
+/// use std::collections::HashMap;
+///
+/// #[derive(Default)]
+/// struct ByName(HashMap<String, Index>);
+///
+/// impl ArenaHooks<String> for ByName {
+///     fn on_insert(&mut self, i: Index, name: &String) {
+///         self.0.insert(name.clone(), i);
+///     }
+///
+///     fn on_remove(&mut self, _i: Index, name: &String) {
+///         self.0.remove(name);
+///     }
+/// }
+///
+/// let mut arena = ObservedArena::new(ByName::default());
+/// let alice = arena.insert("alice".to_string());
+/// arena.insert("bob".to_string());
+/// assert_eq!(arena.hooks().0["alice"], alice);
+///
+/// arena.retain(|_, name| name != "bob");
+/// assert!(!arena.hooks().0.contains_key("bob"));
+///
+/// arena.clear();
+/// assert!(arena.hooks().0.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ObservedArena<T, H: ArenaHooks<T>> {
+    arena: Arena<T>,
+    hooks: H,
+}
+
+impl<T, H: ArenaHooks<T>> ObservedArena<T, H> {
+    /// Constructs a new, empty `ObservedArena` reporting to `hooks`.
+    pub fn new(hooks: H) -> ObservedArena<T, H> {
+        ObservedArena {
+            arena: Arena::new(),
+            hooks,
+        }
+    }
+
+    /// Constructs a new, empty `ObservedArena` with the specified capacity,
+    /// reporting to `hooks`.
+    pub fn with_capacity(n: usize, hooks: H) -> ObservedArena<T, H> {
+        ObservedArena {
+            arena: Arena::with_capacity(n),
+            hooks,
+        }
+    }
+
+    /// Get a shared reference to the hooks.
+    pub fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Get an exclusive reference to the hooks.
+    pub fn hooks_mut(&mut self) -> &mut H {
+        &mut self.hooks
+    }
+
+    /// Get a shared reference to the underlying arena.
+    pub fn arena(&self) -> &Arena<T> {
+        &self.arena
+    }
+
+    /// Split this into the underlying arena and the hooks.
+    pub fn into_parts(self) -> (Arena<T>, H) {
+        (self.arena, self.hooks)
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary,
+    /// and call `on_insert`.
+    pub fn insert(&mut self, value: T) -> Index {
+        let old_capacity = self.arena.capacity();
+        let i = self.arena.insert(value);
+        self.report_growth(old_capacity);
+        self.hooks.on_insert(i, &self.arena[i]);
+        i
+    }
+
+    /// Reserve capacity for at least `additional_capacity` more elements,
+    /// calling `on_grow` if the arena grows.
+    ///
+    /// See `Arena::reserve`.
+    pub fn reserve(&mut self, additional_capacity: usize) {
+        let old_capacity = self.arena.capacity();
+        self.arena.reserve(additional_capacity);
+        self.report_growth(old_capacity);
+    }
+
+    /// Call `on_grow` if the capacity changed from `old_capacity`.
+    fn report_growth(&mut self, old_capacity: usize) {
+        let new_capacity = self.arena.capacity();
+        if new_capacity != old_capacity {
+            self.hooks.on_grow(old_capacity, new_capacity);
+        }
+    }
+
+    /// Remove the element at index `i` from the arena, calling `on_remove` if
+    /// it was there.
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        let value = self.arena.remove(i)?;
+        self.hooks.on_remove(i, &value);
+        Some(value)
+    }
+
+    /// Retains only the elements specified by the predicate, calling
+    /// `on_remove` for each element that is removed.
+    pub fn retain(&mut self, mut predicate: impl FnMut(Index, &mut T) -> bool) {
+        let hooks = &mut self.hooks;
+        self.arena.retain(|i, value| {
+            let keep = predicate(i, value);
+            if !keep {
+                hooks.on_remove(i, value);
+            }
+            keep
+        });
+    }
+
+    /// Remove every element from the arena, calling `on_remove` for each one,
+    /// but keep its allocation.
+    pub fn clear(&mut self) {
+        for (i, value) in self.arena.iter() {
+            self.hooks.on_remove(i, value);
+        }
+        self.arena.clear();
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index) -> bool {
+        self.arena.contains(i)
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index) -> Option<&T> {
+        self.arena.get(i)
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        self.arena.get_mut(i)
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns true if the arena contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Get the capacity of the arena.
+    pub fn capacity(&self) -> usize {
+        self.arena.capacity()
+    }
+
+    /// Returns true if inserting another element would grow the arena.
+    pub fn is_full(&self) -> bool {
+        self.arena.is_full()
+    }
+
+    /// Iterate over shared references to the elements in the arena.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.arena.iter()
+    }
+
+    /// Iterate over exclusive references to the elements in the arena.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.arena.iter_mut()
+    }
+}
+
+impl<T, H: ArenaHooks<T> + Default> Default for ObservedArena<T, H> {
+    fn default() -> ObservedArena<T, H> {
+        ObservedArena::new(H::default())
+    }
+}
+
+impl<T, H: ArenaHooks<T>> ops::Index<Index> for ObservedArena<T, H> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        &self.arena[index]
+    }
+}
+
+impl<T, H: ArenaHooks<T>> ops::IndexMut<Index> for ObservedArena<T, H> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        &mut self.arena[index]
+    }
+}
@@ -0,0 +1,102 @@
+use super::{ArenaId, Index};
+use core::fmt;
+
+/// An optional `Index` that takes no more space than an `Index`.
+///
+/// `Option<Index>` needs room for its discriminant on top of the `Index`,
+/// which adds up in structures full of optional links, such as the parent
+/// and sibling links of tree nodes. An `OptionIndex` instead reserves one
+/// `Index` value, slot `usize::MAX` with generation `u64::MAX`, to mean
+/// "none". No arena ever hands out that index, as it could never have that
+/// many slots.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{Arena, OptionIndex};
+/// use std::mem;
+///
+/// struct Node {
+///     parent: OptionIndex,
+///     value: u32,
+/// }
+///
+/// let mut tree = Arena::new();
+/// let root = tree.insert(Node { parent: OptionIndex::NONE, value: 1 });
+/// let leaf = tree.insert(Node { parent: root.into(), value: 2 });
+///
+/// assert_eq!(tree[leaf].parent.get(), Some(root));
+/// assert!(tree[root].parent.is_none());
+/// assert_eq!(mem::size_of::<OptionIndex>(), mem::size_of::<generational_arena::Index>());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OptionIndex(Index);
+
+impl OptionIndex {
+    /// The `OptionIndex` that holds no index.
+    pub const NONE: OptionIndex = OptionIndex(Index {
+        index: usize::MAX,
+        generation: u64::MAX,
+        arena: ArenaId::UNTAGGED,
+    });
+
+    /// Returns true if this holds no index.
+    #[inline]
+    pub fn is_none(self) -> bool {
+        self == OptionIndex::NONE
+    }
+
+    /// Returns true if this holds an index.
+    #[inline]
+    pub fn is_some(self) -> bool {
+        !self.is_none()
+    }
+
+    /// Get the index this holds, if any.
+    #[inline]
+    pub fn get(self) -> Option<Index> {
+        if self.is_none() {
+            None
+        } else {
+            Some(self.0)
+        }
+    }
+
+    /// Take the index out, leaving `NONE` in its place.
+    pub fn take(&mut self) -> Option<Index> {
+        let index = self.get();
+        *self = OptionIndex::NONE;
+        index
+    }
+}
+
+impl Default for OptionIndex {
+    fn default() -> OptionIndex {
+        OptionIndex::NONE
+    }
+}
+
+impl fmt::Debug for OptionIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// An `Index` equal to the reserved `NONE` value converts to `NONE`.
+impl From<Index> for OptionIndex {
+    fn from(index: Index) -> OptionIndex {
+        OptionIndex(index)
+    }
+}
+
+impl From<Option<Index>> for OptionIndex {
+    fn from(index: Option<Index>) -> OptionIndex {
+        index.map_or(OptionIndex::NONE, OptionIndex)
+    }
+}
+
+impl From<OptionIndex> for Option<Index> {
+    fn from(index: OptionIndex) -> Option<Index> {
+        index.get()
+    }
+}
@@ -0,0 +1,75 @@
+use super::{Arena, Index};
+
+impl<T> Arena<T> {
+    /// Get exclusive references to both elements of each pair of indices in
+    /// `pairs`, one pair at a time.
+    ///
+    /// This is the batch form of `get2_mut`, for relations such as edges
+    /// that are stored elsewhere as `(Index, Index)` pairs. Pairs whose
+    /// elements are not both in the arena are skipped, and so are pairs
+    /// whose indices share a slot, instead of panicking.
+    ///
+    /// Each pair borrows the arena until the next one is requested, so the
+    /// result is not an `Iterator`; call `Get2MutIter::next_pair` in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// let c = arena.insert(3);
+    /// arena.remove(c);
+    ///
+    /// let edges = vec![(a, b), (b, c), (a, a), (b, a)];
+    /// let mut pairs = arena.get2_mut_iter(edges);
+    /// let mut visited = 0;
+    /// while let Some(((_, from), (_, to))) = pairs.next_pair() {
+    ///     *to += *from;
+    ///     visited += 1;
+    /// }
+    ///
+    /// assert_eq!(visited, 2);
+    /// assert_eq!((arena[a], arena[b]), (4, 3));
+    /// ```
+    pub fn get2_mut_iter<I>(&mut self, pairs: I) -> Get2MutIter<'_, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = (Index, Index)>,
+    {
+        Get2MutIter {
+            arena: self,
+            pairs: pairs.into_iter(),
+        }
+    }
+}
+
+/// Exclusive references to pairs of elements of an arena, one pair at a time.
+///
+/// Created by `Arena::get2_mut_iter`.
+#[derive(Debug)]
+pub struct Get2MutIter<'a, T: 'a, I> {
+    arena: &'a mut Arena<T>,
+    pairs: I,
+}
+
+impl<'a, T, I> Get2MutIter<'a, T, I>
+where
+    I: Iterator<Item = (Index, Index)>,
+{
+    /// Get the elements of the next pair whose elements are both in the
+    /// arena, in different slots, along with their indices.
+    #[allow(clippy::type_complexity)]
+    pub fn next_pair(&mut self) -> Option<((Index, &mut T), (Index, &mut T))> {
+        for (i1, i2) in &mut self.pairs {
+            if i1.index != i2.index && self.arena.contains(i1) && self.arena.contains(i2) {
+                return match self.arena.get2_mut(i1, i2) {
+                    (Some(v1), Some(v2)) => Some(((i1, v1), (i2, v2))),
+                    _ => unreachable!("both elements were just found"),
+                };
+            }
+        }
+        None
+    }
+}
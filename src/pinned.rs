@@ -0,0 +1,60 @@
+use super::{Arena, Box, Index};
+use core::pin::Pin;
+
+/// Arenas of pinned, boxed values.
+///
+/// An `Arena<T>` stores its elements inline, so they move whenever the arena
+/// grows. An `Arena<Pin<Box<T>>>` instead stores each element in its own heap
+/// allocation, which never moves for as long as the element is in the arena,
+/// no matter how many other elements are inserted. That makes it suitable for
+/// self-referential or address-sensitive values that need `Pin`.
+impl<T> Arena<Pin<Box<T>>> {
+    /// Pin `value` in its own heap allocation and insert it into the arena.
+    ///
+    /// The value's address stays the same until it is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert_pinned(5);
+    /// let addr: *const i32 = &*arena.get_pin(idx).unwrap();
+    ///
+    /// // Growing the arena does not move already-inserted values.
+    /// for i in 0..100 {
+    ///     arena.insert_pinned(i);
+    /// }
+    /// assert_eq!(addr, &*arena.get_pin(idx).unwrap() as *const i32);
+    /// ```
+    pub fn insert_pinned(&mut self, value: T) -> Index {
+        self.insert(Box::pin(value))
+    }
+
+    /// Get a pinned shared reference to the element at index `i` if it is in
+    /// the arena.
+    pub fn get_pin(&self, i: Index) -> Option<Pin<&T>> {
+        self.get(i).map(|value| value.as_ref())
+    }
+
+    /// Get a pinned exclusive reference to the element at index `i` if it is
+    /// in the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::pin::Pin;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert_pinned(String::from("hello"));
+    ///
+    /// let s: Pin<&mut String> = arena.get_pin_mut(idx).unwrap();
+    /// s.get_mut().push_str(", world");
+    /// assert_eq!(*arena.get_pin(idx).unwrap(), "hello, world");
+    /// ```
+    pub fn get_pin_mut(&mut self, i: Index) -> Option<Pin<&mut T>> {
+        self.get_mut(i).map(|value| value.as_mut())
+    }
+}
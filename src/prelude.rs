@@ -0,0 +1,14 @@
+//! The commonly used types of this crate, for glob importing.
+//!
+//! ```
+//! use generational_arena::prelude::*;
+//!
+//! let mut arena: Arena<u32> = Arena::new();
+//! let idx: Index = arena.insert(1);
+//! let mut iter: Iter<'_, u32> = arena.iter();
+//! assert_eq!(iter.next(), Some((idx, &1)));
+//! ```
+
+pub use crate::{
+    Arena, ArenaHooks, Drain, DrainIndices, Entries, Index, IntoIter, Iter, IterFrom, IterMut,
+};
@@ -0,0 +1,133 @@
+use super::{ArenaId, Index};
+use core::convert::TryFrom;
+use core::fmt;
+use core::mem;
+
+/// A packed, fixed-layout representation of an `Index`.
+///
+/// A `RawIndex` is a single `u64` whose low 32 bits hold the slot and whose
+/// high 32 bits hold the generation. It is `#[repr(transparent)]`, so it has
+/// exactly the size, alignment, and bit validity of a `u64` on every platform,
+/// which makes it suitable for GPU buffers, memory-mapped files, and FFI.
+///
+/// Converting an `Index` into a `RawIndex` fails if either its slot or its
+/// generation does not fit in 32 bits. Converting a `RawIndex` back into an
+/// `Index` always succeeds.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{Arena, Index, RawIndex};
+/// use std::convert::TryFrom;
+///
+/// let mut arena = Arena::new();
+/// let idx = arena.insert("hello");
+///
+/// let raw = RawIndex::try_from(idx).unwrap();
+/// let bits: u64 = raw.to_bits();
+///
+/// let idx2 = Index::from(RawIndex::from_bits(bits));
+/// assert_eq!(idx, idx2);
+/// assert_eq!(arena[idx2], "hello");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct RawIndex(u64);
+
+// The layout promised above, checked at compile time.
+const _: () = assert!(mem::size_of::<RawIndex>() == RawIndex::SIZE_BYTES);
+const _: () = assert!(mem::align_of::<RawIndex>() == mem::align_of::<u64>());
+const _: () = assert!(RawIndex::SLOT_BITS + RawIndex::GENERATION_BITS == 64);
+
+impl RawIndex {
+    /// The size of a `RawIndex` in bytes, on every platform and with every
+    /// feature.
+    pub const SIZE_BYTES: usize = 8;
+
+    /// The number of low bits that hold the slot.
+    pub const SLOT_BITS: u32 = 32;
+
+    /// The number of high bits that hold the generation.
+    pub const GENERATION_BITS: u32 = 32;
+
+    /// Create a `RawIndex` from its packed bit representation.
+    pub fn from_bits(bits: u64) -> RawIndex {
+        RawIndex(bits)
+    }
+
+    /// Get the packed bit representation of this `RawIndex`.
+    pub fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Get the slot stored in the low 32 bits.
+    pub fn slot(self) -> u32 {
+        self.0 as u32
+    }
+
+    /// Get the generation stored in the high 32 bits.
+    pub fn generation(self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+
+    /// Create a `RawIndex` from its packed bit representation in
+    /// little-endian byte order, as written by `to_le_bytes`.
+    pub fn from_le_bytes(bytes: [u8; RawIndex::SIZE_BYTES]) -> RawIndex {
+        RawIndex(u64::from_le_bytes(bytes))
+    }
+
+    /// Get the packed bit representation of this `RawIndex` in
+    /// little-endian byte order, for file formats that must read the same
+    /// on every platform.
+    pub fn to_le_bytes(self) -> [u8; RawIndex::SIZE_BYTES] {
+        self.0.to_le_bytes()
+    }
+}
+
+impl TryFrom<Index> for RawIndex {
+    type Error = TryFromIndexError;
+
+    fn try_from(index: Index) -> Result<RawIndex, TryFromIndexError> {
+        let slot = u32::try_from(index.index).map_err(|_| TryFromIndexError { index })?;
+        let generation =
+            u32::try_from(index.generation).map_err(|_| TryFromIndexError { index })?;
+        Ok(RawIndex(u64::from(slot) | (u64::from(generation) << 32)))
+    }
+}
+
+impl From<RawIndex> for Index {
+    fn from(raw: RawIndex) -> Index {
+        Index {
+            index: raw.slot() as usize,
+            generation: u64::from(raw.generation()),
+            arena: ArenaId::UNTAGGED,
+        }
+    }
+}
+
+/// An error returned when an `Index` cannot be packed into a `RawIndex`
+/// because its slot or generation does not fit in 32 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromIndexError {
+    index: Index,
+}
+
+impl TryFromIndexError {
+    /// Get the `Index` that could not be packed.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+}
+
+impl fmt::Display for TryFromIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "arena index {} does not fit in a 32-bit slot and 32-bit generation",
+            self.index
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryFromIndexError {}
@@ -0,0 +1,60 @@
+use super::{Arena, Entry, Index, Vec};
+use rayon::prelude::*;
+
+impl<T: Send> Arena<T> {
+    /// Retains only the elements specified by the predicate, evaluating the
+    /// predicate in parallel.
+    ///
+    /// This is the parallel counterpart of `retain`: the predicate is called
+    /// for every element on rayon's thread pool, and then the elements it
+    /// rejected are removed one by one on the calling thread. The arena ends
+    /// up exactly as `retain` would have left it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.extend(0..1000);
+    ///
+    /// arena.par_retain(|_, n| *n % 3 == 0);
+    /// assert_eq!(arena.len(), 334);
+    /// assert!(arena.iter().all(|(_, n)| n % 3 == 0));
+    /// ```
+    pub fn par_retain(&mut self, predicate: impl Fn(Index, &mut T) -> bool + Sync + Send) {
+        let id = self.id;
+        let rejected: Vec<Index> = self
+            .items
+            .par_iter_mut()
+            .enumerate()
+            .filter_map(|(i, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    let index = Index {
+                        index: i,
+                        generation: *generation,
+                        arena: id,
+                    };
+                    if predicate(index, value) {
+                        None
+                    } else {
+                        Some(index)
+                    }
+                }
+                Entry::Free { .. } => None,
+            })
+            .collect();
+
+        // Free the slots the same way `retain` does, so that they are reused
+        // in the same order afterwards.
+        if !rejected.is_empty() {
+            self.generation += 1;
+        }
+        for index in rejected {
+            self.items[index.index] = Entry::Free { next_free: None };
+            self.push_free(index.index);
+            self.len -= 1;
+            self.removals += 1;
+        }
+    }
+}
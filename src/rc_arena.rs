@@ -0,0 +1,191 @@
+use super::{Arena, Index, Rc};
+use core::fmt;
+
+/// An arena whose elements can be pinned in place by reference-counted
+/// handles.
+///
+/// `RcArena::get_strong` returns a `StrongIndex`, which keeps its element
+/// alive: while any `StrongIndex` for an element exists, `RcArena::remove`
+/// refuses to remove it and returns `Err(InUse)` instead. Once the last
+/// `StrongIndex` is dropped, the element can be removed as usual. Plain
+/// `Index`es continue to work as weak handles.
+///
+/// This gives shared-ownership semantics for arena elements without resorting
+/// to `Rc<RefCell<T>>` and the reference cycles that come with it.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{InUse, RcArena};
+///
+/// let mut arena = RcArena::new();
+/// let idx = arena.insert("texture");
+///
+/// let strong = arena.get_strong(idx).unwrap();
+/// assert_eq!(arena.remove(idx), Err(InUse));
+/// assert_eq!(arena[strong.index()], "texture");
+///
+/// drop(strong);
+/// assert_eq!(arena.remove(idx), Ok(Some("texture")));
+/// ```
+#[derive(Debug)]
+pub struct RcArena<T> {
+    arena: Arena<(T, Rc<()>)>,
+}
+
+/// A strong handle to an element of an `RcArena`.
+///
+/// While a `StrongIndex` exists, its element cannot be removed from the
+/// arena. Cloning a `StrongIndex` creates another strong handle to the same
+/// element.
+#[derive(Clone)]
+pub struct StrongIndex {
+    index: Index,
+    // Only held to bump the element's strong count for as long as this
+    // handle lives.
+    _token: Rc<()>,
+}
+
+/// The error returned when trying to remove an element of an `RcArena` that
+/// still has `StrongIndex` handles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InUse;
+
+impl<T> Default for RcArena<T> {
+    fn default() -> RcArena<T> {
+        RcArena::new()
+    }
+}
+
+impl<T> RcArena<T> {
+    /// Constructs a new, empty `RcArena`.
+    pub fn new() -> RcArena<T> {
+        RcArena {
+            arena: Arena::new(),
+        }
+    }
+
+    /// Constructs a new, empty `RcArena` with the specified capacity.
+    pub fn with_capacity(n: usize) -> RcArena<T> {
+        RcArena {
+            arena: Arena::with_capacity(n),
+        }
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary.
+    ///
+    /// The new element has no strong handles.
+    pub fn insert(&mut self, value: T) -> Index {
+        self.arena.insert((value, Rc::new(())))
+    }
+
+    /// Get a strong handle to the element at index `i`.
+    ///
+    /// Returns `None` if the element is not in the arena.
+    pub fn get_strong(&self, i: Index) -> Option<StrongIndex> {
+        self.arena.get(i).map(|(_, token)| StrongIndex {
+            index: i,
+            _token: token.clone(),
+        })
+    }
+
+    /// Get the number of strong handles to the element at index `i`.
+    ///
+    /// Returns `None` if the element is not in the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::RcArena;
+    ///
+    /// let mut arena = RcArena::new();
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena.strong_count(idx), Some(0));
+    ///
+    /// let a = arena.get_strong(idx).unwrap();
+    /// let b = a.clone();
+    /// assert_eq!(arena.strong_count(idx), Some(2));
+    /// # drop((a, b));
+    /// ```
+    pub fn strong_count(&self, i: Index) -> Option<usize> {
+        self.arena
+            .get(i)
+            .map(|(_, token)| Rc::strong_count(token) - 1)
+    }
+
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// Returns `Ok(Some(value))` if the element was removed, `Ok(None)` if
+    /// it was not in the arena, and `Err(InUse)` if it has strong handles and
+    /// was therefore left in place.
+    pub fn remove(&mut self, i: Index) -> Result<Option<T>, InUse> {
+        match self.strong_count(i) {
+            None => Ok(None),
+            Some(0) => Ok(self.arena.remove(i).map(|(value, _)| value)),
+            Some(_) => Err(InUse),
+        }
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index) -> bool {
+        self.arena.contains(i)
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index) -> Option<&T> {
+        self.arena.get(i).map(|(value, _)| value)
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        self.arena.get_mut(i).map(|(value, _)| value)
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns true if the arena contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+}
+
+impl<T> core::ops::Index<Index> for RcArena<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        &self.arena[index].0
+    }
+}
+
+impl<T> core::ops::IndexMut<Index> for RcArena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        &mut self.arena[index].0
+    }
+}
+
+impl StrongIndex {
+    /// Get the `Index` of the element this handle keeps alive.
+    pub fn index(&self) -> Index {
+        self.index
+    }
+}
+
+impl fmt::Debug for StrongIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("StrongIndex").field(&self.index).finish()
+    }
+}
+
+impl fmt::Display for InUse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "arena element is still referenced by a strong handle")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InUse {}
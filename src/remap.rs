@@ -0,0 +1,132 @@
+use super::{Arena, Entry, Index, Vec};
+
+/// A translation table from the indices of one arena to those of another.
+///
+/// Created by `Arena::clone_compact`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexRemap {
+    // For each slot of the old arena, the generation of the element that was
+    // there and its new index.
+    table: Vec<Option<(u64, Index)>>,
+    len: usize,
+}
+
+impl IndexRemap {
+    /// Get the new index of the element that had index `old`, if it was
+    /// moved.
+    pub fn get(&self, old: Index) -> Option<Index> {
+        match self.table.get(old.index) {
+            Some(&Some((generation, new))) if generation == old.generation => Some(new),
+            _ => None,
+        }
+    }
+
+    /// Get the number of indices in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the table contains no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Arena<T> {
+    /// Clone the arena into a new one with no free slots, returning it along
+    /// with a table translating old indices into new ones.
+    ///
+    /// The elements keep their ascending slot order, and the new arena has
+    /// just enough capacity for them. Its elements still hold whatever
+    /// indices they held before; use `rewrite_indices` with the returned
+    /// table to fix those up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    /// arena.remove(b);
+    ///
+    /// let (compact, remap) = arena.clone_compact();
+    /// assert_eq!(compact.capacity(), 2);
+    /// assert_eq!(compact[remap.get(a).unwrap()], 'a');
+    /// assert_eq!(compact[remap.get(c).unwrap()], 'c');
+    /// assert_eq!(remap.get(b), None);
+    /// ```
+    pub fn clone_compact(&self) -> (Arena<T>, IndexRemap)
+    where
+        T: Clone,
+    {
+        let mut arena = Arena::with_capacity_and_reuse(self.len, self.reuse);
+        arena.generation = self.generation;
+        arena.growth_percent = self.growth_percent;
+
+        let mut table = Vec::with_capacity(self.items.len());
+        for entry in &self.items {
+            table.push(match entry {
+                Entry::Occupied { generation, value } => {
+                    let new = arena
+                        .try_insert(value.clone())
+                        .map_err(|_| ())
+                        .expect("the new arena has room for every element");
+                    Some((*generation, new))
+                }
+                Entry::Free { .. } => None,
+            });
+        }
+
+        let remap = IndexRemap {
+            table,
+            len: self.len,
+        };
+        (arena, remap)
+    }
+
+    /// Rewrite the indices stored inside every element.
+    ///
+    /// `visit` is called for each element, in ascending slot order, along
+    /// with a function that translates an index by calling `remap`. This
+    /// fixes up cross-references between elements after moving them, e.g.
+    /// with `clone_compact`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, Index};
+    ///
+    /// #[derive(Clone)]
+    /// struct Node {
+    ///     name: &'static str,
+    ///     parent: Option<Index>,
+    /// }
+    ///
+    /// let mut tree = Arena::new();
+    /// let gap = tree.insert(Node { name: "gap", parent: None });
+    /// let root = tree.insert(Node { name: "root", parent: None });
+    /// let leaf = tree.insert(Node { name: "leaf", parent: Some(root) });
+    /// tree.remove(gap);
+    ///
+    /// let (mut compact, remap) = tree.clone_compact();
+    /// compact.rewrite_indices(
+    ///     |old| remap.get(old).unwrap(),
+    ///     |node, remap| node.parent = node.parent.map(|p| remap(p)),
+    /// );
+    ///
+    /// let parent = compact[remap.get(leaf).unwrap()].parent.unwrap();
+    /// assert_eq!(compact[parent].name, "root");
+    /// ```
+    pub fn rewrite_indices(
+        &mut self,
+        mut remap: impl FnMut(Index) -> Index,
+        mut visit: impl FnMut(&mut T, &mut dyn FnMut(Index) -> Index),
+    ) {
+        for (_, value) in self.iter_mut() {
+            visit(value, &mut remap);
+        }
+    }
+}
@@ -1,11 +1,21 @@
-use super::{Arena, Entry, Index, Vec, DEFAULT_CAPACITY};
+use super::{Arena, ArenaId, Entry, Index, OptionIndex, ReusePolicy, Vec, DEFAULT_GROWTH_PERCENT};
 use core::cmp;
 use core::fmt;
 use core::iter;
 use core::marker::PhantomData;
-use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
 use serde::ser::{Serialize, Serializer};
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::borrow::Cow;
+        use std::string::String;
+    } else {
+        use alloc::borrow::Cow;
+        use alloc::string::String;
+    }
+}
+
 impl Serialize for Index {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -23,7 +33,31 @@ impl<'de> Deserialize<'de> for Index {
         D: Deserializer<'de>,
     {
         let (index, generation) = Deserialize::deserialize(deserializer)?;
-        Ok(Index { index, generation })
+        Ok(Index {
+            index,
+            generation,
+            arena: ArenaId::UNTAGGED,
+        })
+    }
+}
+
+impl Serialize for OptionIndex {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The same format as `Option<Index>`, so the two are
+        // interchangeable in serialized data.
+        self.get().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OptionIndex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<Index>::deserialize(deserializer).map(OptionIndex::from)
     }
 }
 
@@ -37,13 +71,24 @@ where
     {
         // Note: do not change the serialization format, or it may break
         // forward and backward compatibility of serialized data!
-        serializer.collect_seq(self.items.iter().map(|entry| match entry {
-            Entry::Occupied { generation, value } => Some((generation, value)),
-            Entry::Free { .. } => None,
-        }))
+        // Slots that have never been used are written out as free ones, so
+        // that the capacity survives a round trip.
+        let unused = self.capacity() - self.items.len();
+        serializer.collect_seq(
+            self.items
+                .iter()
+                .map(|entry| match entry {
+                    Entry::Occupied { generation, value } => Some((generation, value)),
+                    Entry::Free { .. } => None,
+                })
+                .chain(iter::repeat_with(|| None).take(unused)),
+        )
     }
 }
 
+// Elements are deserialized with the input's lifetime, so an `Arena<&'de str>`
+// or `Arena<&'de [u8]>` borrows its elements from the input when the format
+// supports it.
 impl<'de, T> Deserialize<'de> for Arena<T>
 where
     T: Deserialize<'de>,
@@ -54,8 +99,25 @@ where
     {
         deserializer.deserialize_seq(ArenaVisitor::new())
     }
+
+    /// Deserializes into an existing arena, reusing its allocation.
+    ///
+    /// The result is the same as deserializing a new arena, except that the
+    /// capacity is never less than before and the reuse policy and growth
+    /// percentage are kept. The old elements are dropped before any new ones
+    /// are read. If deserialization fails, the arena is left empty.
+    fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(InPlaceArenaVisitor { place })
+    }
 }
 
+/// The most entries to allocate space for up front, based on a sequence's
+/// size hint, before any of them have actually been deserialized.
+const MAX_PREALLOCATED_ENTRIES: usize = 4096;
+
 struct ArenaVisitor<T> {
     marker: PhantomData<fn() -> Arena<T>>,
 }
@@ -82,49 +144,342 @@ where
     where
         M: SeqAccess<'de>,
     {
-        let init_cap = access.size_hint().unwrap_or(DEFAULT_CAPACITY);
-        let mut items = Vec::with_capacity(init_cap);
-
-        let mut generation = 0;
-        while let Some(element) = access.next_element::<Option<(u64, T)>>()? {
-            let item = match element {
-                Some((gen, value)) => {
-                    generation = cmp::max(generation, gen);
-                    Entry::Occupied {
-                        generation: gen,
-                        value,
-                    }
-                }
-                None => Entry::Free { next_free: None },
-            };
-            items.push(item);
-        }
+        let mut items = Vec::new();
+        let generation = read_entries(&mut access, &mut items)?;
+        Ok(from_entries(items, generation))
+    }
+}
 
-        // items.len() must be same as item.capacity(), so fill the unused elements with Free.
-        if items.len() < items.capacity() {
-            let add_cap = items.capacity() - items.len();
-            items.reserve_exact(add_cap);
-            items.extend(iter::repeat_with(|| Entry::Free { next_free: None }).take(add_cap));
-            debug_assert_eq!(items.len(), items.capacity());
-        }
+/// Build a deserialized arena from its entries.
+fn from_entries<T>(mut items: Vec<Entry<T>>, generation: u64) -> Arena<T> {
+    // Every deserialized entry becomes exactly one slot, regardless of
+    // how much capacity the `Vec` happened to allocate, and the free list
+    // is threaded through the free ones.
+    items.shrink_to_fit();
+    let mut arena = Arena {
+        items,
+        generation,
+        free_list_head: None,
+        free_list_tail: None,
+        free_stack: Vec::new(),
+        len: 0,
+        occupied: 0..0,
+        occupancy: Vec::new(),
+        reuse: ReusePolicy::default(),
+        // Indices into the serialized arena were stamped with an id that
+        // means nothing in this process, so accept every index.
+        id: ArenaId::UNTAGGED,
+        removals: 0,
+        growth_percent: DEFAULT_GROWTH_PERCENT,
+    };
+    arena.rebuild_free_list();
+    arena
+}
+
+struct InPlaceArenaVisitor<'a, T> {
+    place: &'a mut Arena<T>,
+}
+
+impl<'a, 'de, T> Visitor<'de> for InPlaceArenaVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
 
-        let mut free_list_head = None;
-        let mut len = items.len();
-        // Iterates `arena.items` in reverse order so that free_list concatenates
-        // indices in ascending order.
-        for (idx, entry) in items.iter_mut().enumerate().rev() {
-            if let Entry::Free { next_free } = entry {
-                *next_free = free_list_head;
-                free_list_head = Some(idx);
-                len -= 1;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a generational arena")
+    }
+
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'de>,
+    {
+        let arena = self.place;
+        arena.clear();
+        let generation = match read_entries(&mut access, &mut arena.items) {
+            Ok(generation) => generation,
+            Err(e) => {
+                // Apart from the half-read entries, `clear` has already left
+                // the arena empty.
+                arena.items.clear();
+                return Err(e);
+            }
+        };
+
+        arena.generation = generation;
+        arena.id = ArenaId::UNTAGGED;
+        arena.removals = 0;
+        arena.rebuild_free_list();
+        Ok(())
+    }
+}
+
+/// Append the entries of a serialized arena to `items`, returning the newest
+/// generation among them.
+fn read_entries<'de, T, M>(access: &mut M, items: &mut Vec<Entry<T>>) -> Result<u64, M::Error>
+where
+    T: Deserialize<'de>,
+    M: SeqAccess<'de>,
+{
+    read_entries_with(access, items, |value| value)
+}
+
+/// Like `read_entries`, but reads each value as a `U` and converts it with
+/// `migrate`.
+fn read_entries_with<'de, U, T, M>(
+    access: &mut M,
+    items: &mut Vec<Entry<T>>,
+    mut migrate: impl FnMut(U) -> T,
+) -> Result<u64, M::Error>
+where
+    U: Deserialize<'de>,
+    M: SeqAccess<'de>,
+{
+    // Don't trust the size hint with an unbounded allocation.
+    let init_cap = cmp::min(access.size_hint().unwrap_or(0), MAX_PREALLOCATED_ENTRIES);
+    items.reserve(init_cap);
+
+    let mut generation = 0;
+    while let Some(element) = access.next_element::<Option<(u64, U)>>()? {
+        let item = match element {
+            Some((gen, value)) => {
+                generation = cmp::max(generation, gen);
+                Entry::Occupied {
+                    generation: gen,
+                    value: migrate(value),
+                }
             }
+            None => Entry::Free { next_free: None },
+        };
+        items.push(item);
+    }
+    Ok(generation)
+}
+
+impl<T> Arena<T> {
+    /// Deserialize an arena whose elements were serialized as `U`s, converting
+    /// each one into a `T` with `migrate` as it is read.
+    ///
+    /// This reads the same format as `Arena<U>`'s `Deserialize` impl, so an
+    /// arena saved before its element type changed can be loaded without
+    /// first building an `Arena<U>` and then converting it. Every element
+    /// keeps its index. `MigrateArena` does the same as a `DeserializeSeed`,
+    /// for arenas nested in other data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate serde_yaml;
+    /// use generational_arena::Arena;
+    ///
+    /// // Version 1 stored names only.
+    /// let mut old = Arena::new();
+    /// let idx = old.insert(String::from("alice"));
+    /// let yaml = serde_yaml::to_string(&old).unwrap();
+    ///
+    /// // Version 2 stores a score, too.
+    /// let de = serde_yaml::Deserializer::from_str(&yaml);
+    /// let new = Arena::deserialize_with(de, |name: String| (name, 0)).unwrap();
+    /// assert_eq!(new[idx], (String::from("alice"), 0));
+    /// ```
+    pub fn deserialize_with<'de, D, U, F>(deserializer: D, migrate: F) -> Result<Arena<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        U: Deserialize<'de>,
+        F: FnMut(U) -> T,
+    {
+        MigrateArena::new(migrate).deserialize(deserializer)
+    }
+}
+
+/// Deserializes an `Arena<T>` from data written as an `Arena<U>`, converting
+/// each element with a function as it is read.
+///
+/// This is the `DeserializeSeed` behind `Arena::deserialize_with`, for
+/// deserializing arenas nested inside other data.
+pub struct MigrateArena<U, F> {
+    migrate: F,
+    marker: PhantomData<fn(U)>,
+}
+
+impl<U, F> MigrateArena<U, F> {
+    /// Constructs a `MigrateArena` that converts each element with `migrate`.
+    pub fn new(migrate: F) -> MigrateArena<U, F> {
+        MigrateArena {
+            migrate,
+            marker: PhantomData,
         }
+    }
+}
 
-        Ok(Arena {
-            items,
-            generation,
-            free_list_head,
-            len,
-        })
+impl<U, F> fmt::Debug for MigrateArena<U, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MigrateArena").finish_non_exhaustive()
+    }
+}
+
+impl<'de, U, T, F> DeserializeSeed<'de> for MigrateArena<U, F>
+where
+    U: Deserialize<'de>,
+    F: FnMut(U) -> T,
+{
+    type Value = Arena<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, U, T, F> Visitor<'de> for MigrateArena<U, F>
+where
+    U: Deserialize<'de>,
+    F: FnMut(U) -> T,
+{
+    type Value = Arena<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a generational arena")
+    }
+
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        let generation = read_entries_with(&mut access, &mut items, self.migrate)?;
+        Ok(from_entries(items, generation))
+    }
+}
+
+impl<'a> Arena<Cow<'a, str>> {
+    /// Deserialize an arena of strings, borrowing each one from the input
+    /// when the format allows it and copying it otherwise.
+    ///
+    /// This reads the same format as `Arena<String>` and `Arena<&str>`.
+    /// Deserializing an `Arena<&'a str>` copies nothing either, but fails for
+    /// any string that cannot be borrowed, such as a JSON string with escape
+    /// sequences, while deserializing an `Arena<Cow<'a, str>>` directly
+    /// always copies, because that is what `Cow`'s `Deserialize` impl does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate bincode;
+    /// use bincode::Options;
+    /// use generational_arena::Arena;
+    /// use std::borrow::Cow;
+    ///
+    /// let mut table = Arena::new();
+    /// let idx = table.insert("a long string");
+    /// let bytes = bincode::DefaultOptions::new().serialize(&table).unwrap();
+    ///
+    /// let mut de = bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new());
+    /// let loaded = Arena::deserialize_cow_str(&mut de).unwrap();
+    /// assert!(matches!(loaded[idx], Cow::Borrowed("a long string")));
+    /// ```
+    pub fn deserialize_cow_str<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        Arena::deserialize_with(deserializer, |s: CowStr<'a>| s.0)
+    }
+}
+
+impl<'a> Arena<Cow<'a, [u8]>> {
+    /// Deserialize an arena of byte strings, borrowing each one from the
+    /// input when the format allows it and copying it otherwise.
+    ///
+    /// This reads the same format as `Arena<Vec<u8>>` and `Arena<&[u8]>`.
+    /// See `deserialize_cow_str`.
+    pub fn deserialize_cow_bytes<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        Arena::deserialize_with(deserializer, |b: CowBytes<'a>| b.0)
+    }
+}
+
+/// A string that is borrowed from the input if possible.
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for CowStr<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+struct CowStrVisitor;
+
+impl<'de> Visitor<'de> for CowStrVisitor {
+    type Value = CowStr<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a string")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(CowStr(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(CowStr(Cow::Owned(v.into())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(CowStr(Cow::Owned(v)))
+    }
+}
+
+/// A byte string that is borrowed from the input if possible.
+struct CowBytes<'a>(Cow<'a, [u8]>);
+
+impl<'de> Deserialize<'de> for CowBytes<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(CowBytesVisitor)
+    }
+}
+
+struct CowBytesVisitor;
+
+impl<'de> Visitor<'de> for CowBytesVisitor {
+    type Value = CowBytes<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a byte string")
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(CowBytes(Cow::Borrowed(v)))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(CowBytes(Cow::Owned(v.into())))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(CowBytes(Cow::Owned(v)))
+    }
+
+    // Self-describing formats without a byte string type write `[u8]` as a
+    // sequence of numbers.
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'de>,
+    {
+        let init_cap = cmp::min(access.size_hint().unwrap_or(0), MAX_PREALLOCATED_ENTRIES);
+        let mut bytes = Vec::with_capacity(init_cap);
+        while let Some(byte) = access.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(CowBytes(Cow::Owned(bytes)))
     }
 }
@@ -0,0 +1,65 @@
+use super::{Arena, Entry, Index};
+use slab::Slab;
+
+impl Index {
+    /// Get the index of the element that had key `key` in a `Slab` that was
+    /// converted into an `Arena` with `Arena::from`.
+    ///
+    /// Converted elements keep their keys as slots, and all start at
+    /// generation zero, so raw keys persisted before a migration keep
+    /// working afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::{Arena, Index};
+    /// use slab::Slab;
+    ///
+    /// let mut slab = Slab::new();
+    /// let key = slab.insert("hello");
+    ///
+    /// let arena = Arena::from(slab);
+    /// assert_eq!(arena[Index::from_slab_key(key)], "hello");
+    /// ```
+    pub fn from_slab_key(key: usize) -> Index {
+        Index::from_raw_parts(key, 0)
+    }
+}
+
+/// Converts a `Slab` into an `Arena`, keeping every element in the slot of
+/// its key. Vacant keys become free slots, and the slab's capacity is kept.
+/// Elements inserted afterwards get newer generations, so stale keys do not
+/// refer to them.
+impl<T> From<Slab<T>> for Arena<T> {
+    fn from(slab: Slab<T>) -> Arena<T> {
+        let mut arena = Arena::with_capacity(slab.capacity());
+        let slots = slab.iter().map(|(key, _)| key + 1).max().unwrap_or(0);
+        arena
+            .items
+            .resize_with(slots, || Entry::Free { next_free: None });
+        for (key, value) in slab {
+            arena.items[key] = Entry::Occupied {
+                generation: 0,
+                value,
+            };
+        }
+        arena.rebuild_free_list();
+        // Keys of elements removed from the slab before the conversion have
+        // generation zero as well, so don't let later elements reuse it.
+        arena.generation = 1;
+        arena
+    }
+}
+
+/// Converts an `Arena` into a `Slab`, keyed by each element's slot.
+///
+/// Slabs have no generations, so these are dropped: a stale `Index` whose
+/// slot has been reused refers to the new element's key.
+impl<T> From<Arena<T>> for Slab<T> {
+    fn from(arena: Arena<T>) -> Slab<T> {
+        arena
+            .into_iter_indexed()
+            .map(|(index, value)| (index.index, value))
+            .collect()
+    }
+}
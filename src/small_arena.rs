@@ -0,0 +1,326 @@
+use super::{
+    Arena, ArenaId, Entry, Index, Iter, IterMut, LookupError, ReusePolicy, Vec,
+    DEFAULT_GROWTH_PERCENT,
+};
+use core::{array, cmp, mem, ops};
+
+/// An arena that stores up to `N` elements inline, without allocating.
+///
+/// A `SmallArena` starts out holding its elements in a fixed-size array. The
+/// first insertion that does not fit moves them all into an ordinary heap
+/// allocated `Arena`, after which it behaves exactly like one. Every `Index`
+/// stays valid across the move.
+///
+/// This is useful when there are many arenas and most of them stay tiny, such
+/// as one per entity, since each tiny arena then costs no heap allocation.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::SmallArena;
+///
+/// let mut children = SmallArena::<&str, 2>::new();
+/// let a = children.insert("a");
+/// let b = children.insert("b");
+/// assert!(children.is_inline());
+///
+/// // The third element spills over to the heap.
+/// let c = children.insert("c");
+/// assert!(!children.is_inline());
+/// assert_eq!((children[a], children[b], children[c]), ("a", "b", "c"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SmallArena<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+#[derive(Clone, Debug)]
+enum Storage<T, const N: usize> {
+    Inline {
+        items: [Entry<T>; N],
+        generation: u64,
+        len: usize,
+        id: ArenaId,
+    },
+    Heap(Arena<T>),
+}
+
+impl<T, const N: usize> Default for SmallArena<T, N> {
+    fn default() -> SmallArena<T, N> {
+        SmallArena::new()
+    }
+}
+
+impl<T, const N: usize> SmallArena<T, N> {
+    /// Constructs a new, empty `SmallArena`, without allocating.
+    pub fn new() -> SmallArena<T, N> {
+        SmallArena {
+            storage: Storage::Inline {
+                items: array::from_fn(|_| Entry::Free { next_free: None }),
+                generation: 0,
+                len: 0,
+                id: ArenaId::fresh(),
+            },
+        }
+    }
+
+    /// Returns true if the elements are still stored inline, rather than in
+    /// a heap allocation.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.storage, Storage::Inline { .. })
+    }
+
+    /// Insert `value` into the arena, moving it to the heap if it is full.
+    ///
+    /// The `value`'s associated index in the arena is returned.
+    pub fn insert(&mut self, value: T) -> Index {
+        if let Storage::Inline {
+            items,
+            generation,
+            len,
+            id,
+        } = &mut self.storage
+        {
+            let free = items
+                .iter()
+                .position(|entry| matches!(entry, Entry::Free { .. }));
+            if let Some(slot) = free {
+                items[slot] = Entry::Occupied {
+                    generation: *generation,
+                    value,
+                };
+                *len += 1;
+                return Index {
+                    index: slot,
+                    generation: *generation,
+                    arena: *id,
+                };
+            }
+            self.spill();
+        }
+
+        match &mut self.storage {
+            Storage::Heap(arena) => arena.insert(value),
+            Storage::Inline { .. } => unreachable!(),
+        }
+    }
+
+    /// Move the elements into a heap-allocated arena with room for more.
+    fn spill(&mut self) {
+        let arena = match &mut self.storage {
+            Storage::Inline {
+                items,
+                generation,
+                len,
+                id,
+            } => {
+                let mut entries = Vec::with_capacity(N * 2);
+                entries.extend(
+                    items
+                        .iter_mut()
+                        .map(|entry| mem::replace(entry, Entry::Free { next_free: None })),
+                );
+                let mut arena = Arena {
+                    items: entries,
+                    generation: *generation,
+                    free_list_head: None,
+                    free_list_tail: None,
+                    free_stack: Vec::new(),
+                    len: *len,
+                    occupied: 0..0,
+                    occupancy: Vec::new(),
+                    reuse: ReusePolicy::default(),
+                    id: *id,
+                    removals: 0,
+                    growth_percent: DEFAULT_GROWTH_PERCENT,
+                };
+                arena.rebuild_free_list();
+                arena.reserve_exact(cmp::max(N, 1));
+                arena
+            }
+            Storage::Heap(_) => return,
+        };
+        self.storage = Storage::Heap(arena);
+    }
+
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// If the element at index `i` is still in the arena, then it is
+    /// returned. If it is not in the arena, then `None` is returned.
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        match &mut self.storage {
+            Storage::Inline {
+                items,
+                generation,
+                len,
+                id,
+            } => {
+                i.check_arena(*id);
+                match items.get(i.index) {
+                    Some(Entry::Occupied { generation: g, .. }) if *g == i.generation => {}
+                    _ => return None,
+                }
+                *generation += 1;
+                *len -= 1;
+                match mem::replace(&mut items[i.index], Entry::Free { next_free: None }) {
+                    Entry::Occupied { value, .. } => Some(value),
+                    Entry::Free { .. } => unreachable!(),
+                }
+            }
+            Storage::Heap(arena) => arena.remove(i),
+        }
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index) -> bool {
+        self.get(i).is_some()
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index) -> Option<&T> {
+        match &self.storage {
+            Storage::Inline { items, id, .. } => {
+                i.check_arena(*id);
+                match items.get(i.index) {
+                    Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+            Storage::Heap(arena) => arena.get(i),
+        }
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        match &mut self.storage {
+            Storage::Inline { items, id, .. } => {
+                i.check_arena(*id);
+                match items.get_mut(i.index) {
+                    Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+            Storage::Heap(arena) => arena.get_mut(i),
+        }
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { len, .. } => *len,
+            Storage::Heap(arena) => arena.len(),
+        }
+    }
+
+    /// Returns true if the arena contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the number of elements the arena can hold without further
+    /// allocation.
+    pub fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { .. } => N,
+            Storage::Heap(arena) => arena.capacity(),
+        }
+    }
+
+    /// Iterate over shared references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index, &T)` items.
+    ///
+    /// Order of iteration is not defined.
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &self.storage {
+            Storage::Inline { items, len, id, .. } => Iter {
+                len: *len,
+                offset: 0,
+                arena: *id,
+                inner: items.iter(),
+            },
+            Storage::Heap(arena) => arena.iter(),
+        }
+    }
+
+    /// Iterate over exclusive references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index, &mut T)` items.
+    ///
+    /// Order of iteration is not defined.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        match &mut self.storage {
+            Storage::Inline { items, len, id, .. } => IterMut {
+                len: *len,
+                offset: 0,
+                arena: *id,
+                inner: items.iter_mut(),
+            },
+            Storage::Heap(arena) => arena.iter_mut(),
+        }
+    }
+
+    fn lookup_error(&self, i: Index) -> LookupError {
+        match &self.storage {
+            Storage::Inline { items, .. } => LookupError::new(i, items),
+            Storage::Heap(arena) => LookupError::new(i, &arena.items),
+        }
+    }
+
+    /// Convert this into an ordinary `Arena`, keeping every `Index` valid.
+    pub fn into_arena(mut self) -> Arena<T> {
+        self.spill();
+        match self.storage {
+            Storage::Heap(arena) => arena,
+            Storage::Inline { .. } => unreachable!(),
+        }
+    }
+}
+
+impl<T, const N: usize> From<SmallArena<T, N>> for Arena<T> {
+    fn from(arena: SmallArena<T, N>) -> Arena<T> {
+        arena.into_arena()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallArena<T, N> {
+    type Item = (Index, &'a T);
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallArena<T, N> {
+    type Item = (Index, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> ops::Index<Index> for SmallArena<T, N> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!("{}", self.lookup_error(index)),
+        }
+    }
+}
+
+impl<T, const N: usize> ops::IndexMut<Index> for SmallArena<T, N> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        if !self.contains(index) {
+            panic!("{}", self.lookup_error(index));
+        }
+        self.get_mut(index).expect("element was just found")
+    }
+}
@@ -0,0 +1,92 @@
+use super::{vec, Arena, Index, Vec};
+use core::iter::FusedIterator;
+
+impl<T> Arena<T> {
+    /// Capture the indices of the elements currently in the arena, to visit
+    /// them later without holding a borrow of the arena.
+    ///
+    /// The returned `Snapshot` does not borrow the arena, so elements may be
+    /// inserted or removed between steps of the iteration. Elements inserted
+    /// after the snapshot was taken are not visited, and `Snapshot::next_in`
+    /// skips elements removed since. Indices are captured in ascending slot
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    /// use std::cell::RefCell;
+    ///
+    /// let arena = RefCell::new(Arena::new());
+    /// arena.borrow_mut().extend(0..3);
+    ///
+    /// let mut seen = vec![];
+    /// let mut snapshot = arena.borrow().iter_snapshot();
+    /// loop {
+    ///     let n = match snapshot.next_in(&arena.borrow()) {
+    ///         Some((_, n)) => *n,
+    ///         None => break,
+    ///     };
+    ///     seen.push(n);
+    ///     // Spawning new elements while iterating is fine.
+    ///     arena.borrow_mut().insert(n + 10);
+    /// }
+    /// assert_eq!(seen, [0, 1, 2]);
+    /// assert_eq!(arena.borrow().len(), 6);
+    /// ```
+    pub fn iter_snapshot(&self) -> Snapshot {
+        Snapshot {
+            indices: self.iter().map(|(i, _)| i).collect::<Vec<_>>().into_iter(),
+        }
+    }
+}
+
+/// The indices of an arena's elements at some point in time.
+///
+/// Yields the captured `Index`es as an `Iterator`, whether or not their
+/// elements are still in the arena. Use `next_in` or `next_in_mut` to skip
+/// the ones that have been removed since.
+///
+/// Created by `Arena::iter_snapshot`.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    indices: vec::IntoIter<Index>,
+}
+
+impl Snapshot {
+    /// Advance to the next captured element that is still in `arena`, and
+    /// get a shared reference to it.
+    pub fn next_in<'a, T>(&mut self, arena: &'a Arena<T>) -> Option<(Index, &'a T)> {
+        let i = self.indices.find(|&i| arena.contains(i))?;
+        Some((i, &arena[i]))
+    }
+
+    /// Advance to the next captured element that is still in `arena`, and
+    /// get an exclusive reference to it.
+    pub fn next_in_mut<'a, T>(&mut self, arena: &'a mut Arena<T>) -> Option<(Index, &'a mut T)> {
+        let i = self.indices.find(|&i| arena.contains(i))?;
+        Some((i, &mut arena[i]))
+    }
+}
+
+impl Iterator for Snapshot {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Index> {
+        self.indices.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Snapshot {
+    fn next_back(&mut self) -> Option<Index> {
+        self.indices.next_back()
+    }
+}
+
+impl ExactSizeIterator for Snapshot {}
+
+impl FusedIterator for Snapshot {}
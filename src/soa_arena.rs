@@ -0,0 +1,261 @@
+use super::{ArenaId, Index, LookupError, SlotState, Vec};
+use core::{iter, ops};
+
+/// An arena that stores its elements' values and generations in separate,
+/// parallel arrays.
+///
+/// An `Arena` keeps each slot's generation, free list link and value
+/// together in one `Entry`. That is compact for lookups, but iterating over
+/// the values alone then strides over the bookkeeping, too. A `SoaArena`
+/// instead keeps the values in one `Vec<Option<T>>` and the generations in
+/// another, with the free slots in a third, so that `values` and
+/// `values_mut` walk densely packed values only. Lookups by `Index` touch
+/// both arrays.
+///
+/// Indices work as they do with `Arena`: each removal advances the arena's
+/// generation, so a stale `Index` never refers to a later element. Free
+/// slots are reused most recently freed first.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::SoaArena;
+///
+/// let mut particles = SoaArena::new();
+/// let a = particles.insert(1.0_f32);
+/// let b = particles.insert(2.0);
+/// particles.remove(a);
+///
+/// for speed in particles.values_mut() {
+///     *speed *= 2.0;
+/// }
+/// assert_eq!(particles[b], 4.0);
+/// assert_eq!(particles.get(a), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SoaArena<T> {
+    // The generation of the element in each slot, or of the last element
+    // that was in it.
+    generations: Vec<u64>,
+    values: Vec<Option<T>>,
+    // The free slots, in the order they are reused from the end.
+    free: Vec<usize>,
+    generation: u64,
+    len: usize,
+    id: ArenaId,
+}
+
+impl<T> Default for SoaArena<T> {
+    fn default() -> SoaArena<T> {
+        SoaArena::new()
+    }
+}
+
+impl<T> SoaArena<T> {
+    /// Constructs a new, empty `SoaArena`.
+    pub fn new() -> SoaArena<T> {
+        SoaArena::with_capacity(0)
+    }
+
+    /// Constructs a new, empty `SoaArena` with room for `n` elements.
+    pub fn with_capacity(n: usize) -> SoaArena<T> {
+        SoaArena {
+            generations: Vec::with_capacity(n),
+            values: Vec::with_capacity(n),
+            free: Vec::new(),
+            generation: 0,
+            len: 0,
+            id: ArenaId::fresh(),
+        }
+    }
+
+    /// Insert `value` into the arena, allocating more capacity if necessary.
+    ///
+    /// The `value`'s associated index in the arena is returned.
+    pub fn insert(&mut self, value: T) -> Index {
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.generations[slot] = self.generation;
+                self.values[slot] = Some(value);
+                slot
+            }
+            None => {
+                self.generations.push(self.generation);
+                self.values.push(Some(value));
+                self.values.len() - 1
+            }
+        };
+        self.len += 1;
+        Index {
+            index: slot,
+            generation: self.generation,
+            arena: self.id,
+        }
+    }
+
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// If the element at index `i` is still in the arena, then it is
+    /// returned. If it is not in the arena, then `None` is returned.
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        if !self.contains(i) {
+            return None;
+        }
+        self.generation += 1;
+        self.len -= 1;
+        self.free.push(i.index);
+        self.values[i.index].take()
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index) -> bool {
+        self.get(i).is_some()
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index) -> Option<&T> {
+        i.check_arena(self.id);
+        match self.generations.get(i.index) {
+            Some(&generation) if generation == i.generation => self.values[i.index].as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        i.check_arena(self.id);
+        match self.generations.get(i.index) {
+            Some(&generation) if generation == i.generation => self.values[i.index].as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the arena contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of elements the arena can hold without further
+    /// allocation.
+    pub fn capacity(&self) -> usize {
+        self.free.len() + self.values.capacity() - self.values.len()
+    }
+
+    /// Remove every element from the arena, but keep its allocation.
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.generation += 1;
+        }
+        self.generations.clear();
+        self.values.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+
+    /// Iterate over shared references to the elements in this arena, along
+    /// with their indices, in ascending slot order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Index, &T)> + '_ {
+        let id = self.id;
+        self.generations
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .filter_map(move |(slot, (&generation, value))| {
+                let index = Index {
+                    index: slot,
+                    generation,
+                    arena: id,
+                };
+                value.as_ref().map(|value| (index, value))
+            })
+    }
+
+    /// Iterate over exclusive references to the elements in this arena,
+    /// along with their indices, in ascending slot order.
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (Index, &mut T)> + '_ {
+        let id = self.id;
+        self.generations
+            .iter()
+            .zip(&mut self.values)
+            .enumerate()
+            .filter_map(move |(slot, (&generation, value))| {
+                let index = Index {
+                    index: slot,
+                    generation,
+                    arena: id,
+                };
+                value.as_mut().map(|value| (index, value))
+            })
+    }
+
+    /// Iterate over shared references to the elements in this arena, in
+    /// ascending slot order, without their indices.
+    ///
+    /// This only reads the values array, so it is the fastest way to visit
+    /// every element.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.values.iter().filter_map(Option::as_ref)
+    }
+
+    /// Iterate over exclusive references to the elements in this arena, in
+    /// ascending slot order, without their indices.
+    pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        self.values.iter_mut().filter_map(Option::as_mut)
+    }
+
+    fn lookup_error(&self, index: Index) -> LookupError {
+        let state = match self.values.get(index.index) {
+            None => SlotState::OutOfBounds {
+                slot_count: self.values.len(),
+            },
+            Some(None) => SlotState::Free,
+            Some(Some(_)) => SlotState::Occupied {
+                generation: self.generations[index.index],
+            },
+        };
+        LookupError { index, state }
+    }
+}
+
+impl<T> Extend<T> for SoaArena<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> iter::FromIterator<T> for SoaArena<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut arena = SoaArena::new();
+        arena.extend(iter);
+        arena
+    }
+}
+
+impl<T> ops::Index<Index> for SoaArena<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!("{}", self.lookup_error(index)),
+        }
+    }
+}
+
+impl<T> ops::IndexMut<Index> for SoaArena<T> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        if !self.contains(index) {
+            panic!("{}", self.lookup_error(index));
+        }
+        self.get_mut(index).expect("element was just found")
+    }
+}
@@ -0,0 +1,236 @@
+use super::{Arena, Index, Vec};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// How far up the generation a shard's number is stored.
+///
+/// Each shard's generation counter starts at `shard << SHARD_SHIFT`, so the
+/// shard can be read back out of any `Index` it hands out, and shards never
+/// hand out the same `Index`. `remove` keeps the counter below the next
+/// shard's start.
+const SHARD_SHIFT: u32 = 48;
+
+/// The most shards that fit in the bits above `SHARD_SHIFT`.
+const MAX_SHARDS: usize = 1 << (64 - SHARD_SHIFT);
+
+/// The most elements one shard can remove before its generation counter
+/// would run into the next shard's range.
+const MAX_REMOVALS: u64 = (1 << SHARD_SHIFT) - 1;
+
+/// One shard of a set of per-thread arenas.
+///
+/// `ThreadLocalArena::shards` creates a fixed number of shards, each meant to
+/// be moved to and owned by one thread. Only the owner of a shard can insert
+/// into or remove from it, while a `ShardRouter` lets any thread read from
+/// any shard. Every `Index` records the shard that created it, so the router
+/// needs nothing else to find its element, and the handles keep the same
+/// format as those of a plain `Arena`.
+///
+/// Each shard is guarded by a lock, which its owner only takes for writing
+/// while mutating it.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::ThreadLocalArena;
+/// use std::thread;
+///
+/// let shards = ThreadLocalArena::shards(4);
+/// let router = shards[0].router();
+///
+/// let workers: Vec<_> = shards
+///     .into_iter()
+///     .map(|mut shard| {
+///         thread::spawn(move || {
+///             let n = shard.shard();
+///             (shard.insert(n * 10), shard)
+///         })
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     let (idx, _shard) = worker.join().unwrap();
+///     let n = router.shard_of(idx);
+///     assert_eq!(router.get_with(idx, |value| *value), Some(n * 10));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ThreadLocalArena<T> {
+    shard: usize,
+    shards: Arc<[RwLock<Arena<T>>]>,
+}
+
+/// A cheaply cloneable handle for reading the elements of any shard in a set
+/// of `ThreadLocalArena`s.
+///
+/// Created by `ThreadLocalArena::router`.
+#[derive(Debug)]
+pub struct ShardRouter<T> {
+    shards: Arc<[RwLock<Arena<T>>]>,
+}
+
+impl<T> Clone for ShardRouter<T> {
+    fn clone(&self) -> ShardRouter<T> {
+        ShardRouter {
+            shards: self.shards.clone(),
+        }
+    }
+}
+
+fn shard_of(i: Index) -> usize {
+    (i.generation >> SHARD_SHIFT) as usize
+}
+
+impl<T> ThreadLocalArena<T> {
+    /// Create `n` empty shards, numbered `0` through `n - 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero or greater than 65536.
+    pub fn shards(n: usize) -> Vec<ThreadLocalArena<T>> {
+        assert!(n > 0, "there must be at least one shard");
+        assert!(n <= MAX_SHARDS, "there can be at most {} shards", MAX_SHARDS);
+        let shards: Arc<[RwLock<Arena<T>>]> = (0..n)
+            .map(|shard| RwLock::new(Arena::with_generation_start((shard as u64) << SHARD_SHIFT)))
+            .collect();
+        (0..n)
+            .map(|shard| ThreadLocalArena {
+                shard,
+                shards: shards.clone(),
+            })
+            .collect()
+    }
+
+    /// Get the number of this shard.
+    pub fn shard(&self) -> usize {
+        self.shard
+    }
+
+    /// Get a router for reading from every shard, including this one.
+    pub fn router(&self) -> ShardRouter<T> {
+        ShardRouter {
+            shards: self.shards.clone(),
+        }
+    }
+
+    fn arena(&self) -> &RwLock<Arena<T>> {
+        &self.shards[self.shard]
+    }
+
+    /// Panic if `i` was handed out by another shard.
+    fn check_shard(&self, i: Index) {
+        if shard_of(i) != self.shard {
+            panic!(
+                "{:?} belongs to shard {}, not shard {}",
+                i,
+                shard_of(i),
+                self.shard
+            );
+        }
+    }
+
+    /// Insert `value` into this shard, allocating more capacity if necessary.
+    ///
+    /// The `value`'s associated index is returned.
+    pub fn insert(&mut self, value: T) -> Index {
+        self.arena()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(value)
+    }
+
+    /// Remove the element at index `i` from this shard.
+    ///
+    /// If the element at index `i` is still in the shard, then it is
+    /// returned. If it is not in the shard, then `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` was handed out by another shard, or if this shard has
+    /// already removed `(1 << 48) - 1` elements. Each removal advances the
+    /// shard's generation counter, and one more would hand out indices that
+    /// belong to the next shard.
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        self.check_shard(i);
+        let mut arena = self.arena().write().unwrap_or_else(PoisonError::into_inner);
+        if arena.contains(i) {
+            let start = (self.shard as u64) << SHARD_SHIFT;
+            assert!(
+                arena.generation() - start < MAX_REMOVALS,
+                "shard {} has used up its {} removals",
+                self.shard,
+                MAX_REMOVALS
+            );
+        }
+        arena.remove(i)
+    }
+
+    /// Call `f` with a shared reference to the element at index `i`, if it
+    /// is in this shard, and return its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` was handed out by another shard.
+    pub fn get_with<U>(&self, i: Index, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.check_shard(i);
+        self.arena()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_with(i, f)
+    }
+
+    /// Call `f` with an exclusive reference to the element at index `i`, if
+    /// it is in this shard, and return its result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` was handed out by another shard.
+    pub fn get_mut_with<U>(&mut self, i: Index, f: impl FnOnce(&mut T) -> U) -> Option<U> {
+        self.check_shard(i);
+        self.arena()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut_with(i, f)
+    }
+
+    /// Get the number of elements in this shard.
+    pub fn len(&self) -> usize {
+        self.arena()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if this shard contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> ShardRouter<T> {
+    /// Get the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Get the number of the shard that handed out index `i`.
+    pub fn shard_of(&self, i: Index) -> usize {
+        shard_of(i)
+    }
+
+    /// Is the element at index `i` in its shard?
+    pub fn contains(&self, i: Index) -> bool {
+        self.get_with(i, |_| ()).is_some()
+    }
+
+    /// Call `f` with a shared reference to the element at index `i`, if it
+    /// is in its shard, and return its result.
+    ///
+    /// Indices that no shard could have handed out are not found.
+    pub fn get_with<U>(&self, i: Index, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.shards
+            .get(shard_of(i))?
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_with(i, f)
+    }
+}
@@ -0,0 +1,217 @@
+use super::{Arena, Index, OptionIndex, Vec};
+use core::ops;
+use core::slice;
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    value: T,
+    parent: OptionIndex,
+    children: Vec<Index>,
+}
+
+/// A forest of elements in an arena, where each element may have a parent
+/// and any number of children.
+///
+/// Removing an element with `remove` also removes all of its descendants,
+/// while `remove_orphaning` turns its children into roots instead. Either
+/// way, every link to the removed elements is unlinked, so no element is
+/// ever left pointing at a removed parent or child, and since no element is
+/// inserted partway through a removal, no slot is reused while one is in
+/// progress.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::ArenaTree;
+///
+/// let mut scene = ArenaTree::new();
+/// let world = scene.insert("world");
+/// let car = scene.insert_child(world, "car").unwrap();
+/// let wheel = scene.insert_child(car, "wheel").unwrap();
+/// let tree = scene.insert_child(world, "tree").unwrap();
+///
+/// assert_eq!(scene.children(world).collect::<Vec<_>>(), [car, tree]);
+/// assert_eq!(scene.parent(wheel), Some(car));
+///
+/// // Removing the car removes its wheel, too.
+/// assert_eq!(scene.remove(car), Some("car"));
+/// assert!(!scene.contains(wheel));
+/// assert_eq!(scene.children(world).collect::<Vec<_>>(), [tree]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ArenaTree<T> {
+    nodes: Arena<Node<T>>,
+}
+
+impl<T> Default for ArenaTree<T> {
+    fn default() -> ArenaTree<T> {
+        ArenaTree::new()
+    }
+}
+
+impl<T> ArenaTree<T> {
+    /// Constructs a new, empty `ArenaTree`.
+    pub fn new() -> ArenaTree<T> {
+        ArenaTree {
+            nodes: Arena::new(),
+        }
+    }
+
+    /// Constructs a new, empty `ArenaTree` with the specified capacity.
+    pub fn with_capacity(n: usize) -> ArenaTree<T> {
+        ArenaTree {
+            nodes: Arena::with_capacity(n),
+        }
+    }
+
+    /// Get the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true if the tree contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Insert `value` as a new root, with no parent.
+    pub fn insert(&mut self, value: T) -> Index {
+        self.nodes.insert(Node {
+            value,
+            parent: OptionIndex::NONE,
+            children: Vec::new(),
+        })
+    }
+
+    /// Insert `value` as the last child of `parent`.
+    ///
+    /// Returns `None`, and drops `value`, if `parent` is not in the tree.
+    pub fn insert_child(&mut self, parent: Index, value: T) -> Option<Index> {
+        if !self.nodes.contains(parent) {
+            return None;
+        }
+        let child = self.nodes.insert(Node {
+            value,
+            parent: parent.into(),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(child);
+        Some(child)
+    }
+
+    /// Is the element at index `i` in the tree?
+    pub fn contains(&self, i: Index) -> bool {
+        self.nodes.contains(i)
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// tree.
+    pub fn get(&self, i: Index) -> Option<&T> {
+        self.nodes.get(i).map(|node| &node.value)
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// tree.
+    pub fn get_mut(&mut self, i: Index) -> Option<&mut T> {
+        self.nodes.get_mut(i).map(|node| &mut node.value)
+    }
+
+    /// Get the parent of the element at index `i`.
+    ///
+    /// Returns `None` if the element is a root or is not in the tree.
+    pub fn parent(&self, i: Index) -> Option<Index> {
+        self.nodes.get(i)?.parent.get()
+    }
+
+    /// Iterate over the children of the element at index `i`, in the order
+    /// they were inserted.
+    ///
+    /// The iterator is empty if the element is not in the tree.
+    pub fn children(&self, i: Index) -> Children<'_> {
+        let children = match self.nodes.get(i) {
+            Some(node) => &node.children[..],
+            None => &[],
+        };
+        Children {
+            inner: children.iter(),
+        }
+    }
+
+    /// Remove the element at index `i` and all of its descendants.
+    ///
+    /// Returns the element's value, or `None` if it was not in the tree. The
+    /// descendants' values are dropped.
+    pub fn remove(&mut self, i: Index) -> Option<T> {
+        let node = self.unlink(i)?;
+        let mut doomed = node.children;
+        while let Some(child) = doomed.pop() {
+            if let Some(child) = self.nodes.remove(child) {
+                doomed.extend(child.children);
+            }
+        }
+        Some(node.value)
+    }
+
+    /// Remove the element at index `i`, turning its children into roots.
+    ///
+    /// Returns the element's value, or `None` if it was not in the tree.
+    pub fn remove_orphaning(&mut self, i: Index) -> Option<T> {
+        let node = self.unlink(i)?;
+        for child in node.children {
+            self.nodes[child].parent = OptionIndex::NONE;
+        }
+        Some(node.value)
+    }
+
+    /// Remove the element at index `i` from the arena and from its parent's
+    /// children, leaving its own children to the caller.
+    fn unlink(&mut self, i: Index) -> Option<Node<T>> {
+        let node = self.nodes.remove(i)?;
+        if let Some(parent) = node.parent.get() {
+            self.nodes[parent].children.retain(|&child| child != i);
+        }
+        Some(node)
+    }
+}
+
+impl<T> ops::Index<Index> for ArenaTree<T> {
+    type Output = T;
+
+    fn index(&self, index: Index) -> &Self::Output {
+        &self.nodes[index].value
+    }
+}
+
+impl<T> ops::IndexMut<Index> for ArenaTree<T> {
+    fn index_mut(&mut self, index: Index) -> &mut Self::Output {
+        &mut self.nodes[index].value
+    }
+}
+
+/// An iterator over the children of an element of an `ArenaTree`.
+///
+/// Created by `ArenaTree::children`.
+#[derive(Clone, Debug)]
+pub struct Children<'a> {
+    inner: slice::Iter<'a, Index>,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Index;
+
+    fn next(&mut self) -> Option<Index> {
+        self.inner.next().copied()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Children<'a> {
+    fn next_back(&mut self) -> Option<Index> {
+        self.inner.next_back().copied()
+    }
+}
+
+impl<'a> ExactSizeIterator for Children<'a> {}
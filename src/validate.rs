@@ -0,0 +1,268 @@
+use super::{Arena, Entry, Index, Vec};
+use core::{cmp, fmt};
+
+/// A single broken invariant found by `Arena::validate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corruption {
+    /// The arena's element count does not match its occupied slots.
+    WrongLen {
+        /// The element count the arena reports.
+        len: usize,
+        /// The number of occupied slots.
+        occupied: usize,
+    },
+    /// The free list links to a slot that does not exist.
+    FreeListOutOfBounds {
+        /// The missing slot.
+        slot: usize,
+    },
+    /// The free list links to an occupied slot.
+    FreeListOccupied {
+        /// The occupied slot.
+        slot: usize,
+    },
+    /// The free list visits the same slot twice.
+    FreeListCycle {
+        /// The first slot visited twice.
+        slot: usize,
+    },
+    /// A free slot is missing from the free list.
+    Unlisted {
+        /// The missing slot.
+        slot: usize,
+    },
+    /// The free list's recorded tail is not its last slot.
+    WrongTail {
+        /// The recorded tail.
+        tail: Option<usize>,
+        /// The last slot actually on the free list.
+        last: Option<usize>,
+    },
+    /// An element's generation is newer than the arena's generation counter,
+    /// so a later insertion could hand out an equal `Index`.
+    GenerationAhead {
+        /// The element's index.
+        index: Index,
+    },
+    /// The recorded range of occupied slots is not the lowest occupied slot
+    /// through one past the highest.
+    WrongOccupiedBounds {
+        /// The recorded `(start, end)`.
+        recorded: (usize, usize),
+        /// The actual `(start, end)`, or `(0, 0)` if no slot is occupied.
+        actual: (usize, usize),
+    },
+    /// A slot's bit in the arena's occupancy bitmap does not say whether the
+    /// slot is occupied.
+    WrongOccupancyBit {
+        /// The slot whose bit is wrong.
+        slot: usize,
+    },
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Corruption::WrongLen { len, occupied } => write!(
+                f,
+                "len is {}, but {} slots are occupied",
+                len, occupied
+            ),
+            Corruption::FreeListOutOfBounds { slot } => {
+                write!(f, "free list links to missing slot {}", slot)
+            }
+            Corruption::FreeListOccupied { slot } => {
+                write!(f, "free list links to occupied slot {}", slot)
+            }
+            Corruption::FreeListCycle { slot } => {
+                write!(f, "free list visits slot {} twice", slot)
+            }
+            Corruption::Unlisted { slot } => write!(f, "free slot {} is not on the free list", slot),
+            Corruption::WrongTail { tail, last } => write!(
+                f,
+                "free list tail is {:?}, but its last slot is {:?}",
+                tail, last
+            ),
+            Corruption::GenerationAhead { index } => write!(
+                f,
+                "element {} is newer than the arena's generation",
+                index
+            ),
+            Corruption::WrongOccupiedBounds { recorded, actual } => write!(
+                f,
+                "occupied slots are recorded as {:?}, but are {:?}",
+                recorded, actual
+            ),
+            Corruption::WrongOccupancyBit { slot } => {
+                write!(f, "occupancy bit of slot {} is wrong", slot)
+            }
+        }
+    }
+}
+
+/// The broken invariants found by `Arena::validate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorruptionReport {
+    problems: Vec<Corruption>,
+}
+
+impl CorruptionReport {
+    /// Get every broken invariant that was found, in the order they were
+    /// found.
+    pub fn problems(&self) -> &[Corruption] {
+        &self.problems
+    }
+}
+
+impl fmt::Display for CorruptionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "arena is corrupt: ")?;
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CorruptionReport {}
+
+impl<T> Arena<T> {
+    /// Check the arena's internal invariants, reporting every one that does
+    /// not hold.
+    ///
+    /// The free list, or the free slot stack of an arena with
+    /// `ReusePolicy::Stack`, must hold exactly the free slots, each once,
+    /// and the free list must end at its recorded tail; `len` must count the
+    /// occupied slots, and the recorded range of occupied slots and the
+    /// occupancy bitmap must match them; and no element may be newer than
+    /// the arena's generation counter. Every arena built through this crate's
+    /// API upholds these, so this is meant for tests and debug assertions in
+    /// code that reconstructs arenas by other means, such as custom
+    /// deserialization.
+    ///
+    /// This visits every slot, so takes time proportional to `slot_count`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut arena = Arena::new();
+    /// let idx = arena.insert(1);
+    /// arena.insert(2);
+    /// arena.remove(idx);
+    ///
+    /// assert!(arena.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), CorruptionReport> {
+        let mut problems = Vec::new();
+        let slots = self.items.len();
+
+        let occupied = self
+            .items
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied { .. }))
+            .count();
+        if occupied != self.len {
+            problems.push(Corruption::WrongLen {
+                len: self.len,
+                occupied,
+            });
+        }
+
+        let mut listed = Vec::new();
+        listed.resize(slots, false);
+        let mut next = self.free_list_head;
+        let mut last = None;
+        while let Some(slot) = next {
+            let problem = match self.items.get(slot) {
+                None => Corruption::FreeListOutOfBounds { slot },
+                Some(Entry::Occupied { .. }) => Corruption::FreeListOccupied { slot },
+                Some(_) if listed[slot] => Corruption::FreeListCycle { slot },
+                Some(Entry::Free { next_free }) => {
+                    listed[slot] = true;
+                    last = Some(slot);
+                    next = *next_free;
+                    continue;
+                }
+            };
+            problems.push(problem);
+            break;
+        }
+        if next.is_none() && last != self.free_list_tail {
+            problems.push(Corruption::WrongTail {
+                tail: self.free_list_tail,
+                last,
+            });
+        }
+
+        // Arenas with `ReusePolicy::Stack` keep their free slots here
+        // instead, with an empty free list.
+        for &slot in &self.free_stack {
+            let problem = match self.items.get(slot) {
+                None => Corruption::FreeListOutOfBounds { slot },
+                Some(Entry::Occupied { .. }) => Corruption::FreeListOccupied { slot },
+                Some(_) if listed[slot] => Corruption::FreeListCycle { slot },
+                Some(Entry::Free { .. }) => {
+                    listed[slot] = true;
+                    continue;
+                }
+            };
+            problems.push(problem);
+        }
+
+        for (slot, entry) in self.items.iter().enumerate() {
+            match *entry {
+                Entry::Free { .. } if !listed[slot] => {
+                    problems.push(Corruption::Unlisted { slot });
+                }
+                Entry::Occupied { generation, .. } if generation > self.generation => {
+                    problems.push(Corruption::GenerationAhead {
+                        index: Index {
+                            index: slot,
+                            generation,
+                            arena: self.id,
+                        },
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let first = self
+            .items
+            .iter()
+            .position(|entry| matches!(entry, Entry::Occupied { .. }));
+        let last = self
+            .items
+            .iter()
+            .rposition(|entry| matches!(entry, Entry::Occupied { .. }));
+        let actual = match (first, last) {
+            (Some(first), Some(last)) => (first, last + 1),
+            _ => (0, 0),
+        };
+        let recorded = (self.occupied.start, self.occupied.end);
+        if recorded != actual {
+            problems.push(Corruption::WrongOccupiedBounds { recorded, actual });
+        }
+
+        let bits = self.occupancy.len() * 64;
+        for slot in 0..cmp::max(slots, bits) {
+            let bit = slot < bits && self.occupancy[slot / 64] & (1 << (slot % 64)) != 0;
+            let occupied = matches!(self.items.get(slot), Some(Entry::Occupied { .. }));
+            if bit != occupied {
+                problems.push(Corruption::WrongOccupancyBit { slot });
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(CorruptionReport { problems })
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{ArenaHooks, Index};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use std::collections::HashMap;
+
+/// A hash index from a key of each element to the element's `Index`, for
+/// looking elements up by value in constant time.
+///
+/// A `ValueIndex` is a set of `ArenaHooks`, so it stays in sync with an
+/// `ObservedArena` as elements are inserted and removed. The key of each
+/// element is computed by a user-provided function when the element is
+/// inserted. Modifying an element in place does not update the index, so
+/// the key should not depend on anything that changes after insertion.
+///
+/// Each key maps to a single element. If several elements share a key, the
+/// most recently inserted one is found, until it is removed.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena::{ObservedArena, ValueIndex};
+///
+/// struct Player {
+///     name: String,
+///     score: u32,
+/// }
+///
+/// let mut arena = ObservedArena::new(ValueIndex::new(|p: &Player| p.name.clone()));
+/// let alice = arena.insert(Player { name: "alice".into(), score: 10 });
+/// arena.insert(Player { name: "bob".into(), score: 20 });
+///
+/// assert_eq!(arena.hooks().get("alice"), Some(alice));
+/// arena[alice].score += 5;
+///
+/// arena.remove(alice);
+/// assert_eq!(arena.hooks().get("alice"), None);
+/// ```
+pub struct ValueIndex<K, T, F> {
+    map: HashMap<K, Index>,
+    key: F,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<K, T, F> ValueIndex<K, T, F>
+where
+    K: Hash + Eq,
+    F: FnMut(&T) -> K,
+{
+    /// Constructs a new, empty `ValueIndex` that keys each element by the
+    /// result of `key`.
+    pub fn new(key: F) -> ValueIndex<K, T, F> {
+        ValueIndex {
+            map: HashMap::new(),
+            key,
+            marker: PhantomData,
+        }
+    }
+
+    /// Get the index of the element with the given key, if there is one.
+    pub fn get<Q>(&self, key: &Q) -> Option<Index>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).copied()
+    }
+
+    /// Get the number of keys in the index.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the index contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<K, T, F> ArenaHooks<T> for ValueIndex<K, T, F>
+where
+    K: Hash + Eq,
+    F: FnMut(&T) -> K,
+{
+    fn on_insert(&mut self, i: Index, value: &T) {
+        let key = (self.key)(value);
+        self.map.insert(key, i);
+    }
+
+    fn on_remove(&mut self, i: Index, value: &T) {
+        let key = (self.key)(value);
+        // Another element with the same key may have taken over the entry.
+        if self.map.get(&key) == Some(&i) {
+            self.map.remove(&key);
+        }
+    }
+}
+
+impl<K: fmt::Debug, T, F> fmt::Debug for ValueIndex<K, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ValueIndex").field("map", &self.map).finish()
+    }
+}
@@ -0,0 +1,162 @@
+//! An explicitly versioned serialization format for `Arena`.
+//!
+//! The default `Serialize` and `Deserialize` implementations for `Arena` use a
+//! bare sequence of entries with no header, so there is no way to tell which
+//! layout a piece of serialized data uses. The functions in this module wrap
+//! the arena in an envelope consisting of a format tag and a version number,
+//! so that data written by older versions of this crate can always be
+//! recognized and migrated when it is read back.
+//!
+//! Use this module with serde's `with` attribute:
+//!
+//! ```
+//! # extern crate serde;
+//! # extern crate bincode;
+//! use generational_arena::Arena;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct World {
+//!     #[serde(with = "generational_arena::versioned")]
+//!     entities: Arena<String>,
+//! }
+//!
+//! let mut world = World { entities: Arena::new() };
+//! let player = world.entities.insert("player".to_string());
+//!
+//! let bytes = bincode::serialize(&world).unwrap();
+//! let world: World = bincode::deserialize(&bytes).unwrap();
+//! assert_eq!(world.entities[player], "player");
+//! ```
+//!
+//! # Format
+//!
+//! An arena is serialized as a three-element tuple:
+//!
+//! 1. The string `"generational-arena"`.
+//! 2. The format version, a `u32`.
+//! 3. The version-specific payload.
+//!
+//! | Version | Payload |
+//! |---------|---------|
+//! | 1       | The same sequence of `Option<(generation, value)>` entries used by `Arena`'s own `Serialize` implementation. |
+//! | 2       | A `(generation, entries)` tuple of the arena's generation counter, a `u64`, and the entries as in version 1. |
+//!
+//! Deserialization accepts every version listed above. Serialization always
+//! writes the latest version, `FORMAT_VERSION`.
+//!
+//! Version 1 does not record the arena's generation counter, so it is
+//! reconstructed as the newest generation of any element. If the newest
+//! elements were removed before saving, an arena loaded from version 1 can
+//! then hand out an `Index` equal to one of theirs. Version 2 saves the
+//! counter, so an arena loaded from it never reissues an `Index` that was
+//! issued before it was saved.
+
+use super::Arena;
+use core::cmp;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// The tag written at the start of every versioned arena.
+pub const FORMAT_TAG: &str = "generational-arena";
+
+/// The version of the format written by `serialize`.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Serialize `arena` in the latest versioned format.
+pub fn serialize<T, S>(arena: &Arena<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    (FORMAT_TAG, FORMAT_VERSION, (arena.generation, arena)).serialize(serializer)
+}
+
+/// Deserialize an arena written in any known version of the versioned format.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Arena<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(3, VersionedArenaVisitor {
+        marker: PhantomData,
+    })
+}
+
+struct VersionedArenaVisitor<T> {
+    marker: PhantomData<fn() -> Arena<T>>,
+}
+
+impl<'de, T> Visitor<'de> for VersionedArenaVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Arena<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a versioned generational arena")
+    }
+
+    fn visit_seq<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: SeqAccess<'de>,
+    {
+        access
+            .next_element::<FormatTag>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version = access
+            .next_element::<u32>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        match version {
+            1 => access
+                .next_element::<Arena<T>>()?
+                .ok_or_else(|| de::Error::invalid_length(2, &self)),
+            2 => {
+                let (generation, mut arena) = access
+                    .next_element::<(u64, Arena<T>)>()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                arena.generation = cmp::max(arena.generation, generation);
+                Ok(arena)
+            }
+            v => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(u64::from(v)),
+                &"a known generational arena format version",
+            )),
+        }
+    }
+}
+
+/// Deserializes only from the exact string `FORMAT_TAG`.
+struct FormatTag;
+
+impl<'de> Deserialize<'de> for FormatTag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(FormatTagVisitor)
+    }
+}
+
+struct FormatTagVisitor;
+
+impl<'de> Visitor<'de> for FormatTagVisitor {
+    type Value = FormatTag;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the string {:?}", FORMAT_TAG)
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<FormatTag, E>
+    where
+        E: de::Error,
+    {
+        if s == FORMAT_TAG {
+            Ok(FormatTag)
+        } else {
+            Err(de::Error::invalid_value(de::Unexpected::Str(s), &self))
+        }
+    }
+}
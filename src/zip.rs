@@ -0,0 +1,124 @@
+use super::{Arena, ArenaId, Entry, Index, IterMut};
+use core::iter::{FusedIterator, Zip};
+use core::{fmt, slice};
+
+impl<'a, A> IterMut<'a, A> {
+    /// Pair up the elements that have not been yielded yet with the elements
+    /// of `other` in the same raw slots.
+    ///
+    /// This is for keeping one component per arena in arenas that share
+    /// their slot layout, for example because elements are always inserted
+    /// into and removed from all of them together. Slots that are free in
+    /// either arena are skipped. The yielded `Index` is the one for this
+    /// iterator's arena; the element of `other` may have a different
+    /// generation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena::Arena;
+    ///
+    /// let mut positions = Arena::new();
+    /// let mut velocities = Arena::new();
+    /// for (p, v) in [(0, 1), (10, -1), (20, 5)] {
+    ///     positions.insert(p);
+    ///     velocities.insert(v);
+    /// }
+    /// let gone = positions.iter().nth(1).unwrap().0;
+    /// positions.remove(gone);
+    ///
+    /// for (_, p, v) in positions.iter_mut().zip_indices_of(&mut velocities) {
+    ///     *p += *v;
+    /// }
+    /// let moved: Vec<_> = positions.iter().map(|(_, p)| *p).collect();
+    /// assert_eq!(moved, [1, 25]);
+    /// ```
+    pub fn zip_indices_of<B>(self, other: &'a mut Arena<B>) -> ZipIndicesOf<'a, A, B> {
+        let start = self.offset.min(other.items.len());
+        ZipIndicesOf {
+            offset: self.offset,
+            arena: self.arena,
+            inner: self.inner.zip(other.items[start..].iter_mut()),
+        }
+    }
+}
+
+/// An iterator over the elements of two arenas that occupy the same raw
+/// slot in both.
+///
+/// Yields triples of `(Index, &mut A, &mut B)`, where the `Index` is for the
+/// first arena.
+///
+/// Created with `IterMut::zip_indices_of`.
+pub struct ZipIndicesOf<'a, A: 'a, B: 'a> {
+    // The slot of the front of `inner`.
+    offset: usize,
+    arena: ArenaId,
+    inner: Zip<slice::IterMut<'a, Entry<A>>, slice::IterMut<'a, Entry<B>>>,
+}
+
+impl<'a, A, B> fmt::Debug for ZipIndicesOf<'a, A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZipIndicesOf")
+            .field("offset", &self.offset)
+            .field("remaining_slots", &self.inner.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, A, B> Iterator for ZipIndicesOf<'a, A, B> {
+    type Item = (Index, &'a mut A, &'a mut B);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let slot = self.offset;
+            let (a, b) = self.inner.next()?;
+            self.offset += 1;
+            if let (
+                Entry::Occupied {
+                    generation,
+                    value: a,
+                },
+                Entry::Occupied { value: b, .. },
+            ) = (a, b)
+            {
+                let idx = Index {
+                    index: slot,
+                    generation: *generation,
+                    arena: self.arena,
+                };
+                return Some((idx, a, b));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.inner.len()))
+    }
+}
+
+impl<'a, A, B> DoubleEndedIterator for ZipIndicesOf<'a, A, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let (a, b) = self.inner.next_back()?;
+            let slot = self.offset + self.inner.len();
+            if let (
+                Entry::Occupied {
+                    generation,
+                    value: a,
+                },
+                Entry::Occupied { value: b, .. },
+            ) = (a, b)
+            {
+                let idx = Index {
+                    index: slot,
+                    generation: *generation,
+                    arena: self.arena,
+                };
+                return Some((idx, a, b));
+            }
+        }
+    }
+}
+
+impl<'a, A, B> FusedIterator for ZipIndicesOf<'a, A, B> {}
@@ -2,7 +2,7 @@ extern crate generational_arena;
 #[macro_use]
 extern crate quickcheck;
 
-use generational_arena::Arena;
+use generational_arena::{Arena, Index};
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
@@ -137,13 +137,13 @@ quickcheck! {
 
         // check that the results from get() match get_unknown_check()
         inserted_indices.iter().enumerate().all(|(i, idx)| {
-            let shared_check = if let Some(_) = arena.get(*idx) {
+            let shared_check = if arena.get(*idx).is_some() {
                 let internal_index = idx.into_raw_parts().0;
                 arena.get_unknown_gen(internal_index).is_some() && unknown_gen_indices[i] == internal_index
             } else {
                 true
             };
-            let mut_check = if let Some(_) = arena.get_mut(*idx) {
+            let mut_check = if arena.get_mut(*idx).is_some() {
                 let internal_index = idx.into_raw_parts().0;
                 arena.get_unknown_gen_mut(internal_index).is_some() && unknown_gen_indices[i] == internal_index
             } else {
@@ -193,3 +193,805 @@ quickcheck! {
         }
     }
 }
+
+quickcheck! {
+    fn index_display_from_str_round_trip(index: usize, generation: u64) -> bool {
+        let idx = Index::from_raw_parts(index, generation);
+        idx.to_string().parse::<Index>() == Ok(idx)
+    }
+}
+
+quickcheck! {
+    fn apply_diff_reproduces_arena(ops: Vec<(bool, usize)>) -> bool {
+        let mut old = Arena::new();
+        let mut indices = vec![];
+        for (i, (delete, value)) in ops.iter().cloned().enumerate() {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                old.remove(idx);
+            } else if i % 3 == 0 && !indices.is_empty() {
+                let idx = indices[value % indices.len()];
+                old[idx] = value;
+            } else {
+                indices.push(old.insert(value));
+            }
+        }
+
+        let mut new = old.clone();
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                new.remove(idx);
+            } else if value % 2 == 0 && !indices.is_empty() {
+                let idx = indices[value % indices.len()];
+                new[idx] = value + 1;
+            } else {
+                indices.push(new.insert(value));
+            }
+        }
+
+        let mut patched = old.clone();
+        patched.apply_diff(old.diff(&new));
+        let fresh = patched.insert(usize::MAX);
+        patched.remove(fresh);
+
+        patched.len() == new.len()
+            && patched.iter().eq(new.iter())
+            && !new.contains(fresh)
+            && !old.contains(fresh)
+    }
+}
+
+quickcheck! {
+    fn iterators_yield_correct_indices_from_both_ends(ops: Vec<(bool, usize)>, ends: Vec<bool>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let mut ends = ends.into_iter().cycle().chain(std::iter::repeat(true));
+        let mut iter = arena.iter();
+        let mut ok = true;
+        while let Some((idx, value)) = if ends.next().unwrap() { iter.next() } else { iter.next_back() } {
+            ok &= arena.get(idx) == Some(value);
+        }
+
+        let expected = arena.clone();
+        let mut iter_mut = arena.iter_mut();
+        while let Some((idx, value)) = if ends.next().unwrap() { iter_mut.next() } else { iter_mut.next_back() } {
+            ok &= expected.get(idx) == Some(&*value);
+        }
+
+        let mut seen = 0;
+        let mut into_iter = arena.clone().into_iter_indexed();
+        while let Some((idx, value)) = if ends.next().unwrap() { into_iter.next() } else { into_iter.next_back() } {
+            ok &= expected.get(idx) == Some(&value);
+            seen += 1;
+        }
+        ok &= seen == expected.len();
+
+        let mut seen = 0;
+        let mut drain = arena.drain();
+        while let Some((idx, value)) = if ends.next().unwrap() { drain.next() } else { drain.next_back() } {
+            ok &= expected.get(idx) == Some(&value);
+            seen += 1;
+        }
+        ok && seen == expected.len()
+    }
+}
+
+quickcheck! {
+    fn occupancy_bitmap_matches_contains_slot(ops: Vec<(bool, usize)>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let mut bitmap = vec![!0; arena.capacity().div_ceil(64) + 1];
+        arena.collect_occupancy(&mut bitmap);
+        (0..bitmap.len() * 64).all(|slot| {
+            let bit = bitmap[slot / 64] & (1 << (slot % 64)) != 0;
+            bit == arena.contains_slot(slot) && bit == arena.get_unknown_gen(slot).is_some()
+        })
+    }
+}
+
+quickcheck! {
+    fn entries_describe_free_list_and_elements(ops: Vec<(bool, usize)>) -> bool {
+        use generational_arena::SlotView;
+
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let slots: Vec<_> = arena.entries().collect();
+        let occupied: Vec<_> = slots
+            .iter()
+            .filter_map(|slot| match *slot {
+                SlotView::Occupied { index, value } => Some((index, value)),
+                SlotView::Free { .. } => None,
+            })
+            .collect();
+        let free = slots.iter().filter(|slot| !slot.is_occupied()).count();
+
+        slots.len() == arena.slot_count()
+            && slots.iter().enumerate().all(|(i, slot)| slot.slot() == i)
+            && occupied == arena.iter().collect::<Vec<_>>()
+            && free == arena.slot_count() - arena.len()
+    }
+}
+
+quickcheck! {
+    fn fifo_interp(ops: Vec<(bool, usize)>) -> bool {
+        let mut arena = Arena::builder().capacity(1).fifo_reuse().build();
+        let mut live = vec![];
+        let mut free_slots: std::collections::VecDeque<usize> = (0..1).collect();
+
+        for (delete, i) in ops {
+            if delete && !live.is_empty() {
+                let (idx, expected): (Index, usize) = live.swap_remove(i % live.len());
+                if arena.remove(idx) != Some(expected) {
+                    return false;
+                }
+                free_slots.push_back(idx.into_raw_parts().0);
+            } else {
+                let old_capacity = arena.capacity();
+                let idx = arena.insert(i);
+                if free_slots.is_empty() {
+                    // Growing adds fresh slots, which are used first.
+                    free_slots.extend(old_capacity..arena.capacity());
+                }
+                if free_slots.pop_front() != Some(idx.into_raw_parts().0) {
+                    return false;
+                }
+                live.push((idx, i));
+            }
+        }
+        live.into_iter().all(|(idx, expected)| arena[idx] == expected)
+    }
+}
+
+quickcheck! {
+    fn get_unknown_gen_many_matches_single_lookups(ops: Vec<(bool, usize)>, slots: Vec<usize>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let mut slots: Vec<usize> = slots.into_iter().map(|s| s % (arena.capacity() + 2)).collect();
+        let expected: Vec<_> = slots.iter().map(|&s| arena.get_unknown_gen(s).map(|(v, i)| (*v, i))).collect();
+        let shared: Vec<_> = arena.get_unknown_gen_many(&slots).map(|f| f.map(|(v, i)| (*v, i))).collect();
+
+        slots.sort_unstable();
+        slots.dedup();
+        let expected_mut: Vec<_> = slots.iter().map(|&s| arena.get_unknown_gen(s).map(|(v, i)| (*v, i))).collect();
+        let exclusive: Vec<_> = arena
+            .get_unknown_gen_many_mut(&slots)
+            .into_iter()
+            .map(|f| f.map(|(v, i)| (*v, i)))
+            .collect();
+
+        shared == expected && exclusive == expected_mut
+    }
+}
+
+#[cfg(feature = "rayon")]
+quickcheck! {
+    fn par_retain_matches_retain(ops: Vec<(bool, usize)>, modulus: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let modulus = modulus % 5 + 1;
+        let mut serial = arena.clone();
+        serial.retain(|_, v| *v % modulus == 0);
+        arena.par_retain(|_, v| *v % modulus == 0);
+
+        // Both must also reuse the freed slots in the same order.
+        let a = serial.insert(0);
+        let b = arena.insert(0);
+        serial.iter().eq(arena.iter()) && a == b
+    }
+}
+
+quickcheck! {
+    fn arena_graph_never_has_dangling_edges(ops: Vec<(u8, usize, usize)>) -> bool {
+        use generational_arena::ArenaGraph;
+
+        let mut graph = ArenaGraph::new();
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for (op, a, b) in ops {
+            match op % 4 {
+                0 => nodes.push(graph.add_node(a)),
+                1 if !nodes.is_empty() => {
+                    let from = nodes[a % nodes.len()];
+                    let to = nodes[b % nodes.len()];
+                    edges.push(graph.add_edge(from, to, a).unwrap());
+                }
+                2 if !nodes.is_empty() => {
+                    let n = nodes.swap_remove(a % nodes.len());
+                    if graph.remove_node(n).is_none() || graph.add_edge(n, n, 0).is_some() {
+                        return false;
+                    }
+                }
+                3 if !edges.is_empty() => {
+                    let e = edges.swap_remove(a % edges.len());
+                    graph.remove_edge(e);
+                }
+                _ => {}
+            }
+        }
+
+        graph.node_count() == nodes.len()
+            && graph.edges().all(|(e, _)| {
+                let (from, to) = graph.edge_endpoints(e).unwrap();
+                graph.edges_from(from).any(|&x| x == e) && graph.edges_to(to).any(|&x| x == e)
+            })
+            && graph.nodes().all(|(n, _)| {
+                graph.edges_from(n).all(|&e| graph.edge_endpoints(e).map(|(from, _)| from) == Some(n))
+                    && graph.edges_to(n).all(|&e| graph.edge_endpoints(e).map(|(_, to)| to) == Some(n))
+                    && graph.neighbors(n).all(|m| graph.contains_node(m))
+            })
+    }
+}
+
+quickcheck! {
+    fn iterator_overrides_match_next(ops: Vec<(bool, usize)>, n: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let n = n % (arena.len() + 2);
+
+        // `for` loops only ever call `next`.
+        fn by_next<I: Iterator>(iter: I) -> Vec<I::Item> {
+            let mut items = vec![];
+            for item in iter {
+                items.push(item);
+            }
+            items
+        }
+
+        let expected: Vec<(Index, usize)> = by_next(arena.iter()).into_iter().map(|(i, v)| (i, *v)).collect();
+
+        let mut nth_iter = arena.iter();
+        let nth = nth_iter.nth(n).map(|(i, v)| (i, *v));
+        let after_nth = nth_iter.next().map(|(i, v)| (i, *v));
+
+        let mut nth_iter_mut = arena.clone();
+        let mut nth_iter_mut = nth_iter_mut.iter_mut();
+        let nth_mut = nth_iter_mut.nth(n).map(|(i, v)| (i, *v));
+
+        arena.iter().map(|(i, v)| (i, *v)).fold(vec![], |mut acc, x| { acc.push(x); acc }) == expected
+            && arena.iter().count() == expected.len()
+            && arena.iter().last().map(|(i, v)| (i, *v)) == expected.last().cloned()
+            && nth == expected.get(n).cloned()
+            && after_nth == expected.get(n + 1).cloned()
+            && nth_mut == nth
+            && arena.clone().iter_mut().map(|(i, v)| (i, *v)).collect::<Vec<_>>() == expected
+            && arena.clone().drain().collect::<Vec<_>>() == expected
+            && arena.clone().drain().nth(n) == nth
+            && arena.clone().drain().last() == expected.last().cloned()
+            && arena.clone().into_iter().collect::<Vec<_>>() == expected.iter().map(|&(_, v)| v).collect::<Vec<_>>()
+            && arena.clone().into_iter().nth(n) == nth.map(|(_, v)| v)
+    }
+}
+
+quickcheck! {
+    fn merge_moves_every_element(a: Vec<(bool, usize)>, b: Vec<(bool, usize)>) -> bool {
+        fn build(ops: Vec<(bool, usize)>) -> Arena<usize> {
+            let mut arena = Arena::new();
+            let mut indices = vec![];
+            for (delete, value) in ops {
+                if delete && !indices.is_empty() {
+                    let idx = indices.swap_remove(value % indices.len());
+                    arena.remove(idx);
+                } else {
+                    indices.push(arena.insert(value));
+                }
+            }
+            arena
+        }
+
+        let mut merged = build(a);
+        let before = merged.clone();
+        let other = build(b);
+        let expected = other.clone();
+
+        let mut pairs = vec![];
+        merged.merge(other, |old, new| pairs.push((old, new)));
+
+        let grew_once = merged.capacity() == before.capacity()
+            || merged.capacity() == before.len() + expected.len();
+        merged.len() == before.len() + expected.len()
+            && grew_once
+            && before.iter().all(|(i, v)| merged.get(i) == Some(v))
+            && pairs.len() == expected.len()
+            && pairs.iter().all(|&(old, new)| expected.get(old) == merged.get(new) && !before.contains(new))
+    }
+}
+
+quickcheck! {
+    fn drain_range_matches_retain(ops: Vec<(bool, usize)>, start: usize, len: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let start = start % (arena.capacity() + 2);
+        let end = start + len % (arena.capacity() + 2);
+
+        let mut expected = arena.clone();
+        let mut removed = vec![];
+        expected.retain(|idx, v| {
+            let (slot, _) = idx.into_raw_parts();
+            if (start..end).contains(&slot) {
+                removed.push((idx, *v));
+                false
+            } else {
+                true
+            }
+        });
+
+        let capacity = arena.capacity();
+        let drained: Vec<_> = arena.drain_range(start..end).collect();
+        // Refill every free slot: the arena must not grow while doing so.
+        let free = arena.capacity() - arena.len();
+        let refilled = (0..free).all(|i| arena.try_insert(i).is_ok());
+
+        drained == removed
+            && arena.capacity() == capacity
+            && refilled
+            && arena.try_insert(0).is_err()
+            && expected.iter().all(|(i, v)| arena.get(i) == Some(v))
+    }
+}
+
+quickcheck! {
+    fn remove_where_matches_retain(ops: Vec<(bool, usize)>, modulus: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let modulus = modulus % 4 + 1;
+
+        let mut expected = arena.clone();
+        let mut rejected = vec![];
+        expected.retain(|idx, v| {
+            let keep = *v % modulus == 0;
+            if !keep {
+                rejected.push((idx, *v));
+            }
+            keep
+        });
+
+        let removed = arena.remove_where(|_, v| *v % modulus != 0);
+        removed == rejected
+            && arena.len() == expected.len()
+            && rejected.iter().all(|&(idx, _)| !arena.contains(idx))
+            && expected.iter().all(|(i, v)| arena.get(i) == Some(v))
+    }
+}
+
+quickcheck! {
+    fn every_operation_keeps_the_arena_valid(ops: Vec<(u8, usize)>, fifo: bool) -> bool {
+        let mut arena = if fifo {
+            Arena::builder().capacity(1).fifo_reuse().build()
+        } else {
+            Arena::with_capacity(1)
+        };
+        let mut indices = vec![];
+        for (op, value) in ops {
+            match op % 8 {
+                0..=2 => indices.push(arena.insert(value)),
+                3 | 4 if !indices.is_empty() => {
+                    let idx = indices.swap_remove(value % indices.len());
+                    arena.remove(idx);
+                }
+                5 => arena.retain(|_, v| *v % 3 != value % 3),
+                6 => {
+                    arena.drain_range(value % 8..value % 16).for_each(drop);
+                }
+                7 => {
+                    let _ = arena.truncate_capacity(value % 32);
+                }
+                _ => arena.reserve(value % 8),
+            }
+            if arena.validate().is_err() {
+                return false;
+            }
+        }
+        arena.clear();
+        arena.validate().is_ok()
+    }
+}
+
+quickcheck! {
+    fn iter_mut_split_at_partitions_remaining(ops: Vec<(bool, usize)>, skip: usize, slot: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let skip = skip % (arena.len() + 1);
+        let slot = slot % (arena.slot_count() + 2);
+
+        let expected: Vec<(Index, usize)> = arena.iter().skip(skip).map(|(i, v)| (i, *v)).collect();
+        let mut iter = arena.iter_mut();
+        for _ in 0..skip {
+            iter.next();
+        }
+        let (front, back) = iter.split_at(slot);
+        let lens = front.len() + back.len();
+        let front: Vec<_> = front.map(|(i, v)| (i, *v)).collect();
+        let back: Vec<_> = back.map(|(i, v)| (i, *v)).collect();
+
+        lens == expected.len()
+            && front.iter().all(|(i, _)| i.into_raw_parts().0 < slot)
+            && back.iter().all(|(i, _)| i.into_raw_parts().0 >= slot)
+            && front.into_iter().chain(back).collect::<Vec<_>>() == expected
+    }
+}
+
+quickcheck! {
+    fn clone_compact_keeps_elements_in_order(ops: Vec<(bool, usize)>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+
+        let (compact, remap) = arena.clone_compact();
+        let moved: Vec<_> = arena.iter().map(|(i, v)| (remap.get(i), v)).collect();
+        compact.len() == arena.len()
+            && remap.len() == arena.len()
+            && compact.slot_count() == arena.len()
+            && compact.iter().map(|(i, v)| (Some(i), v)).eq(moved)
+    }
+}
+
+quickcheck! {
+    fn split_off_slots_partitions_elements(ops: Vec<(bool, usize)>, at: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let at = at % (arena.slot_count() + 2);
+        let before = arena.clone();
+
+        let mut other = arena.split_off_slots(at);
+        let fresh = [arena.insert(0), other.insert(0)];
+        arena.validate().is_ok()
+            && other.validate().is_ok()
+            && arena.len() + other.len() == before.len() + 2
+            && before.iter().all(|(i, v)| {
+                let (slot, _) = i.into_raw_parts();
+                let (home, away) = if slot < at { (&arena, &other) } else { (&other, &arena) };
+                home.get(i) == Some(v) && !away.contains(i)
+            })
+            && fresh.iter().all(|i| !before.contains(*i))
+    }
+
+    fn swap_remove_packs_into_low_slots(values: Vec<usize>, removals: Vec<usize>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices: Vec<_> = values.iter().map(|&v| arena.insert(v)).collect();
+        let mut expected = values.clone();
+
+        for r in removals {
+            if indices.is_empty() {
+                break;
+            }
+            let k = r % indices.len();
+            let (value, moved) = arena.swap_remove(indices[k]).unwrap();
+            if value != expected[k] {
+                return false;
+            }
+            let last = indices.len() - 1;
+            match moved {
+                Some((from, to)) if k != last && from == indices[last] => {
+                    indices.swap_remove(k);
+                    expected.swap_remove(k);
+                    indices[k] = to;
+                }
+                None if k == last => {
+                    indices.pop();
+                    expected.pop();
+                }
+                _ => return false,
+            }
+        }
+
+        arena.validate().is_ok()
+            && arena.slot_count() == indices.len()
+            && indices.iter().zip(&expected).all(|(&i, v)| arena.get(i) == Some(v))
+    }
+
+    fn insertion_order_matches_a_log(ops: Vec<(bool, usize)>) -> bool {
+        use generational_arena::{InsertionOrder, ObservedArena};
+
+        let mut arena = ObservedArena::new(InsertionOrder::new());
+        let mut log = vec![];
+        for (delete, value) in ops {
+            if delete && !log.is_empty() {
+                let idx = log.remove(value % log.len());
+                arena.remove(idx);
+            } else {
+                log.push(arena.insert(value));
+            }
+        }
+
+        let forward: Vec<_> = arena.iter_insertion_order().map(|(i, _)| i).collect();
+        let mut backward: Vec<_> = arena.hooks().iter().rev().collect();
+        backward.reverse();
+        forward == log
+            && backward == log
+            && arena.hooks().len() == log.len()
+            && arena.last_inserted() == log.last().copied()
+            && arena.hooks().first_inserted() == log.first().copied()
+    }
+
+    fn soa_arena_matches_arena(ops: Vec<(bool, usize)>) -> bool {
+        use generational_arena::SoaArena;
+
+        let mut arena = Arena::new();
+        let mut soa_arena = SoaArena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                // With the `arena-id` feature, each index only works with the
+                // arena that made it.
+                let (a, b) = indices.swap_remove(value % indices.len());
+                if arena.remove(a) != soa_arena.remove(b) {
+                    return false;
+                }
+            } else {
+                let a = arena.insert(value);
+                let b = soa_arena.insert(value);
+                if a != b {
+                    return false;
+                }
+                indices.push((a, b));
+            }
+        }
+
+        arena.len() == soa_arena.len()
+            && arena.iter().eq(soa_arena.iter())
+            && arena.iter().map(|(_, v)| v).eq(soa_arena.values())
+    }
+
+    fn drain_filter_matches_remove_where(ops: Vec<(bool, usize)>, modulus: usize, take: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let modulus = modulus % 4 + 1;
+
+        let mut expected = arena.clone();
+        let removed = expected.remove_where(|_, v| *v % modulus != 0);
+        let drained: Vec<_> = arena.drain_filter(|_, v| *v % modulus != 0).collect();
+        if drained != removed || arena.len() != expected.len() {
+            return false;
+        }
+
+        // A partially consumed drain only removes what it yielded, and
+        // advances the generation at most once.
+        let mut partial = expected.clone();
+        let before = partial.generation();
+        let yielded: Vec<_> = partial.drain_filter(|_, _| true).take(take % 8).collect();
+        partial.len() + yielded.len() == expected.len()
+            && partial.generation() - before == if yielded.is_empty() { 0 } else { 1 }
+            && yielded.iter().all(|&(idx, _)| !partial.contains(idx))
+    }
+
+    fn occupied_bounds_track_every_operation(ops: Vec<(u8, usize)>) -> bool {
+        let mut arena = Arena::with_capacity(1);
+        for (op, value) in ops {
+            let slot = value % (arena.slot_count() + 1);
+            let existing = arena.get_unknown_gen(slot).map(|(_, idx)| idx);
+            match (op % 8, existing) {
+                (0..=2, _) => {
+                    arena.insert(value);
+                }
+                (3, Some(idx)) => {
+                    arena.remove(idx);
+                }
+                (4, Some(idx)) => {
+                    arena.swap_remove(idx);
+                }
+                (5, Some(idx)) => {
+                    arena.remove_reusable(idx);
+                }
+                (6, _) => {
+                    arena.drain_filter(|_, v| *v % 4 == value % 4).take(2).for_each(drop);
+                }
+                (7, _) => {
+                    let mut other = arena.split_off_slots(slot);
+                    if other.validate().is_err() {
+                        return false;
+                    }
+                    other.drain().for_each(drop);
+                }
+                _ => {}
+            }
+            if arena.validate().is_err() {
+                return false;
+            }
+
+            let expected: Vec<_> = (0..arena.slot_count())
+                .filter_map(|slot| arena.get_unknown_gen(slot).map(|(_, idx)| idx))
+                .collect();
+            let forward: Vec<_> = arena.iter().map(|(idx, _)| idx).collect();
+            let mut backward: Vec<_> = arena.iter_mut().rev().map(|(idx, _)| idx).collect();
+            backward.reverse();
+            let mut from = arena.iter_occupied_from(slot);
+            let from_slot: Vec<_> = from.by_ref().map(|(idx, _)| idx).collect();
+            if forward != expected
+                || backward != expected
+                || from.next_slot() != arena.slot_count()
+                || from_slot[..] != expected[expected.len() - from_slot.len()..]
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn occupied_slots_in_matches_filtered_iter(ops: Vec<(bool, usize)>, start: usize, len: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let start = start % (arena.slot_count() + 2);
+        let end = start + len % 8;
+
+        let expected: Vec<_> = arena
+            .iter()
+            .filter(|(i, _)| (start..end).contains(&i.into_raw_parts().0))
+            .collect();
+        let mut iter = arena.occupied_slots_in(start..end);
+        let found: Vec<_> = iter.by_ref().collect();
+        found == expected
+            && iter.next_slot() == end.min(arena.slot_count())
+            && arena.occupied_slots_in(start..=end).count()
+                == arena.iter().filter(|(i, _)| (start..=end).contains(&i.into_raw_parts().0)).count()
+    }
+
+    fn retain_rev_is_retain_in_reverse(ops: Vec<(bool, usize)>, modulus: usize) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let modulus = modulus % 4 + 1;
+
+        let mut forward = arena.clone();
+        let mut forward_seen = vec![];
+        forward.retain(|idx, v| {
+            forward_seen.push(idx);
+            *v % modulus == 0
+        });
+        let mut backward_seen = vec![];
+        arena.retain_rev(|idx, v| {
+            backward_seen.push(idx);
+            *v % modulus == 0
+        });
+        backward_seen.reverse();
+
+        forward_seen == backward_seen
+            && arena.iter().eq(forward.iter())
+            && arena.generation() == forward.generation()
+            && arena.validate().is_ok()
+    }
+
+    fn partition_keeps_every_element_and_index(ops: Vec<(bool, usize)>) -> bool {
+        let mut arena = Arena::new();
+        let mut indices = vec![];
+        for (delete, value) in ops {
+            if delete && !indices.is_empty() {
+                let idx = indices.swap_remove(value % indices.len());
+                arena.remove(idx);
+            } else {
+                indices.push(arena.insert(value));
+            }
+        }
+        let before = arena.clone();
+
+        let (even_indices, odd_indices) = arena.partition_indices(|_, v| v % 2 == 0);
+        let (mut even, mut odd) = arena.partition(|_, v| *v % 2 == 0);
+        let fresh = [even.insert(0), odd.insert(1)];
+        even.validate().is_ok()
+            && odd.validate().is_ok()
+            && even.len() + odd.len() == before.len() + 2
+            && before.iter().all(|(i, v)| {
+                let (home, away) = if v % 2 == 0 { (&even, &odd) } else { (&odd, &even) };
+                home.get(i) == Some(v) && !away.contains(i)
+            })
+            && even_indices.iter().all(|&i| even.contains(i))
+            && odd_indices.iter().all(|&i| odd.contains(i))
+            && even_indices.len() + odd_indices.len() == before.len()
+            && fresh.iter().all(|i| !before.contains(*i))
+    }
+}
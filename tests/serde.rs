@@ -1,7 +1,6 @@
 #![cfg(feature = "serde")]
 
 extern crate generational_arena;
-#[macro_use]
 extern crate serde;
 extern crate bincode;
 extern crate serde_test;
@@ -145,10 +144,11 @@ fn arena_from_iter_can_be_serialized_and_deserialized_without_panic() {
 
     let mut vec = vec![0usize];
     let x = vec.drain(..);
-    let mut arena_in = Arena::from_iter(x);
+    let arena_in = Arena::from_iter(x);
 
     let ser = serde_yaml::to_string(&arena_in).unwrap();
     let arena_out: Arena<usize> = serde_yaml::from_str(&ser).unwrap();
+    assert_eq!(arena_out.len(), arena_in.len());
 }
 
 /// Arena wrapper struct for comparing two arenas
@@ -158,7 +158,7 @@ fn arena_from_iter_can_be_serialized_and_deserialized_without_panic() {
 #[derive(Debug, Serialize, Deserialize)]
 struct ArenaCompare<T>(Arena<T>);
 
-impl<'a, T> PartialEq for ArenaCompare<T>
+impl<T> PartialEq for ArenaCompare<T>
 where
     T: PartialEq,
 {
@@ -186,3 +186,353 @@ where
     assert_ser_tokens(value, tokens);
     assert_de_tokens(value, tokens);
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VersionedArena(#[serde(with = "generational_arena::versioned")] Arena<u32>);
+
+impl PartialEq for VersionedArena {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.iter().eq(other.0.iter())
+    }
+}
+
+#[test]
+fn versioned_arena_has_tag_and_version_header() {
+    let mut arena = Arena::with_capacity(2);
+    arena.insert(7);
+
+    serde_test::assert_tokens(
+        &VersionedArena(arena),
+        &[
+            Token::NewtypeStruct {
+                name: "VersionedArena",
+            },
+            Token::Tuple { len: 3 },
+            Token::Str("generational-arena"),
+            Token::U32(2),
+            Token::Tuple { len: 2 },
+            Token::U64(0),
+            Token::Seq { len: Some(2) },
+            Token::Some,
+            Token::Tuple { len: 2 },
+            Token::U64(0),
+            Token::U32(7),
+            Token::TupleEnd,
+            Token::None,
+            Token::SeqEnd,
+            Token::TupleEnd,
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn versioned_arena_reads_version_1() {
+    let mut arena = Arena::with_capacity(2);
+    arena.insert(7);
+
+    serde_test::assert_de_tokens(
+        &VersionedArena(arena),
+        &[
+            Token::NewtypeStruct {
+                name: "VersionedArena",
+            },
+            Token::Tuple { len: 3 },
+            Token::Str("generational-arena"),
+            Token::U32(1),
+            Token::Seq { len: Some(2) },
+            Token::Some,
+            Token::Tuple { len: 2 },
+            Token::U64(0),
+            Token::U32(7),
+            Token::TupleEnd,
+            Token::None,
+            Token::SeqEnd,
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn versioned_arena_never_reissues_removed_indices() {
+    let mut arena = Arena::with_capacity(2);
+    arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(b);
+    let c = arena.insert(3);
+    arena.remove(c);
+
+    let bytes = bincode::serialize(&VersionedArena(arena)).unwrap();
+    let VersionedArena(mut arena) = bincode::deserialize(&bytes).unwrap();
+    let d = arena.insert(4);
+    assert_ne!(d, b);
+    assert_ne!(d, c);
+}
+
+#[test]
+fn versioned_arena_rejects_unknown_versions_and_tags() {
+    serde_test::assert_de_tokens_error::<VersionedArena>(
+        &[
+            Token::NewtypeStruct {
+                name: "VersionedArena",
+            },
+            Token::Tuple { len: 3 },
+            Token::Str("generational-arena"),
+            Token::U32(999),
+        ],
+        "invalid value: integer `999`, expected a known generational arena format version",
+    );
+    serde_test::assert_de_tokens_error::<VersionedArena>(
+        &[
+            Token::NewtypeStruct {
+                name: "VersionedArena",
+            },
+            Token::Tuple { len: 3 },
+            Token::Str("slotmap"),
+        ],
+        "invalid value: string \"slotmap\", expected the string \"generational-arena\"",
+    );
+}
+
+#[test]
+fn versioned_arena_round_trips_through_bincode() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(a);
+    let c = arena.insert(3);
+
+    let bytes = bincode::serialize(&VersionedArena(arena)).unwrap();
+    let VersionedArena(arena) = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(arena.get(a), None);
+    assert_eq!(arena[b], 2);
+    assert_eq!(arena[c], 3);
+}
+
+fn assert_round_trips(name: &str, mut arena: Arena<usize>) {
+    let bytes = bincode::serialize(&arena).expect("arena must be serialized");
+    let mut de_arena: Arena<usize> = bincode::deserialize(&bytes).expect("arena must be deserialized");
+    let yaml = serde_yaml::to_string(&arena).expect("arena must be serialized");
+    let mut yaml_arena: Arena<usize> = serde_yaml::from_str(&yaml).expect("arena must be deserialized");
+
+    for other in &mut [&mut de_arena, &mut yaml_arena] {
+        assert!(arena.iter().eq(other.iter()), "{}: contents differ", name);
+        assert_eq!(arena.len(), other.len(), "{}: len differs", name);
+        assert_eq!(arena.capacity(), other.capacity(), "{}: capacity differs", name);
+        assert_eq!(other.validate(), Ok(()), "{}: arena is corrupt", name);
+
+        // Every free slot can be filled without growing, and then the arena
+        // keeps growing as usual.
+        let free = other.capacity() - other.len();
+        let mut other = other.clone();
+        for i in 0..free {
+            assert!(other.try_insert(i).is_ok(), "{}: free list is too short", name);
+        }
+        assert!(other.try_insert(0).is_err(), "{}: free list is too long", name);
+        let idx = other.insert(42);
+        assert_eq!(other[idx], 42);
+    }
+
+    // The original can still be used afterwards, too.
+    let idx = arena.insert(1);
+    assert_eq!(arena[idx], 1);
+}
+
+#[test]
+fn arenas_built_every_way_round_trip() {
+    assert_round_trips("new", Arena::new());
+    assert_round_trips("with_capacity", Arena::with_capacity(17));
+
+    let mut arena = Arena::with_capacity(3);
+    arena.extend(0..10);
+    assert_round_trips("extend", arena);
+
+    assert_round_trips("from_iter exact", Arena::from_iter(0..5));
+    assert_round_trips("from_iter filtered", Arena::from_iter((0..50).filter(|x| x % 3 == 0)));
+    assert_round_trips("from_iter single", Arena::from_iter(vec![0usize].drain(..)));
+
+    let mut arena = Arena::from_iter(0..8);
+    let indices: Vec<_> = arena.iter().map(|(idx, _)| idx).collect();
+    for idx in indices.iter().step_by(2) {
+        arena.remove(*idx);
+    }
+    assert_round_trips("sparse", arena);
+
+    let mut arena = Arena::from_iter(0..8);
+    arena.clear();
+    assert_round_trips("clear", arena);
+
+    let mut arena = Arena::from_iter(0..8);
+    arena.drain();
+    assert_round_trips("drain", arena);
+
+    let mut arena = Arena::from_iter(0..8);
+    arena.retain(|_, x| *x > 5);
+    assert_round_trips("retain", arena);
+}
+
+#[test]
+fn huge_size_hint_does_not_preallocate() {
+    // A sequence claiming to have `usize::MAX` elements but which is actually
+    // empty must not cause an enormous allocation.
+    serde_test::assert_de_tokens(
+        &ArenaCompare(Arena::<u32>::new()),
+        &[
+            Token::NewtypeStruct {
+                name: "ArenaCompare",
+            },
+            Token::Seq {
+                len: Some(usize::MAX),
+            },
+            Token::SeqEnd,
+        ],
+    );
+}
+
+generational_arena::arena_index! {
+    struct NodeId;
+}
+
+#[test]
+fn arena_index_newtypes_serialize_like_index() {
+    let id = NodeId::from_raw_parts(3, 7);
+    let index = Index::from_raw_parts(3, 7);
+    assert_eq!(bincode::serialize(&id).unwrap(), bincode::serialize(&index).unwrap());
+
+    let bytes = bincode::serialize(&id).unwrap();
+    let back: NodeId = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(back, id);
+}
+
+#[test]
+fn deserialize_in_place_reuses_the_allocation() {
+    let mut saved = Arena::new();
+    let a = saved.insert(1);
+    let b = saved.insert(2);
+    saved.remove(a);
+    let yaml = serde_yaml::to_string(&saved).expect("arena must be serialized");
+
+    let mut place: Arena<usize> = Arena::with_capacity(100);
+    place.extend(0..50);
+    let ptr = place.get_unknown_gen(1).unwrap().0 as *const usize;
+    Arena::deserialize_in_place(serde_yaml::Deserializer::from_str(&yaml), &mut place)
+        .expect("arena must be deserialized");
+
+    assert_eq!(place.capacity(), 100);
+    assert_eq!(place.len(), 1);
+    assert_eq!(place.get(a), None);
+    assert_eq!(place[b], 2);
+    assert_eq!(&place[b] as *const usize, ptr);
+    assert_eq!(place.validate(), Ok(()));
+
+    let bad = serde_yaml::Deserializer::from_str("- [0, 1]\n- oops\n");
+    assert!(Arena::deserialize_in_place(bad, &mut place).is_err());
+    assert!(place.is_empty());
+    assert_eq!(place.validate(), Ok(()));
+}
+
+#[test]
+fn deserialize_with_migrates_each_element() {
+    use generational_arena::MigrateArena;
+    use serde::de::DeserializeSeed;
+
+    let mut old = Arena::new();
+    let a = old.insert(1u32);
+    let b = old.insert(2);
+    let c = old.insert(3);
+    old.remove(b);
+    let yaml = serde_yaml::to_string(&old).expect("arena must be serialized");
+
+    let mut calls = 0;
+    let de = serde_yaml::Deserializer::from_str(&yaml);
+    let new: Arena<String> = MigrateArena::new(|n: u32| {
+        calls += 1;
+        n.to_string()
+    })
+    .deserialize(de)
+    .expect("arena must be deserialized");
+
+    assert_eq!(calls, 2);
+    assert_eq!(new.len(), 2);
+    assert_eq!(new[a], "1");
+    assert_eq!(new.get(b), None);
+    assert_eq!(new[c], "3");
+    assert_eq!(new.validate(), Ok(()));
+
+    let de = serde_yaml::Deserializer::from_str(&yaml);
+    let doubled = Arena::deserialize_with(de, |n: u32| n * 2).expect("arena must be deserialized");
+    assert_eq!(doubled[c], 6);
+}
+
+#[test]
+fn option_index_serializes_like_option() {
+    use generational_arena::OptionIndex;
+
+    let idx = Index::from_raw_parts(1, 2);
+    for opt in [Some(idx), None].iter().copied() {
+        let bytes = bincode::serialize(&OptionIndex::from(opt)).unwrap();
+        assert_eq!(bytes, bincode::serialize(&opt).unwrap());
+        let back: OptionIndex = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back.get(), opt);
+    }
+}
+
+#[test]
+fn deserialized_arena_borrows_from_input() {
+    use std::borrow::Cow;
+
+    fn within(buf: &[u8], part: &[u8]) -> bool {
+        let range = buf.as_ptr_range();
+        range.contains(&part.as_ptr()) && part.as_ptr_range().end <= range.end
+    }
+
+    let mut arena = Arena::new();
+    let a = arena.insert("apple");
+    let b = arena.insert("banana");
+    arena.remove(a);
+    let c = arena.insert("cherry");
+
+    let bytes = bincode::serialize(&arena).expect("arena must be serialized");
+    let strs: Arena<&str> = bincode::deserialize(&bytes).expect("arena must be deserialized");
+    assert_eq!(strs[b], "banana");
+    assert!(strs.iter().all(|(_, s)| within(&bytes, s.as_bytes())));
+
+    let slices: Arena<&[u8]> = bincode::deserialize(&bytes).expect("arena must be deserialized");
+    assert_eq!(slices[c], b"cherry");
+    assert!(slices.iter().all(|(_, s)| within(&bytes, s)));
+
+    let mut de = bincode::Deserializer::from_slice(&bytes, bincode_options());
+    let cows = Arena::deserialize_cow_str(&mut de).expect("arena must be deserialized");
+    assert!(matches!(cows[b], Cow::Borrowed("banana")));
+    assert!(cows.iter().all(|(_, s)| within(&bytes, s.as_bytes())));
+
+    let mut de = bincode::Deserializer::from_slice(&bytes, bincode_options());
+    let cows = Arena::deserialize_cow_bytes(&mut de).expect("arena must be deserialized");
+    assert!(matches!(cows[c], Cow::Borrowed(b"cherry")));
+    assert_eq!(cows.get(a), None);
+
+    // Formats that can't lend out their strings can still be read, by
+    // copying.
+    let yaml = serde_yaml::to_string(&arena).expect("arena must be serialized");
+    let de = serde_yaml::Deserializer::from_str(&yaml);
+    assert!(Arena::<&str>::deserialize(de).is_err());
+    let de = serde_yaml::Deserializer::from_str(&yaml);
+    let cows = Arena::deserialize_cow_str(de).expect("arena must be deserialized");
+    assert!(matches!(cows[b], Cow::Owned(ref s) if s == "banana"));
+    assert_eq!(cows.get(a), None);
+
+    let byte_arena: Arena<&[u8]> = arena.iter().map(|(_, s)| s.as_bytes()).collect();
+    let yaml = serde_yaml::to_string(&byte_arena).expect("arena must be serialized");
+    let de = serde_yaml::Deserializer::from_str(&yaml);
+    let cows = Arena::deserialize_cow_bytes(de).expect("arena must be deserialized");
+    assert_eq!(cows.len(), 2);
+    assert!(byte_arena.iter().all(|(i, b)| cows[i] == *b));
+}
+
+// The options `bincode::serialize` and `bincode::deserialize` use.
+fn bincode_options() -> impl bincode::Options {
+    use bincode::Options;
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
@@ -77,6 +77,19 @@ fn insert_with_indicies_match() {
     assert_eq!(arena[c].1, c);
 }
 
+#[test]
+fn insert_ref_and_alloc_return_the_new_element() {
+    let mut arena = Arena::with_capacity(1);
+    let (a, value) = arena.insert_ref(1);
+    *value += 10;
+    // Growing the arena still hands back the right element.
+    let (b, value) = arena.alloc();
+    *value += 20;
+    assert_eq!(arena[a], 11);
+    assert_eq!(arena[b], 20);
+    assert_eq!(arena.len(), 2);
+}
+
 #[test]
 fn try_insert_with_indicies_match() {
     let mut arena = Arena::with_capacity(3);
@@ -95,8 +108,54 @@ fn try_insert_with_indicies_match() {
 fn capacity_and_reserve() {
     let mut arena: Arena<usize> = Arena::with_capacity(42);
     assert_eq!(arena.capacity(), 42);
-    arena.reserve(10);
+    arena.reserve_exact(10);
+    assert_eq!(arena.capacity(), 42);
+    arena.extend(0..42);
+    arena.reserve_exact(10);
     assert_eq!(arena.capacity(), 52);
+    arena.extend(0..10);
+    arena.reserve(10);
+    assert_eq!(arena.capacity(), 104);
+}
+
+#[test]
+fn new_does_not_allocate() {
+    const EMPTY: Arena<usize> = Arena::new();
+
+    let mut arena = EMPTY;
+    assert_eq!(arena.capacity(), 0);
+    assert!(arena.get(generational_arena::Index::from_raw_parts(0, 0)).is_none());
+
+    let idx = arena.insert(7);
+    assert!(arena.capacity() > 0);
+    assert_eq!(arena[idx], 7);
+    assert!(arena.validate().is_ok());
+}
+
+#[test]
+fn capacity_is_materialized_lazily() {
+    let mut arena = Arena::with_capacity(1 << 20);
+    assert_eq!(arena.capacity(), 1 << 20);
+    assert_eq!(arena.slot_count(), 0);
+    assert_eq!(arena.spare_capacity(), 1 << 20);
+
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(a);
+    assert_eq!(arena.slot_count(), 2);
+    assert_eq!(arena.spare_capacity(), (1 << 20) - 1);
+
+    // The freed slot is reused before any never-used one.
+    let c = arena.insert(3);
+    assert_eq!(c.into_raw_parts().0, a.into_raw_parts().0);
+    assert_eq!(arena.slot_count(), 2);
+
+    // Clearing and cloning both keep the capacity.
+    assert_eq!(arena.clone().capacity(), 1 << 20);
+    arena.clear();
+    assert_eq!(arena.capacity(), 1 << 20);
+    assert_eq!(arena.slot_count(), 0);
+    assert!(!arena.contains(b));
 }
 
 #[test]
@@ -193,9 +252,12 @@ fn index_deleted_item() {
     let mut arena = Arena::new();
     let idx = arena.insert(42);
     arena.remove(idx);
-    arena[idx];
+    let _ = &arena[idx];
 }
 
+// With the `arena-id` feature, these panic instead; see
+// `index_from_other_arena_panics`.
+#[cfg(not(feature = "arena-id"))]
 #[test]
 fn out_of_bounds_get_with_index_from_other_arena() {
     let mut arena1 = Arena::with_capacity(1);
@@ -205,6 +267,9 @@ fn out_of_bounds_get_with_index_from_other_arena() {
     assert!(arena2.get(idx).is_none());
 }
 
+// With the `arena-id` feature, these panic instead; see
+// `index_from_other_arena_panics`.
+#[cfg(not(feature = "arena-id"))]
 #[test]
 fn out_of_bounds_remove_with_index_from_other_arena() {
     let mut arena1 = Arena::with_capacity(1);
@@ -214,6 +279,9 @@ fn out_of_bounds_remove_with_index_from_other_arena() {
     assert!(arena2.remove(idx).is_none());
 }
 
+// With the `arena-id` feature, these panic instead; see
+// `index_from_other_arena_panics`.
+#[cfg(not(feature = "arena-id"))]
 #[test]
 fn out_of_bounds_get2_mut_with_index_from_other_arena() {
     let mut arena1 = Arena::with_capacity(1);
@@ -239,12 +307,14 @@ fn drain() {
     assert!(arena.get(idx_1).is_none());
     assert!(arena.get(idx_2).is_none());
 
-    assert_eq!(arena.capacity(), 0);
+    // Like `Vec::drain`, draining keeps the allocation.
+    assert_eq!(arena.capacity(), 4);
+    assert_eq!(arena.slot_count(), 0);
     assert_eq!(arena.len(), 0);
 
     let idx_3 = arena.insert("a");
     assert_ne!(idx_1, idx_3);
-    assert_eq!(arena.capacity(), 1);
+    assert_eq!(arena.capacity(), 4);
     assert_eq!(arena.len(), 1);
 
     // If there are no elements, do not increment generation.
@@ -328,3 +398,1577 @@ fn retain() {
     assert_eq!(arena.len(), 1);
     assert!(!arena.contains(index));
 }
+
+#[test]
+fn index_from_str_rejects_malformed_input() {
+    use generational_arena::Index;
+    for s in &[
+        "", "12", "v3", "12v", "12v3v4", "-1v3", "12x3", "12 v3", "+1v2", "1v+2", "+12v+3",
+    ] {
+        assert!(s.parse::<Index>().is_err(), "{:?} should not parse", s);
+    }
+    assert_eq!("12v3".parse::<Index>(), Ok(Index::from_raw_parts(12, 3)));
+}
+
+#[test]
+fn raw_index_round_trip_and_overflow() {
+    use generational_arena::{Index, RawIndex};
+    use std::convert::TryFrom;
+
+    let idx = Index::from_raw_parts(7, 9);
+    let raw = RawIndex::try_from(idx).unwrap();
+    assert_eq!(raw.slot(), 7);
+    assert_eq!(raw.generation(), 9);
+    assert_eq!(raw.to_bits(), 7 | (9 << 32));
+    assert_eq!(Index::from(raw), idx);
+
+    let too_old = Index::from_raw_parts(0, u64::from(u32::MAX) + 1);
+    assert_eq!(RawIndex::try_from(too_old).unwrap_err().index(), too_old);
+}
+
+#[test]
+fn index_bits_round_trip() {
+    use generational_arena::Index;
+
+    let idx = Index::from_raw_parts(7, 9);
+    assert_eq!(idx.to_bits(), Ok(7 | (9 << 32)));
+    assert_eq!(Index::from_bits(idx.to_bits().unwrap()), Some(idx));
+    assert_eq!(
+        Index::from_bits(u64::MAX),
+        Some(Index::from_raw_parts(u32::MAX as usize, u64::from(u32::MAX)))
+    );
+}
+
+#[test]
+fn index_to_bits_fails_when_generation_too_large() {
+    use generational_arena::Index;
+
+    let too_old = Index::from_raw_parts(0, u64::from(u32::MAX) + 1);
+    assert_eq!(too_old.to_bits().unwrap_err().index(), too_old);
+}
+
+#[test]
+fn iterators_are_send_and_sync() {
+    fn assert_send<T: Send>(_: &T) {}
+    fn assert_sync<T: Sync>(_: &T) {}
+
+    let mut arena = Arena::<usize>::new();
+    {
+        let iter = arena.iter();
+        assert_send(&iter);
+        assert_sync(&iter);
+    }
+    {
+        let iter_mut = arena.iter_mut();
+        assert_send(&iter_mut);
+        assert_sync(&iter_mut);
+    }
+    {
+        let drain = arena.drain();
+        assert_send(&drain);
+        assert_sync(&drain);
+    }
+    let into_iter = arena.into_iter();
+    assert_send(&into_iter);
+    assert_sync(&into_iter);
+}
+
+#[test]
+fn iter_mut_across_scoped_threads() {
+    let mut arena: Arena<usize> = (0..10).collect();
+    let mut iter = arena.iter_mut();
+    let (a, b) = (iter.next().unwrap(), iter.next_back().unwrap());
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            for (_, value) in iter {
+                *value += 100;
+            }
+        });
+    });
+    assert_eq!((a.0.into_raw_parts().0, *a.1), (0, 0));
+    assert_eq!((b.0.into_raw_parts().0, *b.1), (9, 9));
+    assert!(arena.iter().skip(1).take(8).all(|(_, v)| *v >= 100));
+}
+
+#[test]
+fn rc_arena_strong_handles_block_removal() {
+    use generational_arena::{InUse, RcArena};
+
+    let mut arena = RcArena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+
+    let strong_a = arena.get_strong(a).unwrap();
+    let strong_a2 = strong_a.clone();
+    assert_eq!(arena.strong_count(a), Some(2));
+    assert_eq!(arena.strong_count(b), Some(0));
+
+    assert_eq!(arena.remove(a), Err(InUse));
+    assert_eq!(arena.remove(b), Ok(Some(2)));
+    assert_eq!(arena.remove(b), Ok(None));
+    assert!(arena.get_strong(b).is_none());
+
+    drop(strong_a);
+    assert_eq!(arena.remove(a), Err(InUse));
+    drop(strong_a2);
+    assert_eq!(arena.remove(a), Ok(Some(1)));
+    assert!(arena.is_empty());
+}
+
+#[test]
+fn retain_until_stops_early() {
+    use std::ops::ControlFlow;
+
+    let mut arena = Arena::with_capacity(6);
+    let indices: Vec<_> = (0..6).map(|i| arena.insert(i)).collect();
+
+    let mut visited = vec![];
+    arena.retain_until(|idx, n| {
+        visited.push(idx);
+        match *n {
+            3 => ControlFlow::Break(()),
+            n => ControlFlow::Continue(n % 2 == 0),
+        }
+    });
+
+    assert_eq!(visited, &indices[..4]);
+    assert!(arena.contains(indices[0]));
+    assert!(!arena.contains(indices[1]));
+    assert!(arena.contains(indices[2]));
+    assert!(arena.contains(indices[3]));
+    assert!(arena.contains(indices[4]));
+    assert!(arena.contains(indices[5]));
+}
+
+#[test]
+fn pinned_values_keep_their_address() {
+    use std::marker::PhantomPinned;
+
+    struct NotUnpin(usize, PhantomPinned);
+
+    let mut arena = Arena::with_capacity(1);
+    let first = arena.insert_pinned(NotUnpin(0, PhantomPinned));
+    let addr = &*arena.get_pin(first).unwrap() as *const NotUnpin;
+
+    let others: Vec<_> = (1..64)
+        .map(|i| arena.insert_pinned(NotUnpin(i, PhantomPinned)))
+        .collect();
+    for idx in others.iter().step_by(2) {
+        arena.remove(*idx);
+    }
+
+    let pinned = arena.get_pin_mut(first).unwrap();
+    assert_eq!(&*pinned as *const NotUnpin, addr);
+    assert_eq!(pinned.0, 0);
+    assert!(arena.get_pin(others[0]).is_none());
+}
+
+#[test]
+fn fifo_reuse_reuses_oldest_free_slot() {
+    use generational_arena::ReusePolicy;
+
+    let slot = |idx: generational_arena::Index| idx.into_raw_parts().0;
+
+    for &(reuse, expected) in &[(ReusePolicy::Lifo, [2, 1, 0]), (ReusePolicy::Fifo, [0, 1, 2])] {
+        let mut arena = Arena::builder().capacity(3).reuse(reuse).build();
+        let indices: Vec<_> = (0..3).map(|i| arena.insert(i)).collect();
+        for idx in &indices {
+            arena.remove(*idx);
+        }
+        let reused: Vec<_> = (0..3).map(|i| slot(arena.insert(i))).collect();
+        assert_eq!(reused, expected, "{:?}", reuse);
+
+        // Growing past capacity and emptying out again keeps working.
+        arena.extend(3..10);
+        arena.retain(|_, _| false);
+        arena.extend(0..10);
+        assert_eq!(arena.len(), 10);
+    }
+}
+
+#[test]
+fn reserve_zero_and_clear_after_drain() {
+    let mut arena = Arena::with_capacity(1);
+    arena.insert(0);
+    arena.reserve(0);
+    let idx = arena.insert(1);
+    assert_eq!(arena[idx], 1);
+
+    let mut arena = Arena::with_capacity(1);
+    arena.insert(0);
+    arena.drain();
+    arena.clear();
+    let idx = arena.insert(2);
+    assert_eq!(arena[idx], 2);
+}
+
+#[test]
+fn drain_indices_drops_values_in_place() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct CountDrops(Rc<Cell<usize>>);
+    impl Drop for CountDrops {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let drops = Rc::new(Cell::new(0));
+    let mut arena = Arena::with_capacity(4);
+    let indices: Vec<_> = (0..4).map(|_| arena.insert(CountDrops(drops.clone()))).collect();
+    arena.remove(indices[1]);
+    assert_eq!(drops.get(), 1);
+
+    {
+        let mut drain = arena.drain_indices();
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some(indices[0]));
+        assert_eq!(drain.next_back(), Some(indices[3]));
+        assert_eq!(drops.get(), 3);
+        // The rest is removed when the iterator is dropped.
+    }
+    assert_eq!(drops.get(), 4);
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), 4);
+    assert!(indices.iter().all(|idx| !arena.contains(*idx)));
+
+    // The freed slots are all reusable, with fresh generations.
+    let new: Vec<_> = (0..4).map(|_| arena.insert(CountDrops(drops.clone()))).collect();
+    assert_eq!(arena.capacity(), 4);
+    assert!(new.iter().all(|idx| !indices.contains(idx)));
+}
+
+#[cfg(feature = "arena-id")]
+#[test]
+#[should_panic(expected = "different arena")]
+fn index_from_other_arena_panics() {
+    let mut a = Arena::new();
+    let mut b = Arena::new();
+    let idx = a.insert(1);
+    b.insert(2);
+    // Same slot and generation, but a different arena.
+    b.get(idx);
+}
+
+#[cfg(feature = "arena-id")]
+#[test]
+#[should_panic(expected = "different arena")]
+fn try_get2_mut_checks_both_arenas() {
+    let mut a = Arena::new();
+    let mut b = Arena::new();
+    let idx = a.insert(1);
+    b.insert(2);
+    let (slot, generation) = idx.into_raw_parts();
+    let untagged = generational_arena::Index::from_raw_parts(slot, generation);
+    // Equal to `idx`, since equality ignores the arena, but `idx` is foreign.
+    b.try_get2_mut(untagged, idx);
+}
+
+#[cfg(feature = "arena-id")]
+#[test]
+fn untagged_and_cloned_indices_are_accepted() {
+    let mut a = Arena::new();
+    let idx = a.insert(1);
+    let (slot, generation) = idx.into_raw_parts();
+    let untagged = generational_arena::Index::from_raw_parts(slot, generation);
+    assert_eq!(untagged, idx);
+    assert_eq!(a.get(untagged), Some(&1));
+
+    let mut b = a.clone();
+    assert_eq!(b.remove(idx), Some(1));
+}
+
+#[test]
+fn split_iter_mut_at() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let c = arena.insert(3);
+    let d = arena.insert(4);
+    arena.remove(c);
+
+    let (first, mut rest) = arena.split_iter_mut_at(a).unwrap();
+    assert_eq!(*first, 1);
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest.next(), Some((b, &mut 2)));
+    assert_eq!(rest.next_back(), Some((d, &mut 4)));
+    assert_eq!(rest.next(), None);
+
+    let (last, rest) = arena.split_iter_mut_at(d).unwrap();
+    assert_eq!(*last, 4);
+    assert_eq!(rest.len(), 0);
+
+    assert!(arena.split_iter_mut_at(c).is_none());
+}
+
+#[test]
+fn iter_occupied_from_resumes_across_mutation() {
+    let mut arena = Arena::with_capacity(6);
+    let indices: Vec<_> = (0..6).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[1]);
+
+    let mut iter = arena.iter_occupied_from(0);
+    assert_eq!(iter.next(), Some((indices[0], &0)));
+    assert_eq!(iter.next(), Some((indices[2], &2)));
+    let slot = iter.next_slot();
+    assert_eq!(slot, 3);
+
+    // Changes to the already-visited prefix don't affect the rest.
+    arena.remove(indices[0]);
+    arena.remove(indices[4]);
+    let rest: Vec<_> = arena.iter_occupied_from(slot).map(|(_, v)| *v).collect();
+    assert_eq!(rest, [3, 5]);
+
+    let mut iter = arena.iter_occupied_from(100);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_slot(), arena.capacity());
+}
+
+#[test]
+fn small_arena_spills_to_heap_keeping_indices() {
+    use generational_arena::SmallArena;
+
+    let mut arena = SmallArena::<usize, 3>::new();
+    assert_eq!(arena.capacity(), 3);
+    let a = arena.insert(0);
+    let b = arena.insert(1);
+    assert_eq!(arena.remove(a), Some(0));
+    assert_eq!(arena.remove(a), None);
+    let c = arena.insert(2);
+    assert_ne!(a, c);
+    let d = arena.insert(3);
+    assert!(arena.is_inline());
+    assert_eq!(arena.len(), 3);
+
+    let e = arena.insert(4);
+    assert!(!arena.is_inline());
+    assert!(!arena.contains(a));
+    assert_eq!(
+        [arena[b], arena[c], arena[d], arena[e]],
+        [1, 2, 3, 4]
+    );
+    assert_eq!(arena.iter().count(), 4);
+
+    let arena = arena.into_arena();
+    assert_eq!(arena.len(), 4);
+    assert!(!arena.contains(a));
+    assert_eq!(arena[e], 4);
+}
+
+#[test]
+fn observed_arena_reports_every_insert_and_remove() {
+    use generational_arena::{ArenaHooks, Index, ObservedArena};
+    use std::collections::BTreeMap;
+
+    #[derive(Default)]
+    struct Mirror(BTreeMap<Index, usize>);
+
+    impl ArenaHooks<usize> for Mirror {
+        fn on_insert(&mut self, i: Index, value: &usize) {
+            assert!(self.0.insert(i, *value).is_none());
+        }
+
+        fn on_remove(&mut self, i: Index, value: &usize) {
+            assert_eq!(self.0.remove(&i), Some(*value));
+        }
+    }
+
+    fn in_sync(arena: &ObservedArena<usize, Mirror>) -> bool {
+        arena.iter().map(|(i, v)| (i, *v)).eq(arena.hooks().0.iter().map(|(i, v)| (*i, *v)))
+    }
+
+    let mut arena = ObservedArena::<usize, Mirror>::default();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    assert!(in_sync(&arena));
+
+    assert_eq!(arena.remove(indices[3]), Some(3));
+    assert_eq!(arena.remove(indices[3]), None);
+    assert!(in_sync(&arena));
+
+    arena.retain(|_, v| *v % 2 == 0);
+    assert!(in_sync(&arena));
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert!(in_sync(&arena));
+}
+
+#[test]
+#[should_panic(expected = "slot 1 requested more than once")]
+fn get_unknown_gen_many_mut_rejects_duplicate_slots() {
+    let mut arena = Arena::new();
+    arena.extend(0..3);
+    arena.get_unknown_gen_many_mut(&[1, 0, 1]);
+}
+
+#[test]
+#[should_panic(expected = "slot 2 requested more than once")]
+fn get2_unknown_gen_mut_rejects_the_same_slot() {
+    let mut arena = Arena::new();
+    arena.extend(0..3);
+    arena.get2_unknown_gen_mut(2, 2);
+}
+
+#[test]
+fn unknown_gen_methods_skip_free_slots() {
+    let mut arena = Arena::new();
+    let a = arena.insert('a');
+    let b = arena.insert('b');
+    arena.remove(a);
+
+    assert!(!arena.contains_unknown_gen(0));
+    assert!(arena.contains_unknown_gen(1));
+    assert!(!arena.contains_unknown_gen(2));
+
+    let (first, second) = arena.get2_unknown_gen_mut(0, 1);
+    assert!(first.is_none());
+    assert_eq!(second, Some((&mut 'b', b)));
+
+    assert_eq!(arena.remove_unknown_gen(0), None);
+    assert_eq!(arena.remove_unknown_gen(1), Some(('b', b)));
+    assert!(arena.is_empty());
+}
+
+#[test]
+fn truncate_capacity() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    for &idx in &indices[3..] {
+        arena.remove(idx);
+    }
+
+    assert_eq!(arena.truncate_capacity(2).unwrap_err().index(), indices[2]);
+    assert_eq!(arena.capacity(), 8);
+
+    arena.truncate_capacity(100).unwrap();
+    assert_eq!(arena.capacity(), 8);
+
+    arena.truncate_capacity(4).unwrap();
+    assert_eq!(arena.capacity(), 4);
+    assert_eq!(arena.len(), 3);
+
+    // The single remaining free slot is reused, then the arena grows.
+    let new = arena.try_insert(10).unwrap();
+    assert_eq!(new.into_raw_parts().0, 3);
+    assert!(!indices.contains(&new));
+    assert!(arena.try_insert(11).is_err());
+    arena.insert(11);
+    assert_eq!(arena.len(), 5);
+
+    let mut empty = Arena::<usize>::new();
+    empty.truncate_capacity(0).unwrap();
+    assert_eq!(empty.capacity(), 0);
+    empty.insert(1);
+    assert_eq!(empty.len(), 1);
+}
+
+#[test]
+fn removals_counts_every_kind_of_removal() {
+    let mut arena = Arena::new();
+    arena.extend(0..4);
+    assert_eq!(arena.drain().count(), 4);
+    assert_eq!(arena.removals(), 4);
+
+    arena.extend(0..3);
+    arena.drain_indices().next();
+    assert_eq!(arena.removals(), 7);
+
+    arena.extend(0..2);
+    arena.clear();
+    assert_eq!(arena.removals(), 9);
+
+    // Removing something that isn't there doesn't count.
+    let idx = arena.insert(0);
+    arena.remove(idx);
+    arena.remove(idx);
+    assert_eq!(arena.removals(), 10);
+}
+
+#[test]
+fn fingerprint_writes_are_platform_independent() {
+    use std::hash::Hasher;
+
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    let mut arena = Arena::new();
+    let a = arena.insert(7u32);
+    arena.insert(9u32);
+    arena.remove(a);
+
+    let mut recorder = Recorder::default();
+    arena.fingerprint(&mut recorder);
+
+    let mut expected = vec![];
+    expected.extend_from_slice(&1u64.to_ne_bytes());
+    expected.extend_from_slice(&0u64.to_ne_bytes());
+    expected.extend_from_slice(&9u32.to_ne_bytes());
+    expected.extend_from_slice(&1u64.to_ne_bytes());
+    assert_eq!(recorder.0, expected);
+}
+
+#[test]
+fn lookup_errors_describe_the_slot() {
+    use generational_arena::SlotState;
+
+    let mut arena = Arena::with_capacity(4);
+    let idx = arena.insert(1);
+    arena.insert(2);
+    arena.remove(idx);
+
+    let err = arena.try_get(idx).unwrap_err();
+    assert_eq!(err.index(), idx);
+    assert_eq!(err.state(), SlotState::Free);
+    assert_eq!(err.to_string(), "no element at index 0v0: slot 0 is free");
+
+    let far = generational_arena::Index::from_raw_parts(5, 0);
+    let err = arena.try_get_mut(far).unwrap_err();
+    assert_eq!(err.state(), SlotState::OutOfBounds { slot_count: 2 });
+    assert_eq!(
+        err.to_string(),
+        "no element at index 5v0: the arena only has 2 slots"
+    );
+}
+
+#[test]
+#[should_panic(expected = "no element at index 0v0: slot 0 holds generation 1")]
+fn index_panic_message_names_slot_and_generation() {
+    let mut arena = Arena::new();
+    let idx = arena.insert(1);
+    arena.remove(idx);
+    arena.insert(2);
+    arena[idx] += 1;
+}
+
+#[test]
+fn generation_start_keeps_shards_apart() {
+    let mut a = Arena::with_generation_start(0);
+    let mut b = Arena::builder().capacity(1).generation_start(100).build();
+
+    let mut from_a = vec![];
+    let mut from_b = vec![];
+    for i in 0..10 {
+        let ia = a.insert(i);
+        let ib = b.insert(i);
+        from_a.push(ia);
+        from_b.push(ib);
+        if i % 2 == 0 {
+            a.remove(ia);
+            b.remove(ib);
+        }
+    }
+    assert!(from_b.iter().all(|idx| idx.into_raw_parts().1 >= 100));
+    assert!(from_a.iter().all(|idx| !from_b.contains(idx)));
+}
+
+#[test]
+fn snapshot_skips_removed_and_ignores_inserted() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+
+    let mut snapshot = arena.iter_snapshot();
+    assert_eq!(snapshot.len(), 4);
+    assert_eq!(snapshot.next_in_mut(&mut arena), Some((indices[0], &mut 0)));
+
+    arena.remove(indices[1]);
+    arena.remove(indices[2]);
+    // Reuses slot 2, but with a new generation.
+    arena.insert(99);
+
+    let (idx, value) = snapshot.next_in_mut(&mut arena).unwrap();
+    assert_eq!(idx, indices[3]);
+    *value += 1;
+    assert_eq!(snapshot.next_in(&arena), None);
+    assert_eq!(arena[indices[3]], 4);
+
+    // A new snapshot sees the element inserted since.
+    assert_eq!(arena.iter_snapshot().count(), 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn thread_local_arenas_route_reads_to_the_owning_shard() {
+    use generational_arena::ThreadLocalArena;
+
+    let mut shards = ThreadLocalArena::shards(3);
+    let router = shards[0].router();
+    assert_eq!(router.shard_count(), 3);
+
+    let a = shards[1].insert("a");
+    let b = shards[2].insert("b");
+    // Same slot in different shards, but distinct handles.
+    assert_eq!(a.into_raw_parts().0, b.into_raw_parts().0);
+    assert_ne!(a, b);
+    assert_eq!((router.shard_of(a), router.shard_of(b)), (1, 2));
+    assert_eq!(router.get_with(a, |s| *s), Some("a"));
+    assert_eq!(router.get_with(b, |s| *s), Some("b"));
+
+    assert_eq!(shards[1].remove(a), Some("a"));
+    assert!(!router.contains(a));
+    assert!(shards[1].is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "belongs to shard 1, not shard 0")]
+fn thread_local_arena_rejects_other_shards_indices() {
+    use generational_arena::ThreadLocalArena;
+
+    let mut shards = ThreadLocalArena::shards(2);
+    let idx = shards[1].insert(1);
+    shards[0].remove(idx);
+}
+
+#[test]
+fn find_visits_elements_in_slot_order() {
+    let mut arena = Arena::<u32>::new();
+    let a = arena.insert(3);
+    let b = arena.insert(4);
+    let c = arena.insert(6);
+    assert_eq!(arena.find(|n| n % 2 == 0), Some((b, &4)));
+    assert_eq!(arena.find_map(|n| n.checked_sub(5)), Some((c, 1)));
+
+    arena.remove(b);
+    assert_eq!(arena.find(|n| n % 2 == 0), Some((c, &6)));
+    assert_eq!(arena.find(|n| *n > 10), None);
+    assert_eq!(arena.find_map(|n| Some(*n)), Some((a, 3)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn value_index_tracks_shared_keys() {
+    use generational_arena::{ObservedArena, ValueIndex};
+
+    let mut arena = ObservedArena::new(ValueIndex::new(|s: &(&str, u32)| s.0));
+    let a1 = arena.insert(("a", 1));
+    let b = arena.insert(("b", 2));
+    let a2 = arena.insert(("a", 3));
+    assert_eq!(arena.hooks().len(), 2);
+    assert_eq!(arena.hooks().get("a"), Some(a2));
+
+    // Removing an element whose key was taken over leaves the entry alone.
+    arena.remove(a1);
+    assert_eq!(arena.hooks().get("a"), Some(a2));
+
+    arena.retain(|_, s| s.1 != 3);
+    assert_eq!(arena.hooks().get("a"), None);
+    assert_eq!(arena.hooks().get("b"), Some(b));
+
+    arena.clear();
+    assert!(arena.hooks().is_empty());
+}
+
+#[test]
+fn chunks_split_at_free_slots_and_size() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..9).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[5]);
+    arena.remove(indices[6]);
+
+    let chunks: Vec<_> = arena.iter_chunks(2).collect();
+    let starts: Vec<_> = chunks.iter().map(|c| c.start_slot()).collect();
+    assert_eq!(starts, [1, 3, 7]);
+    assert_eq!(chunks[1].iter().collect::<Vec<_>>(), [(indices[3], &3), (indices[4], &4)]);
+
+    for mut chunk in arena.iter_chunks_mut(8) {
+        for (idx, value) in chunk.iter_mut() {
+            assert_eq!(idx.into_raw_parts().0, *value);
+            *value += 100;
+        }
+    }
+    let lens: Vec<_> = arena.iter_chunks_mut(8).map(|c| c.len()).collect();
+    assert_eq!(lens, [4, 2]);
+    assert_eq!(arena[indices[8]], 108);
+}
+
+#[test]
+fn indices_order_by_slot_then_generation() {
+    use generational_arena::Index;
+
+    let a = Index::from_raw_parts(1, 7);
+    let b = Index::from_raw_parts(2, 0);
+    assert!(a < b);
+    assert!(Index::from_raw_parts(2, 1) > b);
+    assert!(Index::min_for_slot(2) <= b && b <= Index::max_for_slot(2));
+    assert!(Index::max_for_slot(1) < Index::min_for_slot(2));
+}
+
+#[test]
+fn swap_remove_keeps_elements_packed() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..5).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[3]);
+
+    // The highest occupied slot is 4, which moves into slot 1.
+    let (value, moved) = arena.swap_remove(indices[1]).unwrap();
+    assert_eq!(value, 1);
+    let (from, to) = moved.unwrap();
+    assert_eq!(from, indices[4]);
+    assert_eq!(to.into_raw_parts().0, 1);
+    assert_ne!(to, indices[1]);
+    assert_eq!(arena[to], 4);
+    assert_eq!(arena.get(indices[1]), None);
+    assert_eq!(arena.get(indices[4]), None);
+    assert_eq!(arena.swap_remove(indices[1]), None);
+    assert!(arena.validate().is_ok());
+
+    let slots: Vec<_> = arena.iter().map(|(i, _)| i.into_raw_parts().0).collect();
+    assert_eq!(slots, [0, 1, 2]);
+}
+
+#[test]
+fn churn_at_far_end_keeps_iteration_and_swap_remove_right() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..64).map(|n| arena.insert(n)).collect();
+    for &idx in &indices[1..63] {
+        arena.remove(idx);
+    }
+
+    let mut top = indices[63];
+    for _ in 0..8 {
+        let value = arena.remove(top).unwrap();
+        top = arena.insert(value);
+        assert!(arena.validate().is_ok());
+    }
+    assert_eq!(top.into_raw_parts().0, 63);
+    assert_eq!(arena.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [0, 63]);
+    assert_eq!(arena.iter_mut().map(|(_, v)| *v).collect::<Vec<_>>(), [0, 63]);
+
+    // Slot 63 is the highest occupied slot even after the gap below it.
+    let (value, moved) = arena.swap_remove(indices[0]).unwrap();
+    assert_eq!(value, 0);
+    let (from, to) = moved.unwrap();
+    assert_eq!(from, top);
+    assert_eq!(to.into_raw_parts().0, 0);
+    assert_eq!(arena[to], 63);
+    assert!(arena.validate().is_ok());
+}
+
+#[test]
+fn removing_in_any_order_keeps_iteration_ends_exact() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..1000).map(|n| arena.insert(n)).collect();
+    let mut live: Vec<_> = (0..1000).collect();
+
+    // 7919 is prime, so this visits every slot once, scattered.
+    for k in 0..999 {
+        let slot = k * 7919 % 1000;
+        arena.remove(indices[slot]);
+        live.retain(|&n| n != slot);
+        if k % 97 == 0 || live.len() < 4 {
+            assert!(arena.validate().is_ok());
+            assert_eq!(arena.iter().next().map(|(_, n)| *n), live.first().copied());
+            assert_eq!(arena.iter().next_back().map(|(_, n)| *n), live.last().copied());
+        }
+    }
+
+    // `validate` checks that iteration is bounded by exactly the one slot
+    // left, so the freed slots on either side of it are skipped.
+    assert!(arena.validate().is_ok());
+    let last = live[0];
+    let mut iter = arena.iter();
+    assert_eq!(iter.next_back(), Some((indices[last], &last)));
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn index_of_ref_survives_inserts_within_capacity() {
+    let mut arena = Arena::with_capacity(8);
+    let first = arena.insert(String::from("first"));
+    let addr = &arena[first] as *const String;
+
+    let others: Vec<_> = (0..7).map(|n| arena.insert(n.to_string())).collect();
+    arena.remove(others[2]);
+    assert_eq!(&arena[first] as *const String, addr);
+
+    for (idx, value) in arena.iter() {
+        assert_eq!(arena.index_of_ref(value), Some(idx));
+    }
+    assert_eq!(arena.index_of_ref(&String::from("first")), None);
+
+    // A `&T` to an element of a different arena is not mistaken for one.
+    let mut other = Arena::new();
+    let idx = other.insert(String::from("first"));
+    assert_eq!(arena.index_of_ref(&other[idx]), None);
+}
+
+#[test]
+fn remove_reusable_keeps_the_generation() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let generation = arena.generation();
+
+    assert_eq!(arena.remove_reusable(a), Some(1));
+    assert_eq!(arena.remove_reusable(a), None);
+    assert_eq!(arena.generation(), generation);
+    assert_eq!(arena.removals(), 1);
+    assert_eq!(arena[b], 2);
+    assert!(arena.validate().is_ok());
+
+    // An ordinary removal still protects against stale indices.
+    arena.remove(b);
+    let c = arena.insert(3);
+    assert_eq!(arena.get(b), None);
+    assert_eq!(arena[c], 3);
+}
+
+#[test]
+fn get2_mut_iter_skips_missing_and_same_slot_pairs() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(10);
+    let stale = arena.insert(100);
+    arena.remove(stale);
+    let c = arena.insert(100);
+    assert_eq!(c.into_raw_parts().0, stale.into_raw_parts().0);
+
+    let mut pairs = arena.get2_mut_iter(vec![(a, stale), (c, stale), (b, b), (a, c), (c, b)]);
+    let mut seen = vec![];
+    while let Some(((i, x), (j, y))) = pairs.next_pair() {
+        *y += *x;
+        seen.push((i, j));
+    }
+
+    assert_eq!(seen, [(a, c), (c, b)]);
+    assert_eq!((arena[a], arena[b], arena[c]), (1, 111, 101));
+}
+
+#[test]
+fn try_insert_grow_grows_like_insert() {
+    let mut grown = Arena::with_capacity(3);
+    let mut inserted = Arena::with_capacity(3);
+    for n in 0..10 {
+        let a = grown.try_insert_grow(n).unwrap();
+        let b = inserted.insert(n);
+        assert_eq!(a, b);
+        assert_eq!(grown.capacity(), inserted.capacity());
+    }
+
+    let mut empty = Arena::new();
+    let idx = empty.try_insert_grow("first").unwrap();
+    assert_eq!(empty[idx], "first");
+    assert!(empty.validate().is_ok());
+}
+
+#[test]
+fn retain_advances_the_generation_once() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    let generation = arena.generation();
+
+    arena.retain(|_, n| *n % 3 == 0);
+    assert_eq!(arena.generation(), generation + 1);
+    assert_eq!(arena.removals(), 4);
+    assert!(arena.validate().is_ok());
+
+    // Nothing removed, nothing advanced.
+    arena.retain(|_, _| true);
+    assert_eq!(arena.generation(), generation + 1);
+
+    // None of the new elements can be mistaken for a removed one.
+    let new: Vec<_> = (0..4).map(|n| arena.insert(n)).collect();
+    assert!(new.iter().all(|idx| !indices.contains(idx)));
+}
+
+#[test]
+fn unknown_gen_methods_take_slots_or_usizes() {
+    use generational_arena::Slot;
+
+    let mut arena = Arena::new();
+    let a = arena.insert('a');
+    let b = arena.insert('b');
+
+    assert_eq!(a.slot(), Slot::new(0));
+    assert_eq!(usize::from(b.slot()), 1);
+    assert_eq!(arena.get_unknown_gen(b.slot()), arena.get_unknown_gen(1));
+    assert!(arena.contains_unknown_gen(a.slot()));
+    assert_eq!(arena.slot_generation(b.slot()), Some(0));
+
+    let slots = [b.slot(), Slot::new(5)];
+    let found: Vec<_> = arena.get_unknown_gen_many(&slots).collect();
+    assert_eq!(found, [Some((&'b', b)), None]);
+
+    let (x, y) = arena.get2_unknown_gen_mut(a.slot(), 1);
+    std::mem::swap(x.unwrap().0, y.unwrap().0);
+    assert_eq!(arena.remove_unknown_gen(a.slot()), Some(('b', a)));
+}
+
+#[test]
+fn extend_reserves_for_the_size_hint() {
+    let mut arena = Arena::with_capacity(2);
+    arena.insert(0);
+    arena.extend(1..100);
+    assert_eq!(arena.len(), 100);
+    assert_eq!(arena.capacity(), 100);
+
+    // A lower bound of zero reserves nothing up front.
+    let mut arena = Arena::with_capacity(2);
+    arena.extend((0..5).filter(|_| true));
+    assert_eq!(arena.len(), 5);
+    assert_eq!(arena.capacity(), 8);
+}
+
+#[test]
+fn into_iter_indexed_keeps_indices() {
+    let mut arena = Arena::new();
+    let a = arena.insert(String::from("a"));
+    let b = arena.insert(String::from("b"));
+    let c = arena.insert(String::from("c"));
+    arena.remove(b);
+
+    let mut iter = arena.into_iter_indexed();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next(), Some((a, String::from("a"))));
+    assert_eq!(iter.len(), 1);
+    assert_eq!(iter.next_back(), Some((c, String::from("c"))));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[cfg(feature = "slab")]
+#[test]
+fn slab_conversions_keep_slots() {
+    use generational_arena::Index;
+    use slab::Slab;
+
+    let mut slab = Slab::with_capacity(8);
+    let keys: Vec<_> = (0..5).map(|n| slab.insert(n * 10)).collect();
+    slab.remove(keys[1]);
+    slab.remove(keys[4]);
+
+    let mut arena = Arena::from(slab);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.capacity(), 8);
+    assert!(arena.validate().is_ok());
+    for &key in &[keys[0], keys[2], keys[3]] {
+        assert_eq!(arena[Index::from_slab_key(key)], key * 10);
+    }
+    assert_eq!(arena.get(Index::from_slab_key(keys[1])), None);
+
+    // The free slots are reused, with newer generations.
+    let idx = arena.insert(99);
+    assert_ne!(idx, Index::from_slab_key(idx.into_raw_parts().0));
+
+    let slab = Slab::from(arena);
+    assert_eq!(slab.len(), 4);
+    assert_eq!(slab[keys[3]], 30);
+    assert_eq!(slab[idx.into_raw_parts().0], 99);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn replace_with_removes_the_element_if_f_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.replace_with(a, |_| panic!("oops"));
+    }));
+    assert!(result.is_err());
+    assert_eq!(arena.get(a), None);
+    assert_eq!(arena.len(), 1);
+    assert!(arena.validate().is_ok());
+
+    // The slot is reusable, under a new generation.
+    let c = arena.replace_with(a, |old| old.unwrap_or(3));
+    assert_ne!(c, a);
+    assert_eq!(arena[c], 3);
+    assert_eq!(arena.replace_with(b, |old| old.unwrap() * 10), b);
+    assert_eq!(arena[b], 20);
+}
+
+#[test]
+fn soa_arena_rejects_stale_indices() {
+    use generational_arena::SoaArena;
+
+    let mut arena = SoaArena::with_capacity(2);
+    let a = arena.insert("a");
+    let b = arena.insert("b");
+    assert_eq!(arena.capacity(), 0);
+
+    assert_eq!(arena.remove(a), Some("a"));
+    assert_eq!(arena.remove(a), None);
+    assert_eq!(arena.capacity(), 1);
+    let c = arena.insert("c");
+    assert_eq!(c.into_raw_parts().0, a.into_raw_parts().0);
+    assert_eq!(arena.get(a), None);
+    assert_eq!((arena[b], arena[c]), ("b", "c"));
+
+    arena.clear();
+    assert!(arena.is_empty());
+    let d = arena.insert("d");
+    assert!(arena.get(b).is_none() && arena.get(c).is_none());
+    assert_eq!(arena[d], "d");
+}
+
+#[test]
+#[should_panic(expected = "slot 0 is free")]
+fn soa_arena_index_panics_with_slot_state() {
+    use generational_arena::SoaArena;
+
+    let mut arena = SoaArena::new();
+    let a = arena.insert(1);
+    arena.remove(a);
+    let _ = arena[a];
+}
+
+#[test]
+fn indices_and_retain_indices() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[2]);
+
+    let mut iter = arena.indices();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next_back(), Some(indices[5]));
+    assert_eq!(iter.nth(2), Some(indices[3]));
+    assert_eq!(iter.next(), Some(indices[4]));
+    assert_eq!(iter.next(), None);
+
+    let doomed = [indices[0], indices[3]];
+    arena.retain_indices(|idx| !doomed.contains(&idx));
+    let left: Vec<_> = arena.indices().collect();
+    assert_eq!(left, [indices[1], indices[4], indices[5]]);
+}
+
+#[test]
+fn arena_and_index_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Arena<String>>();
+    assert_send_sync::<generational_arena::Index>();
+    fn assert_send<T: Send>() {}
+    assert_send::<generational_arena::ArenaCell<String>>();
+}
+
+#[test]
+fn arena_cell_borrows_are_checked() {
+    use generational_arena::ArenaCell;
+
+    let cell = ArenaCell::new();
+    let a = cell.insert(1);
+    let b = cell.insert(2);
+
+    {
+        let x = cell.borrow_get(a).unwrap();
+        let y = cell.borrow_get(b).unwrap();
+        assert_eq!(*x + *y, 3);
+        assert!(cell.try_borrow_get_mut(b).is_err());
+    }
+    {
+        let mut x = cell.borrow_get_mut(a).unwrap();
+        *x += 10;
+        assert!(cell.try_borrow_get(b).is_err());
+    }
+
+    assert_eq!(cell.remove(b), Some(2));
+    assert!(cell.borrow_get(b).is_none());
+    assert!(cell.try_borrow_get_mut(b).unwrap().is_none());
+    assert_eq!(cell.into_inner()[a], 11);
+}
+
+#[test]
+fn try_get2_mut_handles_aliasing() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+
+    if let (Some(x), None) = arena.try_get2_mut(a, a) {
+        *x += 10;
+    } else {
+        panic!("aliased indices must give only the first reference");
+    }
+    assert_eq!(arena[a], 11);
+    assert_eq!(arena.try_get2_mut(b, a), (Some(&mut 2), Some(&mut 11)));
+
+    // Same slot, different generations, is not aliasing.
+    arena.remove(a);
+    let c = arena.insert(3);
+    assert_eq!(arena.try_get2_mut(a, c), (None, Some(&mut 3)));
+    assert_eq!(arena.try_get2_mut(a, a), (None, None));
+}
+
+#[test]
+fn from_vec_and_array_place_elements_in_order() {
+    use generational_arena::Index;
+
+    let mut arena = Arena::from(vec!['a', 'b', 'c']);
+    let indices: Vec<_> = arena.indices().collect();
+    assert_eq!(
+        indices,
+        (0..3).map(|i| Index::from_raw_parts(i, 0)).collect::<Vec<_>>()
+    );
+    assert_eq!(arena.validate(), Ok(()));
+
+    let d = arena.insert('d');
+    assert_eq!(d.into_raw_parts(), (3, 0));
+    arena.remove(indices[1]);
+    let e = arena.insert('e');
+    assert_eq!(e.into_raw_parts(), (1, 1));
+    assert_eq!(arena.get(Index::from_raw_parts(1, 0)), None);
+
+    let arena = Arena::from([1, 2]);
+    assert_eq!(arena[Index::from_raw_parts(1, 0)], 2);
+    assert!(Arena::<u8>::from(vec![]).is_empty());
+}
+
+#[test]
+fn try_from_raw_parts_validates_against_the_arena() {
+    use generational_arena::Index;
+
+    let mut arena = Arena::with_capacity(4);
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(a);
+
+    // Stale but well-formed indices are accepted.
+    assert_eq!(Index::try_from_raw_parts(0, 0, &arena), Some(a));
+    assert_eq!(Index::try_from_raw_parts(1, 0, &arena), Some(b));
+    assert!(Index::try_from_raw_parts(0, 1, &arena).is_some());
+    assert_eq!(Index::try_from_raw_parts(0, 2, &arena), None);
+    assert_eq!(Index::try_from_raw_parts(1, 1, &arena), None);
+
+    // Slots past the last materialized one are rejected, even within capacity.
+    assert_eq!(Index::try_from_raw_parts(2, 0, &arena), None);
+
+    let idx = Index::try_from_raw_parts(1, 0, &arena).unwrap();
+    assert_eq!(arena[idx], 2);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn keyed_arena_keeps_keys_and_indices_in_sync() {
+    use generational_arena::KeyedArena;
+
+    let mut arena = KeyedArena::with_capacity(2);
+    let a = arena.insert(String::from("a"), 1).unwrap();
+    let b = arena.insert(String::from("b"), 2).unwrap();
+    assert!(arena.insert(String::from("a"), 3).is_err());
+    assert_eq!(arena.len(), 2);
+
+    *arena.get_by_key_mut("b").unwrap() += 10;
+    assert_eq!(arena[b], 12);
+    assert_eq!(arena.key_of(a).map(String::as_str), Some("a"));
+
+    assert_eq!(arena.remove_by_key("a"), Some((a, 1)));
+    assert!(!arena.contains(a));
+    assert_eq!(arena.remove(a), None);
+
+    // The key can be reused once its element is gone, with a new index.
+    let a2 = arena.insert(String::from("a"), 4).unwrap();
+    assert_ne!(a2, a);
+    assert_eq!(arena.get(a), None);
+    assert_eq!(arena.index_of_key("a"), Some(a2));
+
+    assert_eq!(arena.remove(b), Some((String::from("b"), 12)));
+    assert!(!arena.contains_key("b"));
+    let keys: Vec<_> = arena.iter().map(|(_, k, v)| (k.clone(), *v)).collect();
+    assert_eq!(keys, [(String::from("a"), 4)]);
+}
+
+#[test]
+#[cfg(feature = "futures")]
+fn snapshot_stream_yields_every_element_in_chunks() {
+    use futures_core::stream::{FusedStream, Stream};
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..7).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[2]);
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut stream = arena.snapshot_stream(4);
+    let mut chunks = vec![];
+    while let Poll::Ready(Some(chunk)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        chunks.push(chunk);
+    }
+    assert!(stream.is_terminated());
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].len(), 4);
+    let all: Vec<_> = chunks.into_iter().flatten().collect();
+    assert_eq!(all, arena.iter().map(|(i, v)| (i, *v)).collect::<Vec<_>>());
+}
+
+#[test]
+fn take_moves_elements_and_keeps_capacity() {
+    let mut arena = Arena::builder().capacity(4).fifo_reuse().build();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(a);
+
+    let mut taken = arena.take();
+    assert!(arena.is_empty());
+    assert_eq!(arena.capacity(), 4);
+    assert_eq!(arena.validate(), Ok(()));
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[b], 2);
+    assert_eq!(taken.get(a), None);
+    assert_eq!(taken.validate(), Ok(()));
+
+    // New elements get indices that no taken element has.
+    let c = arena.insert(3);
+    let d = arena.insert(4);
+    assert_ne!(c, b);
+    assert_ne!(d, b);
+    assert_eq!(taken.remove(b), Some(2));
+
+    let mut empty: Arena<u8> = Arena::new();
+    assert!(empty.take().is_empty());
+}
+
+#[test]
+fn raw_index_layout_is_fixed() {
+    use generational_arena::{Index, RawIndex};
+    use std::mem;
+
+    assert_eq!(RawIndex::SIZE_BYTES, 8);
+    assert_eq!(mem::size_of::<RawIndex>(), RawIndex::SIZE_BYTES);
+    assert_eq!(mem::align_of::<RawIndex>(), mem::align_of::<u64>());
+    assert_eq!(mem::size_of::<Index>(), Index::SIZE_BYTES);
+
+    // Slot in the low half, generation in the high half, little-endian.
+    let raw = RawIndex::from_bits(0x0000_0002_0000_0001);
+    assert_eq!((raw.slot(), raw.generation()), (1, 2));
+    assert_eq!(raw.to_le_bytes(), [1, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(RawIndex::from_le_bytes([1, 0, 0, 0, 2, 0, 0, 0]), raw);
+}
+
+#[test]
+fn arena_pool_reuses_released_arenas() {
+    use generational_arena::ArenaPool;
+
+    let mut pool = ArenaPool::new();
+    let mut a = pool.acquire_with_capacity(16);
+    assert_eq!(a.capacity(), 16);
+    let idx = a.insert("a");
+    pool.release(a);
+    assert_eq!(pool.idle(), 1);
+
+    let mut b = pool.acquire_with_capacity(4);
+    assert_eq!(pool.idle(), 0);
+    assert!(b.is_empty());
+    assert_eq!(b.capacity(), 16);
+    assert_eq!(b.get(idx), None);
+    let fresh = b.insert("b");
+    assert_ne!(fresh, idx);
+    assert_eq!(b.validate(), Ok(()));
+
+    // An empty pool makes new arenas.
+    let c = pool.acquire();
+    assert!(c.is_empty());
+    pool.release(b);
+    pool.release(c);
+    assert_eq!(pool.idle(), 2);
+    pool.clear();
+    assert_eq!(pool.idle(), 0);
+}
+
+#[test]
+fn option_index_round_trips_options() {
+    use generational_arena::{Index, OptionIndex};
+
+    let idx = Index::from_raw_parts(3, 4);
+    let mut some = OptionIndex::from(idx);
+    assert!(some.is_some());
+    assert_eq!(some.get(), Some(idx));
+    assert_eq!(Option::<Index>::from(some), Some(idx));
+    assert_eq!(format!("{:?}", some), format!("{:?}", Some(idx)));
+
+    assert_eq!(some.take(), Some(idx));
+    assert!(some.is_none());
+    assert_eq!(some, OptionIndex::default());
+    assert_eq!(OptionIndex::from(None), OptionIndex::NONE);
+    assert_eq!(OptionIndex::NONE.get(), None);
+    assert_eq!(
+        std::mem::size_of::<OptionIndex>(),
+        std::mem::size_of::<Index>()
+    );
+}
+
+#[test]
+fn arena_tree_cascades_and_orphans() {
+    use generational_arena::ArenaTree;
+
+    let mut tree = ArenaTree::with_capacity(8);
+    let root = tree.insert(0);
+    let a = tree.insert_child(root, 1).unwrap();
+    let b = tree.insert_child(root, 2).unwrap();
+    let a1 = tree.insert_child(a, 3).unwrap();
+    let a1x = tree.insert_child(a1, 4).unwrap();
+    let b1 = tree.insert_child(b, 5).unwrap();
+    assert_eq!(tree.len(), 6);
+
+    // Cascading removal reaches grandchildren.
+    assert_eq!(tree.remove(a), Some(1));
+    assert_eq!(tree.len(), 3);
+    assert!(!tree.contains(a1) && !tree.contains(a1x));
+    assert_eq!(tree.children(root).collect::<Vec<_>>(), [b]);
+    assert_eq!(tree.remove(a), None);
+
+    // New elements may reuse freed slots, but never match stale indices.
+    let c = tree.insert_child(root, 6).unwrap();
+    assert_eq!(tree.get(a1), None);
+    assert_eq!(tree.children(root).collect::<Vec<_>>(), [b, c]);
+
+    // Orphaning turns children into roots.
+    assert_eq!(tree.remove_orphaning(b), Some(2));
+    assert_eq!(tree.parent(b1), None);
+    assert_eq!(tree[b1], 5);
+    assert_eq!(tree.children(b).count(), 0);
+    assert_eq!(tree.insert_child(b, 7), None);
+
+    tree[c] += 10;
+    assert_eq!(tree.remove(root), Some(0));
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree.get(c), None);
+}
+
+#[test]
+fn apply_batch() {
+    use generational_arena::ArenaOp;
+
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+
+    // Lenient batches skip operations on missing elements.
+    let inserted = arena.apply_batch(vec![
+        ArenaOp::Remove(a),
+        ArenaOp::Update(a, 10),
+        ArenaOp::Insert(3),
+        ArenaOp::Update(b, 20),
+        ArenaOp::Remove(a),
+    ]);
+    assert_eq!(inserted.len(), 1);
+    assert!(!arena.contains(a));
+    assert_eq!(arena[b], 20);
+    assert_eq!(arena[inserted[0]], 3);
+    assert!(arena.validate().is_ok());
+
+    // Checked batches apply nothing if any operation is invalid.
+    let batch = vec![
+        ArenaOp::Update(b, 21),
+        ArenaOp::Insert(4),
+        ArenaOp::Remove(b),
+        ArenaOp::Remove(a),
+        ArenaOp::Update(b, 22),
+    ];
+    let err = arena.try_apply_batch(batch.clone()).unwrap_err();
+    assert_eq!(err.position(), 3);
+    assert_eq!(err.index(), a);
+    assert_eq!(err.into_ops(), batch);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena[b], 20);
+
+    let mut use_after_remove = batch[..3].to_vec();
+    use_after_remove.push(batch[4].clone());
+    let err = arena.try_apply_batch(use_after_remove).unwrap_err();
+    assert_eq!(err.position(), 3);
+    assert_eq!(err.index(), b);
+
+    let inserted = arena.try_apply_batch(batch[..3].to_vec()).unwrap();
+    assert_eq!(inserted.len(), 1);
+    assert_eq!(arena[inserted[0]], 4);
+    assert!(!arena.contains(b));
+    assert!(arena.validate().is_ok());
+}
+
+#[test]
+fn stack_reuse() {
+    use generational_arena::ReusePolicy;
+
+    let mut arena = Arena::builder().capacity(4).stack_reuse().build();
+    let indices: Vec<_> = (0..4).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[2]);
+    arena.remove(indices[0]);
+    arena.remove(indices[3]);
+    assert!(arena.validate().is_ok());
+
+    // Slots are reused most recently freed first, like `ReusePolicy::Lifo`.
+    let slot = |idx: generational_arena::Index| idx.into_raw_parts().0;
+    let reused: Vec<_> = (0..3).map(|n| slot(arena.insert(n))).collect();
+    assert_eq!(reused, [3, 0, 2]);
+    assert!(arena.validate().is_ok());
+
+    // Rebuilt free slots are reused lowest first.
+    let mut arena: Arena<_> = Arena::builder().reuse(ReusePolicy::Stack).build();
+    let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[4]);
+    arena.remove(indices[1]);
+    let mut other = arena.split_off_slots(3);
+    assert!(arena.validate().is_ok());
+    assert!(other.validate().is_ok());
+    assert_eq!(slot(other.insert(6)), 0);
+    assert_eq!(slot(other.insert(7)), 1);
+    assert_eq!(slot(other.insert(8)), 2);
+    assert_eq!(slot(other.insert(9)), 4);
+
+    arena.clear();
+    assert!(arena.validate().is_ok());
+    assert_eq!(slot(arena.insert(10)), 0);
+}
+
+#[test]
+fn zip_indices_of() {
+    let mut names = Arena::new();
+    let mut ages = Arena::new();
+    let indices: Vec<_> = (0..5).map(|n| names.insert(n.to_string())).collect();
+    for n in 0..4 {
+        ages.insert(n * 10);
+    }
+    names.remove(indices[1]);
+    let stale = ages.iter().nth(3).unwrap().0;
+    ages.remove(stale);
+
+    let mut joint = names.iter_mut().zip_indices_of(&mut ages);
+    let (idx, name, age) = joint.next_back().unwrap();
+    assert_eq!((idx, name.as_str(), *age), (indices[2], "2", 20));
+    *age += 1;
+    let rest: Vec<_> = joint.map(|(idx, name, age)| (idx, name.clone(), *age)).collect();
+    assert_eq!(rest, [(indices[0], "0".to_string(), 0)]);
+    assert_eq!(ages.iter().map(|(_, a)| *a).collect::<Vec<_>>(), [0, 10, 21]);
+
+    // A partly consumed iterator only pairs up the slots it has left.
+    let mut iter = names.iter_mut();
+    iter.next();
+    let slots: Vec<_> = iter
+        .zip_indices_of(&mut ages)
+        .map(|(idx, _, _)| idx.into_raw_parts().0)
+        .collect();
+    assert_eq!(slots, [2]);
+}
+
+#[test]
+fn growth_is_reported() {
+    use generational_arena::{ArenaHooks, Index, ObservedArena};
+
+    #[derive(Default)]
+    struct GrowthLog(Vec<(usize, usize)>);
+
+    impl ArenaHooks<u32> for GrowthLog {
+        fn on_insert(&mut self, _i: Index, _value: &u32) {}
+        fn on_remove(&mut self, _i: Index, _value: &u32) {}
+        fn on_grow(&mut self, old_capacity: usize, new_capacity: usize) {
+            self.0.push((old_capacity, new_capacity));
+        }
+    }
+
+    let mut arena = ObservedArena::with_capacity(2, GrowthLog::default());
+    let a = arena.insert(1);
+    assert!(!arena.is_full());
+    arena.insert(2);
+    assert!(arena.is_full());
+    assert!(arena.hooks().0.is_empty());
+
+    arena.insert(3);
+    assert_eq!(arena.hooks().0, [(2, 4)]);
+    arena.remove(a);
+    arena.insert(4);
+    assert_eq!(arena.hooks().0.len(), 1);
+
+    // Reserving ahead of time reports growth too, and then inserting
+    // doesn't.
+    arena.reserve(10);
+    assert_eq!(arena.hooks().0, [(2, 4), (4, 13)]);
+    for n in 0..10 {
+        arena.insert(n);
+    }
+    assert!(arena.is_full());
+    assert_eq!(arena.hooks().0.len(), 2);
+}
+
+#[test]
+fn partition() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..6).map(|n| arena.insert(n)).collect();
+    arena.remove(indices[4]);
+
+    let (low, high) = arena.partition(|_, n| *n < 3);
+    assert_eq!(low.len(), 3);
+    assert_eq!(high.len(), 2);
+    assert!(low.validate().is_ok());
+    assert!(high.validate().is_ok());
+    for (i, &idx) in indices.iter().enumerate() {
+        assert_eq!(low.get(idx), if i < 3 { Some(&i) } else { None });
+        assert_eq!(high.get(idx), if i >= 3 && i != 4 { Some(&i) } else { None });
+    }
+
+    let (none, all) = high.partition(|_, _| false);
+    assert!(none.is_empty());
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[indices[5]], 5);
+}
+
+#[test]
+fn debug_with() {
+    // Deliberately not `Debug`.
+    struct Opaque(u32);
+
+    let mut arena = Arena::new();
+    let a = arena.insert(Opaque(1));
+    let b = arena.insert(Opaque(2));
+    arena.remove(a);
+
+    let dump = format!("{:?}", arena.debug_with(|v, f| write!(f, "#{}", v.0)));
+    assert_eq!(
+        dump,
+        format!(
+            "Arena {{ len: 1, generation: 1, slots: [Free {{ slot: 0, next_free: None }}, \
+             Occupied {{ index: {:?}, value: #2 }}] }}",
+            b
+        )
+    );
+
+    // The formatter's flags carry through to the value.
+    let as_tuple =
+        |v: &Opaque, f: &mut std::fmt::Formatter| f.debug_tuple("Opaque").field(&v.0).finish();
+    let pretty = format!("{:#?}", arena.debug_with(as_tuple));
+    assert!(pretty.contains("value: Opaque(\n"));
+}
+
+#[test]
+#[should_panic(expected = "growth percentage must be more than 100")]
+fn growth_percent_of_100_is_rejected() {
+    let _ = Arena::<u32>::builder().growth_percent(100);
+}
+
+#[test]
+fn small_growth_percent_still_grows_by_several_slots() {
+    let mut arena = Arena::builder().capacity(2).growth_percent(101).build();
+    arena.extend(0..3);
+    assert_eq!(arena.capacity(), 4);
+    arena.extend(0..2);
+    assert_eq!(arena.capacity(), 8);
+
+    let mut arena = Arena::builder().capacity(100).growth_percent(101).build();
+    arena.extend(0..101);
+    assert_eq!(arena.capacity(), 104);
+
+    // Large arenas grow by the percentage as usual.
+    let mut arena = Arena::builder().capacity(1000).growth_percent(101).build();
+    arena.extend(0..1001);
+    assert_eq!(arena.capacity(), 1010);
+}